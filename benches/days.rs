@@ -0,0 +1,27 @@
+//! Parse, part 1 and part 2 of every day against the checked-in inputs.
+//!
+//! `cargo bench -- day16/` benchmarks a single day, `cargo bench -- part_2` a single part everywhere.
+
+use std::{fs, hint::black_box};
+
+use aoc2024::registry;
+use criterion::{criterion_group, criterion_main, Criterion};
+
+fn days(c: &mut Criterion) {
+    for day in registry::days() {
+        let n = day.number;
+        let data = fs::read_to_string(day.input_path()).expect("Can't open file");
+        let puzzle = day.parse(&data).expect("Can't parse input");
+
+        let mut group = c.benchmark_group(format!("day{n}"));
+        // Some days take seconds per iteration, the default 100 samples would take forever
+        group.sample_size(10);
+        group.bench_function("parse", |b| b.iter(|| day.parse(black_box(&data))));
+        group.bench_function("part_1", |b| b.iter(|| puzzle.part_1()));
+        group.bench_function("part_2", |b| b.iter(|| puzzle.part_2()));
+        group.finish();
+    }
+}
+
+criterion_group!(benches, days);
+criterion_main!(benches);
@@ -1,27 +1,60 @@
-use std::fs;
+//! Day XX
 
-pub fn solve_1(input: &str) -> u32 {
-    let data = fs::read_to_string(input).expect("Can't open file");
-    0
+use std::{fmt::Display, fs};
+
+use crate::error::Result;
+use crate::solution::Solution;
+use crate::util::random::Rng;
+
+pub struct Input {}
+
+pub fn parse(_data: &str) -> Result<Input> {
+    Ok(Input {})
+}
+
+pub fn part_1(_input: &Input) -> Result<u32> {
+    Ok(0)
+}
+
+pub fn part_2(_input: &Input) -> Result<u32> {
+    Ok(0)
+}
+
+/// A random puzzle text, `size` being ...
+pub fn generate(_rng: &mut Rng, _size: usize) -> String {
+    String::new()
 }
 
-pub fn solve_2(input: &str) -> u32 {
-    let data = fs::read_to_string(input).expect("Can't open file");
-    0
+pub fn solve_1(input: &str) -> Result<u32> {
+    let data = fs::read_to_string(input)?;
+    part_1(&parse(&data)?)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    #[test]
-    fn part1() {
-        let result = solve_1("src/dayXX/test1.txt");
-        assert_eq!(result, 0);
+pub fn solve_2(input: &str) -> Result<u32> {
+    let data = fs::read_to_string(input)?;
+    part_2(&parse(&data)?)
+}
+
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    const DAY: u8 = XX;
+
+    type Input = Input;
+
+    fn parse(data: &str) -> Result<Self::Input> {
+        parse(data)
+    }
+
+    fn part_1(input: &Self::Input) -> Result<impl Display> {
+        part_1(input)
+    }
+
+    fn part_2(input: &Self::Input) -> Result<impl Display> {
+        part_2(input)
     }
 
-    #[test]
-    fn part2() {
-        let result = solve_2("src/dayXX/test2.txt");
-        assert_eq!(result, 0);
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        generate(rng, size)
     }
 }
@@ -0,0 +1,151 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::error::Result;
+use crate::solution::Params;
+use crate::util::input::lines;
+
+/// Recorded answers of one puzzle input, kept next to it as a small TOML file:
+///
+/// ```toml
+/// part1 = "2264607"
+/// part2 = "19457120"
+/// ```
+///
+/// An example whose puzzle text gives other numbers than the real one also has those, as
+/// unquoted [`Params`] of its day:
+///
+/// ```toml
+/// blinks = 6
+/// part1 = "22"
+/// ```
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Answers {
+    pub params: Params,
+    pub part_1: Option<String>,
+    pub part_2: Option<String>,
+}
+
+impl Answers {
+    /// `src/day1/input.txt` has its answers in `src/day1/input.answers.toml`
+    pub fn path_for(input: &Path) -> PathBuf {
+        input.with_extension("answers.toml")
+    }
+
+    /// Answers of the given input file, empty if nothing was recorded yet
+    pub fn load(input: &Path) -> Result<Self> {
+        match fs::read_to_string(Self::path_for(input)) {
+            Ok(text) => Self::parse(&text),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn save(&self, input: &Path) -> io::Result<()> {
+        fs::write(Self::path_for(input), self.to_toml())
+    }
+
+    /// Only the tiny subset of TOML the answer files need: comments, `partN = "..."` and
+    /// `parameter = N`
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut answers = Self::default();
+        for line in lines(text) {
+            let content = line.text.trim();
+            if content.is_empty() || content.starts_with('#') {
+                continue;
+            }
+            let (key, value) = line.split_once("=")?;
+            let value = value.trim();
+            let part = match key.trim() {
+                "part1" => &mut answers.part_1,
+                "part2" => &mut answers.part_2,
+                name => match value.parse() {
+                    Ok(number) => {
+                        answers.params.set(name, number);
+                        continue;
+                    }
+                    Err(_) => return Err(line.error_at(key, format!("unknown key '{name}'"))),
+                },
+            };
+            let Some(quoted) = value
+                .strip_prefix('"')
+                .and_then(|value| value.strip_suffix('"'))
+            else {
+                return Err(line.error_at(value, "expected a quoted string"));
+            };
+            *part = Some(unescape(quoted));
+        }
+        Ok(answers)
+    }
+
+    pub fn to_toml(&self) -> String {
+        let params = self
+            .params
+            .iter()
+            .map(|(name, value)| format!("{name} = {value}\n"));
+        let answers = [("part1", &self.part_1), ("part2", &self.part_2)]
+            .into_iter()
+            .filter_map(|(key, answer)| {
+                answer
+                    .as_ref()
+                    .map(|answer| format!("{key} = \"{}\"\n", escape(answer)))
+            });
+        params.chain(answers).collect()
+    }
+
+    pub fn get(&self, part: u8) -> Option<&str> {
+        match part {
+            1 => self.part_1.as_deref(),
+            2 => self.part_2.as_deref(),
+            _ => None,
+        }
+    }
+
+    pub fn set(&mut self, part: u8, answer: String) {
+        match part {
+            1 => self.part_1 = Some(answer),
+            2 => self.part_2 = Some(answer),
+            _ => {}
+        }
+    }
+}
+
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn unescape(s: &str) -> String {
+    s.replace("\\\"", "\"").replace("\\\\", "\\")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let mut answers = Answers {
+            part_1: Some("2,1,0".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(answers.to_toml(), "part1 = \"2,1,0\"\n");
+        assert_eq!(Answers::parse(&answers.to_toml()).unwrap(), answers);
+
+        answers.params.set("blinks", 6);
+        assert_eq!(answers.to_toml(), "blinks = 6\npart1 = \"2,1,0\"\n");
+        assert_eq!(Answers::parse(&answers.to_toml()).unwrap(), answers);
+    }
+
+    #[test]
+    fn malformed() {
+        let error = Answers::parse("# day 1\npart1 = 12\n").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 2, column 9: expected a quoted string"
+        );
+        let error = Answers::parse("prat1 = \"12\"\n").unwrap_err();
+        assert_eq!(error.to_string(), "line 1, column 1: unknown key 'prat1'");
+    }
+}
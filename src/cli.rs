@@ -0,0 +1,606 @@
+use std::{fmt, ops::RangeInclusive, path::PathBuf, time::Duration};
+
+use aoc2024::util::png::Palette;
+
+use crate::timing::Format;
+
+pub const USAGE: &str = "\
+Usage: aoc2024 [run <DAY|all> [--part <1|2>] [--input <PATH|->] [--jobs <N>] [--timeout <SECONDS>]
+                            [--timings] [--timings-out <PATH>] [--visualize [--delay <MS>]]
+                            [--png <DIR> [--frames <LIST>] [--scale <N>] [--palette <LIST>]]
+                            [--stream] [--explain]]
+       aoc2024 verify [DAY|all] [--part <1|2>] [--jobs <N>] [--timeout <SECONDS>] [--record]
+       aoc2024 generate <DAY> [--size <N>] [--seed <N>]
+       aoc2024 new-day <DAY>
+
+Commands:
+  run <DAY|all>       Solve one day or every day (the default without arguments)
+  verify [DAY|all]    Compare the answers with the ones recorded in src/dayN/input.answers.toml
+  generate <DAY>      Print a random puzzle input for the day
+  new-day <DAY>       Create src/dayN from day_mod_template.rs with empty inputs and register it
+  help                Print this message
+
+Options:
+  -p, --part <1|2>         Only solve the given part
+  -i, --input <PATH>       Read the puzzle input from PATH instead of src/dayN/input.txt,
+                           '-' reads it from stdin (needs a single day)
+  -T, --timings            Print how long parsing and each part took, slowest day first
+  --timings-out <PATH>     Write the timings to PATH as JSON or CSV, depending on its extension
+  -j, --jobs <N>           Solve N days (or parts) at the same time, still reporting them in order
+                           (default: 1)
+  -t, --timeout <SECONDS>  Give up on a day this long after its parsing started (default: no limit)
+  -v, --visualize          Animate the maps of the days that draw them (needs a single day)
+  --delay <MS>             How long each frame of the animation stays on the screen (default: 100)
+  --png <DIR>              Save the frames of the animation as DIR/frame_00001.png and so on
+                           (needs a single day)
+  --frames <LIST>          Only save these frames, e.g. '1-10,250' (default: all of them)
+  --scale <N>              Size of a map cell in pixels (default: 4)
+  --palette <LIST>         Colours of the map symbols, e.g. '#=808080,O=ffcc00'
+  --stream                 Solve the input while it's read a line at a time, showing how far it got
+                           (needs a single day, one of 1, 2 and 22; day 1 still keeps every
+                           number, only not the text)
+  -e, --explain            Print what the solver found out on the way to the answers, best with
+                           --part (needs a single day)
+  --record                 Save the answers as the expected ones instead of comparing
+  --size <N>               How big the generated input is, what that means depends on the day
+                           (default: 100)
+  --seed <N>               Generate the same input again (default: a new one every time)";
+
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    Run(RunArgs),
+    Verify(VerifyArgs),
+    Generate(GenerateArgs),
+    NewDay(u8),
+    Help,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Selection {
+    All,
+    Day(u8),
+}
+
+#[derive(Debug, PartialEq)]
+pub enum InputSource {
+    Path(PathBuf),
+    Stdin,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct RunArgs {
+    pub days: Selection,
+    pub part: Option<u8>,
+    pub input: Option<InputSource>,
+    pub jobs: usize,
+    pub timeout: Option<Duration>,
+    pub timings: bool,
+    pub timings_out: Option<(PathBuf, Format)>,
+    pub visualize: bool,
+    /// Time between the frames of the animation
+    pub delay: Duration,
+    /// Where to save the frames as PNG
+    pub png: Option<PathBuf>,
+    pub frames: Option<Vec<RangeInclusive<usize>>>,
+    pub scale: usize,
+    pub palette: Palette,
+    /// Solve while reading the input instead of parsing it first
+    pub stream: bool,
+    pub explain: bool,
+}
+
+impl RunArgs {
+    pub fn runs_part(&self, part: u8) -> bool {
+        self.part.is_none_or(|p| p == part)
+    }
+}
+
+impl Default for RunArgs {
+    fn default() -> Self {
+        Self {
+            days: Selection::All,
+            part: None,
+            input: None,
+            jobs: 1,
+            timeout: None,
+            timings: false,
+            timings_out: None,
+            visualize: false,
+            delay: Duration::from_millis(100),
+            png: None,
+            frames: None,
+            scale: 4,
+            palette: Palette::default(),
+            stream: false,
+            explain: false,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct VerifyArgs {
+    pub days: Selection,
+    pub part: Option<u8>,
+    pub jobs: usize,
+    pub timeout: Option<Duration>,
+    pub record: bool,
+}
+
+impl VerifyArgs {
+    pub fn runs_part(&self, part: u8) -> bool {
+        self.part.is_none_or(|p| p == part)
+    }
+}
+
+impl Default for VerifyArgs {
+    fn default() -> Self {
+        Self {
+            days: Selection::All,
+            part: None,
+            jobs: 1,
+            timeout: None,
+            record: false,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct GenerateArgs {
+    pub day: u8,
+    pub size: usize,
+    /// Picked from the clock if not given
+    pub seed: Option<u64>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ArgError(String);
+
+impl fmt::Display for ArgError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+fn error<T>(message: impl Into<String>) -> Result<T, ArgError> {
+    Err(ArgError(message.into()))
+}
+
+pub fn parse(mut args: impl Iterator<Item = String>) -> Result<Command, ArgError> {
+    let Some(command) = args.next() else {
+        // No arguments at all: solve everything, like it always did
+        return Ok(Command::Run(RunArgs::default()));
+    };
+
+    match command.as_str() {
+        "run" => parse_run(args).map(Command::Run),
+        "verify" => parse_verify(args).map(Command::Verify),
+        "generate" => parse_generate(args).map(Command::Generate),
+        "new-day" => parse_new_day(args).map(Command::NewDay),
+        "help" | "-h" | "--help" => Ok(Command::Help),
+        other => error(format!("Unknown command '{other}'")),
+    }
+}
+
+fn parse_days(day: &str) -> Result<Selection, ArgError> {
+    match day {
+        "all" => Ok(Selection::All),
+        day => match day.parse::<u8>() {
+            Ok(n) if (1..=25).contains(&n) => Ok(Selection::Day(n)),
+            _ => error(format!("Invalid day '{day}'")),
+        },
+    }
+}
+
+fn parse_part(part: Option<String>) -> Result<Option<u8>, ArgError> {
+    match part.as_deref() {
+        Some("1") => Ok(Some(1)),
+        Some("2") => Ok(Some(2)),
+        Some(part) => error(format!("Invalid part '{part}'")),
+        None => error("Missing value for --part"),
+    }
+}
+
+fn parse_jobs(jobs: Option<String>) -> Result<usize, ArgError> {
+    match jobs.map(|s| s.parse::<usize>()) {
+        Some(Ok(jobs)) if jobs > 0 => Ok(jobs),
+        Some(_) => error("Invalid value for --jobs"),
+        None => error("Missing value for --jobs"),
+    }
+}
+
+fn parse_timeout(timeout: Option<String>) -> Result<Duration, ArgError> {
+    match timeout.map(|s| s.parse::<f64>()) {
+        Some(Ok(secs)) if secs > 0.0 && secs.is_finite() => Ok(Duration::from_secs_f64(secs)),
+        Some(_) => error("Invalid value for --timeout"),
+        None => error("Missing value for --timeout"),
+    }
+}
+
+/// Frame numbers and ranges of them, like `1-10,250`
+fn parse_frames(frames: Option<String>) -> Result<Vec<RangeInclusive<usize>>, ArgError> {
+    let Some(frames) = frames else {
+        return error("Missing value for --frames");
+    };
+    frames
+        .split(',')
+        .map(|range| {
+            let (first, last) = range.split_once('-').unwrap_or((range, range));
+            match (first.parse::<usize>(), last.parse::<usize>()) {
+                (Ok(first), Ok(last)) if 0 < first && first <= last => Ok(first..=last),
+                _ => error(format!("Invalid frames '{range}'")),
+            }
+        })
+        .collect()
+}
+
+fn parse_run(mut args: impl Iterator<Item = String>) -> Result<RunArgs, ArgError> {
+    let days = match args.next() {
+        Some(day) => parse_days(&day)?,
+        None => return error("Missing day, expected a number or 'all'"),
+    };
+    let mut run = RunArgs {
+        days,
+        ..Default::default()
+    };
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-p" | "--part" => run.part = parse_part(args.next())?,
+            "-i" | "--input" => {
+                run.input = match args.next() {
+                    Some(path) if path == "-" => Some(InputSource::Stdin),
+                    Some(path) => Some(InputSource::Path(PathBuf::from(path))),
+                    None => return error("Missing value for --input"),
+                }
+            }
+            "-j" | "--jobs" => run.jobs = parse_jobs(args.next())?,
+            "-t" | "--timeout" => run.timeout = Some(parse_timeout(args.next())?),
+            "-T" | "--timings" => run.timings = true,
+            "--timings-out" => {
+                let Some(path) = args.next().map(PathBuf::from) else {
+                    return error("Missing value for --timings-out");
+                };
+                let Some(format) = Format::from_path(&path) else {
+                    return error("--timings-out needs a .json or .csv file");
+                };
+                run.timings_out = Some((path, format));
+            }
+            "-v" | "--visualize" => run.visualize = true,
+            "--delay" => {
+                run.delay = match args.next().map(|s| s.parse::<u64>()) {
+                    Some(Ok(ms)) => Duration::from_millis(ms),
+                    Some(Err(_)) => return error("Invalid value for --delay"),
+                    None => return error("Missing value for --delay"),
+                }
+            }
+            "--png" => match args.next() {
+                Some(dir) => run.png = Some(PathBuf::from(dir)),
+                None => return error("Missing value for --png"),
+            },
+            "--frames" => run.frames = Some(parse_frames(args.next())?),
+            "--scale" => {
+                run.scale = match args.next().map(|s| s.parse::<usize>()) {
+                    Some(Ok(scale)) if scale > 0 => scale,
+                    Some(_) => return error("Invalid value for --scale"),
+                    None => return error("Missing value for --scale"),
+                }
+            }
+            "--palette" => {
+                run.palette = match args.next().map(|s| s.parse::<Palette>()) {
+                    Some(Ok(palette)) => palette,
+                    Some(Err(e)) => return error(format!("Invalid value for --palette, {e}")),
+                    None => return error("Missing value for --palette"),
+                }
+            }
+            "--stream" => run.stream = true,
+            "-e" | "--explain" => run.explain = true,
+            other => return error(format!("Unknown option '{other}'")),
+        }
+    }
+
+    if run.days == Selection::All && run.input.is_some() {
+        return error("--input can only be used with a single day");
+    }
+    if run.days == Selection::All && run.visualize {
+        return error("--visualize can only be used with a single day");
+    }
+    if run.days == Selection::All && run.png.is_some() {
+        return error("--png can only be used with a single day");
+    }
+    if run.days == Selection::All && run.stream {
+        return error("--stream can only be used with a single day");
+    }
+    if run.stream {
+        // Streaming skips the runner, which is what these are about
+        let unused = [
+            ("--jobs", run.jobs != 1),
+            ("--timeout", run.timeout.is_some()),
+            ("--timings", run.timings),
+            ("--timings-out", run.timings_out.is_some()),
+            ("--visualize", run.visualize),
+            ("--png", run.png.is_some()),
+        ];
+        if let Some((option, _)) = unused.iter().find(|(_, used)| *used) {
+            return error(format!("--stream can't be used with {option}"));
+        }
+    }
+    if run.days == Selection::All && run.explain {
+        return error("--explain can only be used with a single day");
+    }
+
+    Ok(run)
+}
+
+fn parse_verify(args: impl Iterator<Item = String>) -> Result<VerifyArgs, ArgError> {
+    let mut verify = VerifyArgs::default();
+    let mut args = args.peekable();
+
+    // The day is optional here, everything is verified by default
+    if let Some(day) = args.next_if(|arg| !arg.starts_with('-')) {
+        verify.days = parse_days(&day)?;
+    }
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-p" | "--part" => verify.part = parse_part(args.next())?,
+            "-j" | "--jobs" => verify.jobs = parse_jobs(args.next())?,
+            "-t" | "--timeout" => verify.timeout = Some(parse_timeout(args.next())?),
+            "--record" => verify.record = true,
+            other => return error(format!("Unknown option '{other}'")),
+        }
+    }
+
+    Ok(verify)
+}
+
+fn parse_generate(mut args: impl Iterator<Item = String>) -> Result<GenerateArgs, ArgError> {
+    let day = match args.next().as_deref().map(parse_days) {
+        Some(Ok(Selection::Day(n))) => n,
+        Some(Ok(Selection::All)) => return error("generate needs a single day"),
+        Some(Err(e)) => return Err(e),
+        None => return error("Missing day, expected a number"),
+    };
+    let mut generate = GenerateArgs {
+        day,
+        size: 100,
+        seed: None,
+    };
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--size" => {
+                generate.size = match args.next().map(|s| s.parse::<usize>()) {
+                    Some(Ok(size)) => size,
+                    Some(Err(_)) => return error("Invalid value for --size"),
+                    None => return error("Missing value for --size"),
+                }
+            }
+            "--seed" => {
+                generate.seed = match args.next().map(|s| s.parse::<u64>()) {
+                    Some(Ok(seed)) => Some(seed),
+                    Some(Err(_)) => return error("Invalid value for --seed"),
+                    None => return error("Missing value for --seed"),
+                }
+            }
+            other => return error(format!("Unknown option '{other}'")),
+        }
+    }
+
+    Ok(generate)
+}
+
+fn parse_new_day(mut args: impl Iterator<Item = String>) -> Result<u8, ArgError> {
+    let day = match args.next().as_deref().map(parse_days) {
+        Some(Ok(Selection::Day(n))) => n,
+        Some(Ok(Selection::All)) => return error("new-day needs a single day"),
+        Some(Err(e)) => return Err(e),
+        None => return error("Missing day, expected a number"),
+    };
+    match args.next() {
+        Some(other) => error(format!("Unknown option '{other}'")),
+        None => Ok(day),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(s: &str) -> impl Iterator<Item = String> + '_ {
+        s.split_whitespace().map(String::from)
+    }
+
+    #[test]
+    fn no_arguments() {
+        let result = parse(args(""));
+        assert_eq!(result, Ok(Command::Run(RunArgs::default())));
+    }
+
+    #[test]
+    fn single_day() {
+        let result = parse(args("run 12 --part 2 --input src/day12/test5.txt"));
+        assert_eq!(
+            result,
+            Ok(Command::Run(RunArgs {
+                days: Selection::Day(12),
+                part: Some(2),
+                input: Some(InputSource::Path(PathBuf::from("src/day12/test5.txt"))),
+                ..Default::default()
+            }))
+        );
+    }
+
+    #[test]
+    fn stdin() {
+        let result = parse(args("run 1 -i -"));
+        assert_eq!(
+            result,
+            Ok(Command::Run(RunArgs {
+                days: Selection::Day(1),
+                part: None,
+                input: Some(InputSource::Stdin),
+                ..Default::default()
+            }))
+        );
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(parse(args("run")).is_err());
+        assert!(parse(args("run 26")).is_err());
+        assert!(parse(args("run 1 --part 3")).is_err());
+        assert!(parse(args("run all --input -")).is_err());
+        assert!(parse(args("solve 1")).is_err());
+        assert!(parse(args("verify 1 --timeout 0")).is_err());
+        assert!(parse(args("verify 1 --input -")).is_err());
+        assert!(parse(args("run all --timings-out timings.txt")).is_err());
+        assert!(parse(args("run all --jobs 0")).is_err());
+        assert!(parse(args("run all --visualize")).is_err());
+        assert!(parse(args("run 6 --delay fast")).is_err());
+        assert!(parse(args("run all --png frames")).is_err());
+        assert!(parse(args("run 14 --png frames --frames 5-2")).is_err());
+        assert!(parse(args("run 14 --png frames --scale 0")).is_err());
+        assert!(parse(args("run 14 --png frames --palette red")).is_err());
+        assert!(parse(args("run all --stream")).is_err());
+        assert!(parse(args("run 22 --stream --jobs 4")).is_err());
+        assert!(parse(args("run 22 --stream --timings")).is_err());
+        assert!(parse(args("run 2 --stream -v")).is_err());
+        assert!(parse(args("run all --explain")).is_err());
+        assert!(parse(args("generate all")).is_err());
+        assert!(parse(args("generate 5 --size big")).is_err());
+        assert!(parse(args("generate 5 --seed")).is_err());
+        assert!(parse(args("new-day")).is_err());
+        assert!(parse(args("new-day all")).is_err());
+        assert!(parse(args("new-day 25 --part 1")).is_err());
+    }
+
+    #[test]
+    fn timings() {
+        let result = parse(args("run all -T --timings-out out/timings.csv"));
+        assert_eq!(
+            result,
+            Ok(Command::Run(RunArgs {
+                timings: true,
+                timings_out: Some((PathBuf::from("out/timings.csv"), Format::Csv)),
+                ..Default::default()
+            }))
+        );
+    }
+
+    #[test]
+    fn parallel() {
+        let result = parse(args("run all -j 8 --timeout 30"));
+        assert_eq!(
+            result,
+            Ok(Command::Run(RunArgs {
+                jobs: 8,
+                timeout: Some(Duration::from_secs(30)),
+                ..Default::default()
+            }))
+        );
+    }
+
+    #[test]
+    fn stream() {
+        let result = parse(args("run 22 -i big.txt --stream"));
+        assert_eq!(
+            result,
+            Ok(Command::Run(RunArgs {
+                days: Selection::Day(22),
+                input: Some(InputSource::Path(PathBuf::from("big.txt"))),
+                stream: true,
+                ..Default::default()
+            }))
+        );
+    }
+
+    #[test]
+    fn explain() {
+        let result = parse(args("run 2 -p 1 -e"));
+        assert_eq!(
+            result,
+            Ok(Command::Run(RunArgs {
+                days: Selection::Day(2),
+                part: Some(1),
+                explain: true,
+                ..Default::default()
+            }))
+        );
+    }
+
+    #[test]
+    fn visualize() {
+        let result = parse(args("run 15 -v --delay 20"));
+        assert_eq!(
+            result,
+            Ok(Command::Run(RunArgs {
+                days: Selection::Day(15),
+                visualize: true,
+                delay: Duration::from_millis(20),
+                ..Default::default()
+            }))
+        );
+    }
+
+    #[test]
+    fn png() {
+        let result = parse(args(
+            "run 14 -p 2 --png frames --frames 1-3,7 --scale 2 --palette X=00ff00",
+        ));
+        assert_eq!(
+            result,
+            Ok(Command::Run(RunArgs {
+                days: Selection::Day(14),
+                part: Some(2),
+                png: Some(PathBuf::from("frames")),
+                frames: Some(vec![1..=3, 7..=7]),
+                scale: 2,
+                palette: Palette::default().with('X', [0, 255, 0]),
+                ..Default::default()
+            }))
+        );
+    }
+
+    #[test]
+    fn verify() {
+        assert_eq!(
+            parse(args("verify")),
+            Ok(Command::Verify(VerifyArgs::default()))
+        );
+        assert_eq!(
+            parse(args("verify 6 --part 2 -t 0.5 --record")),
+            Ok(Command::Verify(VerifyArgs {
+                days: Selection::Day(6),
+                part: Some(2),
+                jobs: 1,
+                timeout: Some(Duration::from_millis(500)),
+                record: true,
+            }))
+        );
+    }
+
+    #[test]
+    fn generate() {
+        assert_eq!(
+            parse(args("generate 16")),
+            Ok(Command::Generate(GenerateArgs {
+                day: 16,
+                size: 100,
+                seed: None,
+            }))
+        );
+        assert_eq!(
+            parse(args("generate 16 --size 31 --seed 7")),
+            Ok(Command::Generate(GenerateArgs {
+                day: 16,
+                size: 31,
+                seed: Some(7),
+            }))
+        );
+    }
+
+    #[test]
+    fn new_day() {
+        assert_eq!(parse(args("new-day 25")), Ok(Command::NewDay(25)));
+    }
+}
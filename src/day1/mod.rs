@@ -1,59 +1,178 @@
-use std::{collections::HashMap, fs};
+//! Day 1: Historian Hysteria
+//!
+//! ```
+//! use aoc2024::day1;
+//!
+//! let input = day1::parse(&std::fs::read_to_string("src/day1/test1.txt")?)?;
+//! assert_eq!(day1::part_1(&input)?, 11);
+//! # Ok::<(), aoc2024::error::Error>(())
+//! ```
 
-pub fn solve_1(input: &str) -> u32 {
-    let data = fs::read_to_string(input).expect("Can't open file");
-    let mut nums_1 = vec![];
-    let mut nums_2 = vec![];
+use std::{collections::HashMap, fmt::Display, fs, io::BufRead};
 
-    for line in data.lines() {
-        let mut iter = line.split_whitespace();
-        nums_1.push(iter.next().unwrap().parse::<u32>().unwrap());
-        nums_2.push(iter.next().unwrap().parse::<u32>().unwrap());
-    }
-    nums_1.sort();
-    nums_2.sort();
+use crate::error::Result;
+use crate::solution::Solution;
+use crate::util::hook;
+use crate::util::input::stream_lines;
+use crate::util::random::Rng;
+use crate::util::trace::Fact;
+
+pub struct Input {
+    left: Vec<u32>,
+    right: Vec<u32>,
+}
+
+pub fn parse(data: &str) -> Result<Input> {
+    parse_reader(data.as_bytes())
+}
 
-    let nums: Vec<_> = nums_1
-        .iter()
-        .zip(nums_2.iter())
-        .map(|(a, b)| a.abs_diff(*b))
-        .collect();
+/// Like `parse`, without the whole text in memory at once. The numbers are still all kept, both
+/// parts need every one of them.
+pub fn parse_reader(reader: impl BufRead) -> Result<Input> {
+    let mut left = vec![];
+    let mut right = vec![];
 
-    nums.iter().sum()
+    stream_lines(reader, |line| {
+        let mut iter = line.text.split_whitespace();
+        left.push(line.parse_next(&mut iter)?);
+        right.push(line.parse_next(&mut iter)?);
+        Ok(())
+    })?;
+    Ok(Input { left, right })
 }
 
-pub fn solve_2(input: &str) -> u32 {
-    let data = fs::read_to_string(input).expect("Can't open file");
-    let mut nums_1 = vec![];
+/// Part 1 on lists it can sort in place
+fn total_distance(left: &mut [u32], right: &mut [u32]) -> u32 {
+    left.sort();
+    right.sort();
+
+    left.iter()
+        .zip(right.iter())
+        .map(|(a, b)| {
+            hook::fact(|| {
+                Fact::new("pair")
+                    .with("left", a)
+                    .with("right", b)
+                    .with("distance", a.abs_diff(*b))
+            });
+            a.abs_diff(*b)
+        })
+        .sum()
+}
+
+fn similarity_score(left: &[u32], right: &[u32]) -> u32 {
     let mut similarity: HashMap<u32, u32> = HashMap::new();
+    for sim in right {
+        *similarity.entry(*sim).or_insert(0) += 1;
+    }
 
-    for line in data.lines() {
-        let mut iter = line.split_whitespace();
-        nums_1.push(iter.next().unwrap().parse::<u32>().unwrap());
-        let sim = iter.next().unwrap().parse::<u32>().unwrap();
-        *similarity.entry(sim).or_insert(0) += 1;
+    left.iter()
+        .map(|v| {
+            let times = similarity.get(v).unwrap_or(&0);
+            hook::fact(|| {
+                Fact::new("number")
+                    .with("left", v)
+                    .with("times_on_right", times)
+                    .with("score", v * times)
+            });
+            v * times
+        })
+        .sum()
+}
+
+pub fn part_1(input: &Input) -> Result<u32> {
+    Ok(total_distance(
+        &mut input.left.clone(),
+        &mut input.right.clone(),
+    ))
+}
+
+pub fn part_2(input: &Input) -> Result<u32> {
+    Ok(similarity_score(&input.left, &input.right))
+}
+
+/// `size` pairs of numbers, with some of the right ones also on the left
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let left: Vec<i64> = (0..size).map(|_| rng.range(10000..=99999)).collect();
+    let mut text = String::new();
+    for &l in &left {
+        let r = if rng.chance(0.3) {
+            *rng.pick(&left)
+        } else {
+            rng.range(10000..=99999)
+        };
+        text += &format!("{l}   {r}\n");
     }
+    text
+}
 
-    let nums: Vec<u32> = nums_1
-        .iter()
-        .map(|v| v * similarity.get(v).unwrap_or(&0))
-        .collect();
+/// Both parts from `reader`, see `parse_reader`. The lists are sorted where they are instead of
+/// copies of them, after part 2 which doesn't care about the order.
+pub fn solve_reader(reader: impl BufRead) -> Result<(u32, u32)> {
+    let Input {
+        mut left,
+        mut right,
+    } = parse_reader(reader)?;
+    let part_2 = similarity_score(&left, &right);
+    Ok((total_distance(&mut left, &mut right), part_2))
+}
 
-    nums.iter().sum()
+pub fn solve_1(input: &str) -> Result<u32> {
+    let data = fs::read_to_string(input)?;
+    part_1(&parse(&data)?)
+}
+
+pub fn solve_2(input: &str) -> Result<u32> {
+    let data = fs::read_to_string(input)?;
+    part_2(&parse(&data)?)
+}
+
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    const DAY: u8 = 1;
+
+    type Input = Input;
+
+    fn parse(data: &str) -> Result<Self::Input> {
+        parse(data)
+    }
+
+    fn part_1(input: &Self::Input) -> Result<impl Display> {
+        part_1(input)
+    }
+
+    fn part_2(input: &Self::Input) -> Result<impl Display> {
+        part_2(input)
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        generate(rng, size)
+    }
+
+    fn stream(reader: &mut dyn BufRead) -> Option<Result<(impl Display, impl Display)>> {
+        Some(solve_reader(reader))
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::io;
+
     use super::*;
+
     #[test]
-    fn part1() {
-        let result = solve_1("src/day1/test1.txt");
-        assert_eq!(result, 11);
+    fn malformed() {
+        let error = parse("3   4\n5   x\n").err().unwrap();
+        assert_eq!(
+            error.to_string(),
+            "line 2, column 5: expected u32, found 'x'"
+        );
     }
 
     #[test]
-    fn part2() {
-        let result = solve_2("src/day1/test2.txt");
-        assert_eq!(result, 31);
+    fn reader() {
+        let file = fs::File::open("src/day1/test1.txt").unwrap();
+        assert_eq!(solve_reader(io::BufReader::new(file)).unwrap(), (11, 31));
     }
 }
@@ -1,9 +1,29 @@
+//! Day 10: Hoof It
+//!
+//! ```
+//! use aoc2024::day10;
+//!
+//! let input = day10::parse(&std::fs::read_to_string("src/day10/test1.txt")?)?;
+//! assert_eq!(day10::part_1(&input)?, 1);
+//! # Ok::<(), aoc2024::error::Error>(())
+//! ```
+
 use std::{
     cell::RefCell,
     collections::{HashMap, HashSet},
+    fmt::Display,
     fs,
 };
 
+use crate::error::Result;
+use crate::solution::Solution;
+use crate::util::coord::Coord;
+use crate::util::grid::Grid;
+use crate::util::hook;
+use crate::util::input::lines;
+use crate::util::random::Rng;
+use crate::util::trace::Fact;
+
 #[derive(Debug, Clone, Copy)]
 struct Height {
     height: u32,
@@ -88,26 +108,28 @@ fn build_graphs(
     (trail_ends.len() as u32, score_pt2)
 }
 
-pub fn solve_1(input: &str) -> u32 {
-    let data = fs::read_to_string(input).expect("Can't open file");
-    let mut id = 0;
+pub struct Input {
+    map: Vec<Vec<Height>>,
+}
 
-    let mut points: HashMap<usize, Height> = HashMap::new();
-    let mut map: Vec<Vec<Height>> = data
-        .lines()
+pub fn parse(data: &str) -> Result<Input> {
+    let map = lines(data)
         .map(|line| {
             line.chars()
-                .map(|b| {
-                    id += 1;
-                    let h = Height {
-                        height: b.to_digit(10).unwrap(),
-                    };
-                    points.insert(id, h);
-                    h
+                .map(|(col, b)| {
+                    let height = b.to_digit(10).ok_or_else(|| {
+                        line.error(col, format!("expected a height, found '{b}'"))
+                    })?;
+                    Ok(Height { height })
                 })
                 .collect()
         })
-        .collect();
+        .collect::<Result<_>>()?;
+    Ok(Input { map })
+}
+
+pub fn part_1(input: &Input) -> Result<u32> {
+    let mut map = input.map.clone();
 
     let mut graphs: Vec<Graph> = Vec::new();
     let mut scores = 0;
@@ -117,32 +139,20 @@ pub fn solve_1(input: &str) -> u32 {
             if point.height == 0 {
                 let mut trail_ends: HashSet<(usize, usize)> = HashSet::new();
                 let (tmp_score, _) = build_graphs(&mut map, &mut graphs, row, col, &mut trail_ends);
+                hook::fact(|| {
+                    Fact::new("trailhead")
+                        .with("at", Coord::from_usize(row, col))
+                        .with("score", tmp_score)
+                });
                 scores += tmp_score;
             }
         }
     }
-    scores
+    Ok(scores)
 }
-pub fn solve_2(input: &str) -> u32 {
-    let data = fs::read_to_string(input).expect("Can't open file");
-    let mut id = 0;
 
-    let mut points: HashMap<usize, Height> = HashMap::new();
-    let mut map: Vec<Vec<Height>> = data
-        .lines()
-        .map(|line| {
-            line.chars()
-                .map(|b| {
-                    id += 1;
-                    let h = Height {
-                        height: b.to_digit(10).unwrap(),
-                    };
-                    points.insert(id, h);
-                    h
-                })
-                .collect()
-        })
-        .collect();
+pub fn part_2(input: &Input) -> Result<u32> {
+    let mut map = input.map.clone();
 
     let mut graphs: Vec<Graph> = Vec::new();
     let mut scores = 0;
@@ -152,31 +162,78 @@ pub fn solve_2(input: &str) -> u32 {
             if point.height == 0 {
                 let mut trail_ends: HashSet<(usize, usize)> = HashSet::new();
                 let (_, tmp_score) = build_graphs(&mut map, &mut graphs, row, col, &mut trail_ends);
+                hook::fact(|| {
+                    Fact::new("trailhead")
+                        .with("at", Coord::from_usize(row, col))
+                        .with("rating", tmp_score)
+                });
                 scores += tmp_score;
             }
         }
     }
-    scores
+    Ok(scores)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    #[test]
-    fn part1() {
-        let result = solve_1("src/day10/test1.txt");
-        assert_eq!(result, 1);
+/// A `size` by `size` map with at least `size` hiking trails
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let size = size.max(4);
+    let mut map = Grid::new(size, size, '0');
+    for (_, height) in map.iter_mut() {
+        *height = char::from(b'0' + rng.below(10) as u8);
+    }
+    let mut trails = 0;
+    while trails < size {
+        let mut trail = vec![Coord::from_usize(rng.below(size), rng.below(size))];
+        while trail.len() < 10 {
+            let next: Vec<Coord> = map
+                .neighbors(trail[trail.len() - 1])
+                .filter(|next| !trail.contains(next))
+                .collect();
+            if next.is_empty() {
+                break;
+            }
+            trail.push(*rng.pick(&next));
+        }
+        if trail.len() == 10 {
+            for (height, &coord) in trail.iter().enumerate() {
+                map[coord] = char::from(b'0' + height as u8);
+            }
+            trails += 1;
+        }
+    }
+    format!("{map}\n")
+}
+
+pub fn solve_1(input: &str) -> Result<u32> {
+    let data = fs::read_to_string(input)?;
+    part_1(&parse(&data)?)
+}
+
+pub fn solve_2(input: &str) -> Result<u32> {
+    let data = fs::read_to_string(input)?;
+    part_2(&parse(&data)?)
+}
+
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    const DAY: u8 = 10;
+
+    type Input = Input;
+
+    fn parse(data: &str) -> Result<Self::Input> {
+        parse(data)
+    }
+
+    fn part_1(input: &Self::Input) -> Result<impl Display> {
+        part_1(input)
     }
 
-    #[test]
-    fn part1_larger() {
-        let result = solve_1("src/day10/test2.txt");
-        assert_eq!(result, 36);
+    fn part_2(input: &Self::Input) -> Result<impl Display> {
+        part_2(input)
     }
 
-    #[test]
-    fn part2() {
-        let result = solve_2("src/day10/test2.txt");
-        assert_eq!(result, 81);
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        generate(rng, size)
     }
 }
@@ -1,11 +1,28 @@
-use std::{collections::HashMap, fs};
+//! Day 11: Plutonian Pebbles
+//!
+//! ```
+//! use aoc2024::day11;
+//!
+//! let input = day11::parse(&std::fs::read_to_string("src/day11/test1.txt")?)?;
+//! assert_eq!(day11::part_1(&input, 1)?, 7);
+//! # Ok::<(), aoc2024::error::Error>(())
+//! ```
+
+use std::{collections::HashMap, fmt::Display, fs};
+
+use crate::error::Result;
+use crate::solution::{Params, Solution};
+use crate::util::hook;
+use crate::util::input::lines;
+use crate::util::random::Rng;
+use crate::util::trace::Fact;
 
 fn blink(stones: &mut Vec<u64>) {
     for (i, stone) in stones.clone().iter().enumerate() {
         let digits = (*stone as f64 + 0.1).log10().ceil() as u64;
         if *stone == 0 {
             stones[i] = 1;
-        } else if digits % 2 == 0 {
+        } else if digits.is_multiple_of(2) {
             let temp = stone.to_string();
             // we know it's even
             let (t1, t2) = temp.split_at(temp.len() / 2);
@@ -32,7 +49,7 @@ fn blink_2(stones: &mut HashMap<u64, u64>) {
             // "move" 1 stone from 0 to 1
             stones.entry(0).and_modify(|d| *d -= cnt);
             stones.entry(1).and_modify(|d| *d += cnt).or_insert(cnt);
-        } else if digits % 2 == 0 {
+        } else if digits.is_multiple_of(2) {
             let temp = stone.0.to_string();
             // we know it's even
             let (t1, t2) = temp.split_at(temp.len() / 2);
@@ -54,80 +71,106 @@ fn blink_2(stones: &mut HashMap<u64, u64>) {
     }
 }
 
-pub fn solve_1(input: &str, blinks: u64) -> u64 {
-    let data = fs::read_to_string(input).expect("Can't open file");
-    // Contrary to the puzzle text, the order is not important at all
-    let mut stones: Vec<u64> = data
-        .lines()
+pub struct Input {
+    stones: Vec<u64>,
+    /// Blinks of both parts instead of 25 and 75, for the examples
+    blinks: Option<u64>,
+}
+
+pub fn parse(data: &str) -> Result<Input> {
+    let stones = lines(data)
         .flat_map(|line| {
-            let nums = line.split_ascii_whitespace();
-            nums.map(|num| num.parse::<u64>().unwrap())
+            let nums = line.text.split_ascii_whitespace();
+            nums.map(move |num| line.parse::<u64>(num))
         })
-        .collect();
-    for _ in 1..=blinks {
+        .collect::<Result<_>>()?;
+    Ok(Input {
+        stones,
+        blinks: None,
+    })
+}
+
+pub fn part_1(input: &Input, blinks: u64) -> Result<u64> {
+    // Contrary to the puzzle text, the order is not important at all
+    let mut stones = input.stones.clone();
+    for i in 1..=blinks {
         blink(&mut stones);
+        hook::fact(|| {
+            Fact::new("blink")
+                .with("number", i)
+                .with("stones", stones.len())
+        });
     }
-    stones.len() as u64
+    Ok(stones.len() as u64)
 }
-pub fn solve_2(input: &str, blinks: u64) -> u64 {
-    let data = fs::read_to_string(input).expect("Can't open file");
 
+pub fn part_2(input: &Input, blinks: u64) -> Result<u64> {
     let mut stones: HashMap<u64, u64> = HashMap::new();
-    for line in data.lines() {
-        for num in line.split_ascii_whitespace() {
-            stones
-                .entry(num.parse::<u64>().unwrap())
-                .and_modify(|d| *d += 1)
-                .or_insert(1);
-        }
+    for num in &input.stones {
+        stones.entry(*num).and_modify(|d| *d += 1).or_insert(1);
     }
-    for _ in 1..=blinks {
+    for i in 1..=blinks {
         blink_2(&mut stones);
+        hook::fact(|| {
+            Fact::new("blink")
+                .with("number", i)
+                .with("stones", stones.values().sum::<u64>())
+                .with("different", stones.values().filter(|&&n| n > 0).count())
+        });
     }
     let mut stones_filtered: HashMap<u64, u64> = HashMap::new();
     for stone in stones.iter().filter(|s| *s.1 > 0) {
         stones_filtered.insert(*stone.0, *stone.1);
     }
 
-    stones_filtered.iter().map(|s| s.1).sum()
+    Ok(stones_filtered.iter().map(|s| s.1).sum())
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    #[test]
-    fn part1() {
-        let result = solve_1("src/day11/test1.txt", 1);
-        assert_eq!(result, 7);
-    }
+/// A line of `size` stones
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let stones: Vec<String> = (0..size.max(1))
+        .map(|_| rng.range(0..=999_999).to_string())
+        .collect();
+    stones.join(" ") + "\n"
+}
+
+pub fn solve_1(input: &str, blinks: u64) -> Result<u64> {
+    let data = fs::read_to_string(input)?;
+    part_1(&parse(&data)?, blinks)
+}
+
+pub fn solve_2(input: &str, blinks: u64) -> Result<u64> {
+    let data = fs::read_to_string(input)?;
+    part_2(&parse(&data)?, blinks)
+}
+
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    const DAY: u8 = 11;
+
+    type Input = Input;
 
-    #[test]
-    fn part1_2() {
-        let result = solve_1("src/day11/test2.txt", 6);
-        assert_eq!(result, 22);
+    fn parse(data: &str) -> Result<Self::Input> {
+        parse(data)
     }
 
-    #[test]
-    fn part1_3() {
-        let result = solve_1("src/day11/test2.txt", 25);
-        assert_eq!(result, 55312);
+    fn parse_with(data: &str, params: &Params) -> Result<Self::Input> {
+        params.check(&["blinks"])?;
+        let mut input = parse(data)?;
+        input.blinks = params.get("blinks").map(|blinks| blinks as u64);
+        Ok(input)
     }
 
-    #[test]
-    fn part2() {
-        let result = solve_2("src/day11/test1.txt", 1);
-        assert_eq!(result, 7);
+    fn part_1(input: &Self::Input) -> Result<impl Display> {
+        part_1(input, input.blinks.unwrap_or(25))
     }
 
-    #[test]
-    fn part2_2() {
-        let result = solve_2("src/day11/test2.txt", 6);
-        assert_eq!(result, 22);
+    fn part_2(input: &Self::Input) -> Result<impl Display> {
+        part_2(input, input.blinks.unwrap_or(75))
     }
 
-    #[test]
-    fn part2_3() {
-        let result = solve_2("src/day11/test2.txt", 25);
-        assert_eq!(result, 55312);
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        generate(rng, size)
     }
 }
@@ -1,26 +1,30 @@
-use std::{collections::HashSet, fs};
-
-type Coord = (isize, isize);
-const NEIGHBOR_CELLS: [Coord; 4] = [
-    (-1, 0), // up
-    (0, 1),  // down
-    (1, 0),  // left
-    (0, -1), // right
-];
-
-struct Garden {
-    plants: Vec<Vec<char>>,
-    size: isize, // height and width are the same
-}
+//! Day 12: Garden Groups
+//!
+//! ```
+//! use aoc2024::day12;
+//!
+//! let input = day12::parse(&std::fs::read_to_string("src/day12/test1.txt")?)?;
+//! assert_eq!(day12::part_1(&input)?, 140);
+//! # Ok::<(), aoc2024::error::Error>(())
+//! ```
+
+use std::{collections::HashSet, fmt::Display, fs};
+
+use crate::error::Result;
+use crate::solution::Solution;
+use crate::util::coord::Coord;
+use crate::util::grid::Grid;
+use crate::util::hook;
+use crate::util::random::Rng;
+use crate::util::trace::Fact;
 
 /// Return an (area, perimeter) tuple
-fn flood_fill(garden: &Garden, coord: Coord, visited: &mut HashSet<Coord>) -> Coord {
+fn flood_fill(garden: &Grid<char>, coord: Coord, visited: &mut HashSet<Coord>) -> (isize, isize) {
     if visited.contains(&coord) {
         return (0, 0);
     }
 
-    let current_plant = &garden.plants[coord.0 as usize][coord.1 as usize];
-    let size = garden.size;
+    let current_plant = garden[coord];
     let mut neighbors = vec![];
     visited.insert(coord);
 
@@ -31,28 +35,20 @@ fn flood_fill(garden: &Garden, coord: Coord, visited: &mut HashSet<Coord>) -> Co
     //    -|A|B
     //      -
     //
-    let mut perimeter = NEIGHBOR_CELLS.iter().fold(0, |mut acc, shift| {
-        let neighbor_coord = (coord.0 + shift.0, coord.1 + shift.1);
-        if neighbor_coord.0 >= 0
-            && neighbor_coord.0 < size
-            && neighbor_coord.1 >= 0
-            && neighbor_coord.1 < size
-        {
-            // Neighbor coordinates are valid
-            let neighbor = &garden.plants[neighbor_coord.0 as usize][neighbor_coord.1 as usize];
-            if neighbor == current_plant {
+    let mut perimeter = coord
+        .neighbors()
+        .iter()
+        .fold(0, |mut acc, &neighbor_coord| {
+            if garden.get(neighbor_coord) == Some(&current_plant) {
                 neighbors.push(neighbor_coord);
                 // The currently visited neighbor is of the same type, perimeter doesn't increase
             } else {
-                // The currently visited neighbor is of another type, perimeter += 1
+                // The currently visited neighbor is of another type or we're at the edge
+                // -> add a fence -> perimeter += 1
                 acc += 1;
             }
-        } else {
-            // The currently visited plant is at the edge -> add an edge fence -> perimeter += 1
-            acc += 1;
-        }
-        acc
-    });
+            acc
+        });
 
     let mut area = 1; // Each cell has their own area
 
@@ -66,16 +62,16 @@ fn flood_fill(garden: &Garden, coord: Coord, visited: &mut HashSet<Coord>) -> Co
 }
 
 /// Return an (area, sides) tuple
-fn flood_fill_corner(garden: &Garden, coord: Coord, visited: &mut HashSet<Coord>) -> Coord {
+fn flood_fill_corner(
+    garden: &Grid<char>,
+    coord: Coord,
+    visited: &mut HashSet<Coord>,
+) -> (isize, isize) {
     if visited.contains(&coord) {
         return (0, 0);
     }
-    if garden.plants[coord.0 as usize][coord.1 as usize] == '.' {
-        return (0, 0);
-    }
 
-    let current_plant = garden.plants[coord.0 as usize][coord.1 as usize];
-    let size = garden.size;
+    let current_plant = garden[coord];
     visited.insert(coord);
 
     // Here we need to check for corners
@@ -85,27 +81,24 @@ fn flood_fill_corner(garden: &Garden, coord: Coord, visited: &mut HashSet<Coord>
     // 3 neighbors == ouch
     // 4 neighbors == anything between 0-4
 
-    let neighbors: Vec<Coord> = NEIGHBOR_CELLS
-        .iter()
-        .map(|shift| (coord.0 + shift.0, coord.1 + shift.1))
-        .filter(|c| {
-            (c.0 >= 0 && c.0 < size && c.1 >= 0 && c.1 < size)
-                && (garden.plants[c.0 as usize][c.1 as usize] == current_plant)
-        })
+    let neighbors: Vec<Coord> = garden
+        .neighbors(coord)
+        .filter(|&c| garden[c] == current_plant)
         .collect();
 
     // Ugh.
     // There are only 8 ways a corner can appear, depending on the neighbors of a plant
-    // Since the data is padded with '.'s, no boundary checks are needed
-
-    let top_left = garden.plants[coord.0 as usize - 1][coord.1 as usize - 1];
-    let top = garden.plants[coord.0 as usize - 1][coord.1 as usize];
-    let top_right = garden.plants[coord.0 as usize - 1][coord.1 as usize + 1];
-    let left = garden.plants[coord.0 as usize][coord.1 as usize - 1];
-    let right = garden.plants[coord.0 as usize][coord.1 as usize + 1];
-    let bottom_left = garden.plants[coord.0 as usize + 1][coord.1 as usize - 1];
-    let bottom = garden.plants[coord.0 as usize + 1][coord.1 as usize];
-    let bottom_right = garden.plants[coord.0 as usize + 1][coord.1 as usize + 1];
+    // Anything outside of the garden is a '.', which is never a plant
+
+    let plant = |x, y| garden.get(coord + Coord::new(x, y)).copied().unwrap_or('.');
+    let top_left = plant(-1, -1);
+    let top = plant(-1, 0);
+    let top_right = plant(-1, 1);
+    let left = plant(0, -1);
+    let right = plant(0, 1);
+    let bottom_left = plant(1, -1);
+    let bottom = plant(1, 0);
+    let bottom_right = plant(1, 1);
 
     let mut corners = 0;
 
@@ -145,112 +138,107 @@ fn flood_fill_corner(garden: &Garden, coord: Coord, visited: &mut HashSet<Coord>
     (area, corners)
 }
 
-pub fn solve_1(input: &str) -> isize {
-    let data = fs::read_to_string(input).expect("Can't open file");
+pub struct Input {
+    plants: Grid<char>,
+}
+
+pub fn parse(data: &str) -> Result<Input> {
+    let plants = Grid::parse(data, Some)?;
+    Ok(Input { plants })
+}
+
+pub fn part_1(input: &Input) -> Result<isize> {
     let mut fence_price = 0;
-    let plants: Vec<Vec<char>> = data.lines().map(|line| line.chars().collect()).collect();
+    let garden = &input.plants;
 
     let mut visited = HashSet::new();
-    let garden = Garden {
-        size: plants.len() as isize,
-        plants: plants.clone(),
-    };
-
-    for (row, plant_row) in plants.iter().enumerate() {
-        for (col, _) in plant_row.iter().enumerate() {
-            let coord = (row as isize, col as isize);
-            if !visited.contains(&coord) {
-                let price = flood_fill(&garden, coord, &mut visited);
-                fence_price += price.0 * price.1;
-            }
+    for coord in garden.coords() {
+        if !visited.contains(&coord) {
+            let price = flood_fill(garden, coord, &mut visited);
+            hook::fact(|| {
+                Fact::new("region")
+                    .with("plant", garden[coord])
+                    .with("at", coord)
+                    .with("area", price.0)
+                    .with("perimeter", price.1)
+            });
+            fence_price += price.0 * price.1;
         }
     }
 
-    fence_price
+    Ok(fence_price)
 }
-pub fn solve_2(input: &str) -> isize {
-    let data = fs::read_to_string(input).expect("Can't open file");
+
+pub fn part_2(input: &Input) -> Result<isize> {
     let mut fence_price = 0;
-    let mut plants: Vec<Vec<char>> = data.lines().map(|line| line.chars().collect()).collect();
-    for row in &mut plants {
-        row.push('.');
-        row.insert(0, '.');
-    }
-    let new_size = plants[0].len();
-    plants.push(vec!['.'; new_size]);
-    plants.insert(0, vec!['.'; new_size]);
+    let garden = &input.plants;
+
     let mut visited = HashSet::new();
-    let garden = Garden {
-        size: plants.len() as isize,
-        plants: plants.clone(),
-    };
-
-    for (row, plant_row) in plants.iter().enumerate() {
-        for (col, _) in plant_row.iter().enumerate() {
-            let coord = (row as isize, col as isize);
-            if !visited.contains(&coord) && plants[row][col] != '.' {
-                let price = flood_fill_corner(&garden, coord, &mut visited);
-                fence_price += price.0 * price.1;
-            }
+    for coord in garden.coords() {
+        if !visited.contains(&coord) {
+            let price = flood_fill_corner(garden, coord, &mut visited);
+            hook::fact(|| {
+                Fact::new("region")
+                    .with("plant", garden[coord])
+                    .with("at", coord)
+                    .with("area", price.0)
+                    .with("sides", price.1)
+            });
+            fence_price += price.0 * price.1;
         }
     }
-    fence_price
+    Ok(fence_price)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    #[test]
-    fn part1() {
-        let result = solve_1("src/day12/test1.txt");
-        assert_eq!(result, 140);
-    }
+/// A `size` by `size` garden of regions grown around random plants
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let size = size.max(1);
+    let seeds: Vec<(Coord, char)> = (0..(size * size / 20).max(1))
+        .map(|_| {
+            let coord = Coord::from_usize(rng.below(size), rng.below(size));
+            (coord, char::from(b'A' + rng.below(26) as u8))
+        })
+        .collect();
+    let mut garden = Grid::new(size, size, '.');
+    for (coord, plant) in garden.iter_mut() {
+        *plant = seeds
+            .iter()
+            .min_by_key(|(seed, _)| seed.distance(&coord))
+            .map_or('A', |&(_, plant)| plant);
+    }
+    format!("{garden}\n")
+}
 
-    #[test]
-    fn part1_2() {
-        let result = solve_1("src/day12/test2.txt");
-        assert_eq!(result, 772);
-    }
+pub fn solve_1(input: &str) -> Result<isize> {
+    let data = fs::read_to_string(input)?;
+    part_1(&parse(&data)?)
+}
 
-    #[test]
-    fn part1_3() {
-        let result = solve_1("src/day12/test3.txt");
-        assert_eq!(result, 1930);
-    }
+pub fn solve_2(input: &str) -> Result<isize> {
+    let data = fs::read_to_string(input)?;
+    part_2(&parse(&data)?)
+}
 
-    #[test]
-    fn part2() {
-        let result = solve_2("src/day12/test1.txt");
-        assert_eq!(result, 80);
-    }
+pub struct Puzzle;
 
-    #[test]
-    fn part2_2() {
-        let result = solve_2("src/day12/test2.txt");
-        assert_eq!(result, 436);
-    }
+impl Solution for Puzzle {
+    const DAY: u8 = 12;
+
+    type Input = Input;
 
-    #[test]
-    fn part2_3() {
-        let result = solve_2("src/day12/test3.txt");
-        assert_eq!(result, 1206);
+    fn parse(data: &str) -> Result<Self::Input> {
+        parse(data)
     }
 
-    #[test]
-    fn part2_4() {
-        let result = solve_2("src/day12/test4.txt");
-        assert_eq!(result, 236);
+    fn part_1(input: &Self::Input) -> Result<impl Display> {
+        part_1(input)
     }
 
-    #[test]
-    fn part2_5() {
-        let result = solve_2("src/day12/test5.txt");
-        assert_eq!(result, 368);
+    fn part_2(input: &Self::Input) -> Result<impl Display> {
+        part_2(input)
     }
 
-    #[test]
-    fn part2_6() {
-        let result = solve_2("src/day12/test6.txt");
-        assert_eq!(result, 4 * 16);
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        generate(rng, size)
     }
 }
@@ -1,8 +1,27 @@
-use std::fs;
+//! Day 13: Claw Contraption
+//!
+//! ```
+//! use aoc2024::day13;
+//!
+//! let input = day13::parse(&std::fs::read_to_string("src/day13/test1.txt")?)?;
+//! assert_eq!(day13::part_1(&input)?, 480);
+//! # Ok::<(), aoc2024::error::Error>(())
+//! ```
 
-use regex::Regex;
+use std::{fmt::Display, fs};
 
-#[derive(Debug)]
+use crate::error::{Error, Result};
+use crate::solution::Solution;
+use crate::util::hook;
+use crate::util::input::sections;
+use crate::util::parse::{exactly, unsigned};
+use crate::util::random::Rng;
+use crate::util::trace::Fact;
+
+#[cfg(feature = "reference")]
+pub mod reference;
+
+#[derive(Debug, Clone, Copy)]
 struct Equation {
     a: (u64, u64),
     b: (u64, u64),
@@ -26,7 +45,8 @@ fn calculate(eq: &Equation) -> Option<(u64, u64)> {
     let m_f = (eq.a.0 as f64 * eq.target.1 as f64 - eq.a.1 as f64 * eq.target.0 as f64) / div;
 
     // If the results are not integers, it's not possible to reach the target
-    if n_f % 1f64 != 0f64 || m_f % 1f64 != 0f64 {
+    // Whole presses only, and no button can be pressed a negative number of times
+    if n_f % 1f64 != 0f64 || m_f % 1f64 != 0f64 || n_f < 0f64 || m_f < 0f64 {
         return None;
     }
     let n = n_f as u64;
@@ -35,100 +55,149 @@ fn calculate(eq: &Equation) -> Option<(u64, u64)> {
     Some((n, m))
 }
 
-pub fn solve_1(input: &str) -> u64 {
-    let data = fs::read_to_string(input).expect("Can't open file");
-    let binding = data.replace("\r\n", "\n");
-    let filtered = binding.as_str();
-    let re = Regex::new(
-        r"Button A: X\+(\d+), Y\+(\d+)\nButton B: X\+(\d+), Y\+(\d+)\nPrize: X=(\d+), Y=(\d+)",
-    )
-    .unwrap();
-
-    let mut eqs = vec![];
-    for caps in re.captures_iter(filtered) {
-        eqs.push(Equation {
-            a: (
-                caps[1].parse::<u64>().unwrap(),
-                caps[2].parse::<u64>().unwrap(),
-            ),
-            b: (
-                caps[3].parse::<u64>().unwrap(),
-                caps[4].parse::<u64>().unwrap(),
-            ),
-            target: (
-                caps[5].parse::<u64>().unwrap(),
-                caps[6].parse::<u64>().unwrap(),
-            ),
-        });
+/// Tells the observers how the claw gets to the prize of `eq` with `presses`, if it does
+fn explain(eq: &Equation, presses: Option<(u64, u64)>) {
+    hook::fact(|| {
+        let fact = Fact::new("machine").with("prize", format!("{:?}", eq.target));
+        match presses {
+            Some((n, m)) => fact.with("a", n).with("b", m).with("tokens", 3 * n + m),
+            None => fact.with("winnable", false),
+        }
+    });
+}
+
+pub struct Input {
+    machines: Vec<Equation>,
+}
+
+pub fn parse(data: &str) -> Result<Input> {
+    let mut machines = vec![];
+    for section in sections(data) {
+        // Button A, button B and the prize, X and Y on each line
+        let xy = section
+            .lines()
+            .map(|line| exactly(&line, unsigned(&line)?).map(|[x, y]| (x, y)))
+            .collect::<Result<Vec<_>>>()?;
+        let [a, b, target] = xy[..] else {
+            return Err(Error::parse(
+                section.first_line,
+                1,
+                "expected the two buttons and the prize",
+            ));
+        };
+        machines.push(Equation { a, b, target });
     }
+    Ok(Input { machines })
+}
 
-    let presses: Vec<_> = eqs.iter().map(calculate).collect();
+pub fn part_1(input: &Input) -> Result<u64> {
+    let presses: Vec<_> = input.machines.iter().map(calculate).collect();
+    for (eq, press) in input.machines.iter().zip(&presses) {
+        explain(eq, press.filter(|&(n, m)| n <= 100 && m <= 100));
+    }
 
-    presses
+    Ok(presses
         .iter()
         .filter(|press| {
             press.is_some_and(|(n, m)|
                 // Filter too long plays
-                n <= 100 || m <= 100)
+                n <= 100 && m <= 100)
         })
         .map(|press| match press {
             Some(p) => 3 * p.0 + p.1,
             None => 0,
         })
-        .sum()
+        .sum())
 }
 
-pub fn solve_2(input: &str) -> u64 {
-    let data = fs::read_to_string(input).expect("Can't open file");
-    let binding = data.replace("\r\n", "\n");
-    let filtered = binding.as_str();
-    let re = Regex::new(
-        r"Button A: X\+(\d+), Y\+(\d+)\nButton B: X\+(\d+), Y\+(\d+)\nPrize: X=(\d+), Y=(\d+)",
-    )
-    .unwrap();
-
-    let mut eqs = vec![];
-    for caps in re.captures_iter(filtered) {
-        eqs.push(Equation {
-            a: (
-                caps[1].parse::<u64>().unwrap(),
-                caps[2].parse::<u64>().unwrap(),
-            ),
-            b: (
-                caps[3].parse::<u64>().unwrap(),
-                caps[4].parse::<u64>().unwrap(),
-            ),
-            target: (
-                caps[5].parse::<u64>().unwrap() + 10000000000000,
-                caps[6].parse::<u64>().unwrap() + 10000000000000,
-            ),
-        });
-    }
-
-    let presses: Vec<_> = eqs.iter().map(calculate).collect();
+pub fn part_2(input: &Input) -> Result<u64> {
+    let presses: Vec<_> = input
+        .machines
+        .iter()
+        .map(|eq| Equation {
+            target: (eq.target.0 + 10000000000000, eq.target.1 + 10000000000000),
+            ..*eq
+        })
+        .map(|eq| {
+            let press = calculate(&eq);
+            explain(&eq, press);
+            press
+        })
+        .collect();
 
     // Not filtering <= 100 presses anymore
-    presses
+    Ok(presses
         .iter()
         .map(|press| match press {
             Some(p) => 3 * p.0 + p.1,
             None => 0,
         })
-        .sum()
+        .sum())
+}
+
+/// `size` claw machines, about half of them with a way to the prize
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut machines = vec![];
+    for _ in 0..size {
+        let a = (rng.range(10..=99), rng.range(10..=99));
+        let b = (rng.range(10..=99), rng.range(10..=99));
+        let prize = if rng.chance(0.5) {
+            let (n, m) = (rng.range(1..=100), rng.range(1..=100));
+            (n * a.0 + m * b.0, n * a.1 + m * b.1)
+        } else {
+            (rng.range(1000..=20000), rng.range(1000..=20000))
+        };
+        machines.push(format!(
+            "Button A: X+{}, Y+{}\nButton B: X+{}, Y+{}\nPrize: X={}, Y={}\n",
+            a.0, a.1, b.0, b.1, prize.0, prize.1
+        ));
+    }
+    machines.join("\n")
+}
+
+pub fn solve_1(input: &str) -> Result<u64> {
+    let data = fs::read_to_string(input)?;
+    part_1(&parse(&data)?)
+}
+
+pub fn solve_2(input: &str) -> Result<u64> {
+    let data = fs::read_to_string(input)?;
+    part_2(&parse(&data)?)
+}
+
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    const DAY: u8 = 13;
+
+    type Input = Input;
+
+    fn parse(data: &str) -> Result<Self::Input> {
+        parse(data)
+    }
+
+    fn part_1(input: &Self::Input) -> Result<impl Display> {
+        part_1(input)
+    }
+
+    fn part_2(input: &Self::Input) -> Result<impl Display> {
+        part_2(input)
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        generate(rng, size)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    #[test]
-    fn part1() {
-        let result = solve_1("src/day13/test1.txt");
-        assert_eq!(result, 480);
-    }
 
     #[test]
-    fn part2() {
-        let result = solve_2("src/day13/test2.txt");
-        assert_eq!(result, 0);
+    fn negative_presses() {
+        // Solved by 2000000000094 presses of A and -1999999999955 of B
+        let input =
+            parse("Button A: X+30, Y+92\nButton B: X+25, Y+87\nPrize: X=3945, Y=12563\n").unwrap();
+        assert_eq!(part_2(&input).unwrap(), 0);
     }
 }
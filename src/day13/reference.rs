@@ -0,0 +1,86 @@
+//! Slow but simple answers, to check the floating point shortcut of `calculate` against
+
+use crate::error::Result;
+
+use super::{Equation, Input};
+
+/// Tries every number of presses up to 100 for both buttons
+pub fn part_1(input: &Input) -> Result<u64> {
+    Ok(input
+        .machines
+        .iter()
+        .filter_map(|eq| {
+            (0..=100)
+                .flat_map(|n| (0..=100).map(move |m| (n, m)))
+                .filter(|&(n, m)| {
+                    n * eq.a.0 + m * eq.b.0 == eq.target.0 && n * eq.a.1 + m * eq.b.1 == eq.target.1
+                })
+                .map(|(n, m)| 3 * n + m)
+                .min()
+        })
+        .sum())
+}
+
+/// Far too many presses to try them, solves the equations with exact integers instead
+pub fn part_2(input: &Input) -> Result<u64> {
+    Ok(input
+        .machines
+        .iter()
+        .filter_map(|eq| {
+            cheapest(&Equation {
+                target: (eq.target.0 + 10000000000000, eq.target.1 + 10000000000000),
+                ..*eq
+            })
+        })
+        .sum())
+}
+
+/// `(gcd, x, y)` with `a * x + b * y == gcd`
+fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    if b == 0 {
+        (a, 1, 0)
+    } else {
+        let (gcd, x, y) = extended_gcd(b, a % b);
+        (gcd, y, x - a / b * y)
+    }
+}
+
+fn cheapest(eq: &Equation) -> Option<u64> {
+    let (a0, a1) = (eq.a.0 as i128, eq.a.1 as i128);
+    let (b0, b1) = (eq.b.0 as i128, eq.b.1 as i128);
+    let (t0, t1) = (eq.target.0 as i128, eq.target.1 as i128);
+
+    let det = a0 * b1 - a1 * b0;
+    if det != 0 {
+        // A single solution, which has to be whole and not negative
+        let (n, m) = (b1 * t0 - b0 * t1, a0 * t1 - a1 * t0);
+        if n % det != 0 || m % det != 0 || n / det < 0 || m / det < 0 {
+            return None;
+        }
+        return Some((3 * n / det + m / det) as u64);
+    }
+
+    // Both buttons move the claw along the same line, which has to go through the prize
+    if a0 * t1 != a1 * t0 {
+        return None;
+    }
+    // All the whole solutions of n * a0 + m * b0 == t0 are n0 + k * db, m0 - k * da
+    let (gcd, x, y) = extended_gcd(a0, b0);
+    if t0 % gcd != 0 {
+        return None;
+    }
+    let (n0, m0) = (x * (t0 / gcd), y * (t0 / gcd));
+    let (da, db) = (a0 / gcd, b0 / gcd);
+    // Neither count can be negative, which limits k to a range
+    let low = (-n0).div_euclid(db) + i128::from((-n0).rem_euclid(db) != 0);
+    let high = m0.div_euclid(da);
+    if low > high {
+        return None;
+    }
+    // The cost changes by the same amount with every step of k, the cheapest is at one end
+    [low, high]
+        .into_iter()
+        .map(|k| 3 * (n0 + k * db) + (m0 - k * da))
+        .min()
+        .map(|cost| cost as u64)
+}
@@ -1,84 +1,131 @@
-use std::fs;
+//! Day 14: Restroom Redoubt
+//!
+//! ```
+//! use aoc2024::day14;
+//! use aoc2024::util::coord::Coord;
+//!
+//! let input = day14::parse(&std::fs::read_to_string("src/day14/test1.txt")?)?;
+//! assert_eq!(input.map(), day14::MAP);
+//! assert_eq!(day14::part_1(&input, Coord::new(7, 11))?, 12);
+//! # Ok::<(), aoc2024::error::Error>(())
+//! ```
 
-use regex::Regex;
+use std::{fmt::Display, fs};
 
-type Coord = (usize, usize);
-#[cfg(test)]
-const MAP: (usize, usize) = (7, 11);
-#[cfg(test)]
-const MID: (usize, usize) = (3, 5);
-#[cfg(not(test))]
-const MAP: (usize, usize) = (101, 103);
-#[cfg(not(test))]
-const MID: (usize, usize) = (50, 51);
-
-const MAX_STEPS: usize = MAP.0 * MAP.1; // 101 and 103 are both primes, their least common multiple is their product
-#[derive(Debug)]
+use crate::error::{Error, Result};
+use crate::solution::{Params, Solution};
+use crate::util::coord::Coord;
+use crate::util::grid::Grid;
+use crate::util::hook;
+use crate::util::input::lines;
+use crate::util::parse::{exactly, signed};
+use crate::util::random::Rng;
+use crate::util::trace::Fact;
+use crate::util::viz::{Color, Frame};
+
+/// Height and width of the bathroom of the real input, which the generated ones use too. The
+/// example's is only 11 wide and 7 tall, which its answers file gives.
+pub const MAP: Coord = Coord::new(103, 101);
+
+#[derive(Debug, Clone)]
 struct Robot {
     pos: Coord,
-    vel: (isize, isize),
+    vel: Coord,
 }
 
 impl Robot {
-    fn move_robot(&mut self, seconds: usize, map: (usize, usize)) {
-        let pos_x = self.pos.0 as isize + self.vel.0 * seconds as isize;
-        let pos_y = self.pos.1 as isize + self.vel.1 * seconds as isize;
+    fn move_robot(&mut self, seconds: isize, map: Coord) {
+        self.pos = (self.pos + self.vel * seconds).wrap(map);
+    }
+}
+
+pub struct Input {
+    robots: Vec<Robot>,
+    map: Coord,
+}
 
-        self.pos.0 = pos_x.rem_euclid(map.0 as isize) as usize;
-        self.pos.1 = pos_y.rem_euclid(map.1 as isize) as usize;
+impl Input {
+    /// Height and width of the bathroom, which the puzzle text gives but the input doesn't: `MAP`
+    /// unless the `width` and `height` parameters say otherwise
+    pub fn map(&self) -> Coord {
+        self.map
     }
 }
 
-pub fn solve_1(input: &str) -> u32 {
-    let data = fs::read_to_string(input).expect("Can't open file");
-    let re = Regex::new(r"p=(\d+),(\d+) v=(-?\d+),(-?\d+)").unwrap();
-    let mut robots = vec![];
-    for caps in re.captures_iter(data.as_str()) {
-        robots.push(Robot {
-            pos: (
-                caps[1].parse::<usize>().unwrap(),
-                caps[2].parse::<usize>().unwrap(),
-            ),
-            vel: (
-                caps[3].parse::<isize>().unwrap(),
-                caps[4].parse::<isize>().unwrap(),
-            ),
-        });
+fn parse_robots(data: &str) -> Result<Vec<Robot>> {
+    lines(data)
+        .map(|line| {
+            let [px, py, vx, vy] = exactly(&line, signed(&line)?)?;
+            if px < 0 || py < 0 {
+                // The position comes first, so its minus is the first one of the line
+                let column = line.text.find('-').map_or(1, |i| i + 1);
+                return Err(line.error(column, "a robot can't start at a negative position"));
+            }
+            // The input has them column first, x is the row like everywhere else
+            Ok(Robot {
+                pos: Coord::new(py, px),
+                vel: Coord::new(vy, vx),
+            })
+        })
+        .collect()
+}
+
+/// The robots in a bathroom of the given size, which has to have room for all of them
+fn bathroom(robots: Vec<Robot>, map: Coord) -> Result<Input> {
+    if map.x == 0 || map.y == 0 {
+        return Err(Error::invalid(format!(
+            "a {}x{} bathroom has no room for robots",
+            map.y, map.x
+        )));
     }
+    if let Some(robot) = robots
+        .iter()
+        .find(|robot| robot.pos.x >= map.x || robot.pos.y >= map.y)
+    {
+        return Err(Error::invalid(format!(
+            "the robot at {},{} is outside of the {}x{} bathroom",
+            robot.pos.y, robot.pos.x, map.y, map.x
+        )));
+    }
+    Ok(Input { robots, map })
+}
+
+pub fn parse(data: &str) -> Result<Input> {
+    bathroom(parse_robots(data)?, MAP)
+}
+
+pub fn part_1(input: &Input, map: Coord) -> Result<u32> {
+    let mut robots = input.robots.clone();
+    let mid = Coord::new(map.x / 2, map.y / 2);
 
     for r in &mut robots {
-        r.move_robot(100, MAP);
+        r.move_robot(100, map);
     }
 
     let mut quadrants = [0; 4];
     for r in robots {
-        if r.pos.0 == MID.0 || r.pos.1 == MID.1 {
+        if r.pos.x == mid.x || r.pos.y == mid.y {
             continue;
         }
         // turns out bool can be converted to usize
-        let top_bottom = (r.pos.0 < MID.0) as usize;
-        let left_right = (r.pos.1 < MID.1) as usize;
+        let top_bottom = (r.pos.x < mid.x) as usize;
+        let left_right = (r.pos.y < mid.y) as usize;
         quadrants[top_bottom * 2 + left_right] += 1;
     }
-    quadrants.iter().product()
+    hook::fact(|| {
+        Fact::new("quadrants")
+            .with("bottom_right", quadrants[0])
+            .with("bottom_left", quadrants[1])
+            .with("top_right", quadrants[2])
+            .with("top_left", quadrants[3])
+    });
+    Ok(quadrants.iter().product())
 }
 
-pub fn solve_2(input: &str) -> u32 {
-    let data = fs::read_to_string(input).expect("Can't open file");
-    let re = Regex::new(r"p=(\d+),(\d+) v=(-?\d+),(-?\d+)").unwrap();
-    let mut robots = vec![];
-    for caps in re.captures_iter(data.as_str()) {
-        robots.push(Robot {
-            pos: (
-                caps[1].parse::<usize>().unwrap(),
-                caps[2].parse::<usize>().unwrap(),
-            ),
-            vel: (
-                caps[3].parse::<isize>().unwrap(),
-                caps[4].parse::<isize>().unwrap(),
-            ),
-        });
-    }
+pub fn part_2(input: &Input, map: Coord) -> Result<u32> {
+    let mut robots = input.robots.clone();
+    // 103 and 101 are both primes, their least common multiple is their product
+    let max_steps = map.x * map.y;
 
     // Idea: If there's a valid picture, a lot of pixels are next to each other
     // i.e. search for the minimum entropy
@@ -86,11 +133,13 @@ pub fn solve_2(input: &str) -> u32 {
     let mut minimum_entropy = (0, usize::MAX); // (step, entropy)
 
     // of course, off-by-one error
-    for i in 1..=MAX_STEPS {
-        let mut field = vec![vec!['.'; MAP.0]; MAP.1];
+    for i in 1..=max_steps {
+        let mut field = vec![vec!['.'; map.y as usize]; map.x as usize];
         for r in &mut robots {
-            r.move_robot(1, MAP);
-            field[r.pos.1][r.pos.0] = 'X'; // I am indexing the other way around
+            r.move_robot(1, map);
+            // Always inside of the map after wrapping around it
+            let (x, y) = r.pos.to_usize().unwrap();
+            field[x][y] = 'X';
         }
 
         let field_str: Vec<u8> = field
@@ -101,23 +150,101 @@ pub fn solve_2(input: &str) -> u32 {
         if compressed.len() < minimum_entropy.1 {
             minimum_entropy.0 = i as u32;
             minimum_entropy.1 = compressed.len();
+            hook::fact(|| {
+                Fact::new("more orderly")
+                    .with("second", i)
+                    .with("compressed", compressed.len())
+            });
+            // The most orderly picture so far
+            hook::frame(|| {
+                let mut grid = Grid::new(map.x as usize, map.y as usize, '.');
+                for r in &robots {
+                    grid[r.pos] = 'X';
+                }
+                Frame::new(&grid, |&c| c)
+                    .with_title(format!("Second {i}, {} bytes compressed", compressed.len()))
+                    .color('X', Color::Green)
+            });
         }
     }
-    minimum_entropy.0
+    Ok(minimum_entropy.0)
+}
+
+/// `size` robots on the real map
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut text = String::new();
+    for _ in 0..size {
+        let (px, py) = (
+            rng.range(0..=MAP.y as i64 - 1),
+            rng.range(0..=MAP.x as i64 - 1),
+        );
+        let (vx, vy) = (rng.range(-99..=99), rng.range(-99..=99));
+        text += &format!("p={px},{py} v={vx},{vy}\n");
+    }
+    text
+}
+
+pub fn solve_1(input: &str, map: Coord) -> Result<u32> {
+    let data = fs::read_to_string(input)?;
+    part_1(&parse(&data)?, map)
+}
+
+pub fn solve_2(input: &str, map: Coord) -> Result<u32> {
+    let data = fs::read_to_string(input)?;
+    part_2(&parse(&data)?, map)
+}
+
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    const DAY: u8 = 14;
+
+    type Input = Input;
+
+    fn parse(data: &str) -> Result<Self::Input> {
+        parse(data)
+    }
+
+    fn parse_with(data: &str, params: &Params) -> Result<Self::Input> {
+        params.check(&["width", "height"])?;
+        let map = Coord::new(
+            params.get("height").map_or(MAP.x, |height| height as isize),
+            params.get("width").map_or(MAP.y, |width| width as isize),
+        );
+        bathroom(parse_robots(data)?, map)
+    }
+
+    fn part_1(input: &Self::Input) -> Result<impl Display> {
+        part_1(input, input.map)
+    }
+
+    fn part_2(input: &Self::Input) -> Result<impl Display> {
+        part_2(input, input.map)
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        generate(rng, size)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    #[test]
-    fn part1() {
-        let result = solve_1("src/day14/test1.txt");
-        assert_eq!(result, 12);
+
+    fn params(width: usize, height: usize) -> Params {
+        let mut params = Params::default();
+        params.set("width", width);
+        params.set("height", height);
+        params
     }
 
     #[test]
-    fn part2() {
-        let result = solve_2("src/day14/test2.txt");
-        assert_eq!(result, 0);
+    fn bathroom_size() {
+        let data = "p=0,4 v=3,-3\np=6,3 v=-1,-3\n";
+        assert!(Puzzle::parse_with(data, &params(11, 7)).is_ok());
+        assert!(Puzzle::parse_with(data, &params(0, 7)).is_err());
+        assert!(Puzzle::parse_with(data, &params(11, 0)).is_err());
+        assert!(Puzzle::parse_with(data, &params(6, 7)).is_err());
+        assert!(Puzzle::parse_with(data, &params(11, 4)).is_err());
     }
 }
@@ -1,5 +1,28 @@
-use aoc2024::util::coord::Coord;
-use std::{fmt::Debug, fs};
+//! Day 15: Warehouse Woes
+//!
+//! ```
+//! use aoc2024::day15;
+//!
+//! let input = day15::parse(&std::fs::read_to_string("src/day15/test1.txt")?)?;
+//! assert_eq!(day15::part_1(&input)?, 2028);
+//! # Ok::<(), aoc2024::error::Error>(())
+//! ```
+
+use crate::error::{Error, Result};
+use crate::solution::Solution;
+use crate::util::coord::Coord;
+use crate::util::direction::Direction4;
+use crate::util::grid::Grid;
+use crate::util::hook;
+use crate::util::input::sections;
+use crate::util::random::Rng;
+use crate::util::trace::Fact;
+use crate::util::viz::{Color, Frame};
+use itertools::Itertools;
+use std::{
+    fmt::{Debug, Display},
+    fs,
+};
 
 #[derive(Eq, PartialEq, Clone, Copy)]
 enum Object {
@@ -12,152 +35,75 @@ enum Object {
     BoxRight,
 }
 
-impl Debug for Object {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let c = match self {
-            Object::Wall => "#",     //"🧱",
-            Object::Box => "O",      //"📦",
-            Object::Robot => "@",    //"🤖",
-            Object::Floor => ".",    //"  ",
-            Object::BoxLeft => "[",  //"🟨",
-            Object::BoxRight => "]", //"🟨",
-        };
-        write!(f, "{c}")
-    }
-}
-
-enum Instruction {
-    Up,
-    Down,
-    Left,
-    Right,
-}
-
-impl Instruction {
-    fn to_coord(&self) -> (isize, isize) {
+impl Object {
+    fn to_char(self) -> char {
         match self {
-            Instruction::Up => (-1, 0),
-            Instruction::Down => (1, 0),
-            Instruction::Left => (0, -1),
-            Instruction::Right => (0, 1),
+            Object::Wall => '#',     //"🧱",
+            Object::Box => 'O',      //"📦",
+            Object::Robot => '@',    //"🤖",
+            Object::Floor => '.',    //"  ",
+            Object::BoxLeft => '[',  //"🟨",
+            Object::BoxRight => ']', //"🟨",
         }
     }
 }
 
-impl Debug for Instruction {
+impl Debug for Object {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let c = match self {
-            Instruction::Up => '▲',
-            Instruction::Down => '▼',
-            Instruction::Left => '◄',
-            Instruction::Right => '►',
-        };
-        write!(f, "{c}")
+        write!(f, "{}", self.to_char())
     }
 }
 
-fn handle_move(map: &mut [Vec<Object>], obj: &mut (usize, usize), instruction: &Instruction) {
-    let current_type = map[obj.0][obj.1];
+/// Frame of the warehouse after the robot tried to make its `step`th move
+fn show(map: &Grid<Object>, step: usize, instruction: Direction4) {
+    hook::frame(|| {
+        Frame::new(map, |&object| object.to_char())
+            .with_title(format!("Move {step}: {}", instruction.to_char()))
+            .color('#', Color::BrightBlack)
+            .color('O', Color::Yellow)
+            .color('[', Color::Yellow)
+            .color(']', Color::Yellow)
+            .color('@', Color::BrightGreen)
+    });
+}
+
+fn handle_move(map: &mut Grid<Object>, obj: &mut Coord, instruction: Direction4) {
+    let current_type = map[*obj];
 
     let mut possible_changes = vec![];
     let mut new_type = current_type;
     let mut coord = *obj;
-    match instruction {
-        Instruction::Up => {
-            while new_type != Object::Wall && new_type != Object::Floor {
-                possible_changes.push((map[coord.0][coord.1], coord));
-                coord = (coord.0 - 1, coord.1);
-                new_type = map[coord.0][coord.1];
-            }
-        }
-        Instruction::Down => {
-            while new_type != Object::Wall && new_type != Object::Floor {
-                possible_changes.push((map[coord.0][coord.1], coord));
-                coord = (coord.0 + 1, coord.1);
-                new_type = map[coord.0][coord.1];
-            }
-        }
-        Instruction::Left => {
-            while new_type != Object::Wall && new_type != Object::Floor {
-                possible_changes.push((map[coord.0][coord.1], coord));
-                coord = (coord.0, coord.1 - 1);
-                new_type = map[coord.0][coord.1];
-            }
-        }
-        Instruction::Right => {
-            while new_type != Object::Wall && new_type != Object::Floor {
-                possible_changes.push((map[coord.0][coord.1], coord));
-                coord = (coord.0, coord.1 + 1);
-                new_type = map[coord.0][coord.1];
-            }
-        }
+    while new_type != Object::Wall && new_type != Object::Floor {
+        possible_changes.push((map[coord], coord));
+        coord += instruction;
+        new_type = map[coord];
     }
     // Traverse the possible moves in reverse
-    for object in possible_changes.iter().rev() {
-        match instruction {
-            Instruction::Up => {
-                let next = (object.1 .0 - 1, object.1 .1); // this looks awful
-                if map[next.0][next.1] == Object::Floor {
-                    // Possible to move
-                    map[next.0][next.1] = object.0;
-                    map[object.1 .0][object.1 .1] = Object::Floor;
-                    if current_type == Object::Robot {
-                        *obj = next;
-                    }
-                }
-            }
-            Instruction::Down => {
-                let next = (object.1 .0 + 1, object.1 .1);
-                if map[next.0][next.1] == Object::Floor {
-                    // Possible to move
-                    map[next.0][next.1] = object.0;
-                    map[object.1 .0][object.1 .1] = Object::Floor;
-                    if current_type == Object::Robot {
-                        *obj = next;
-                    }
-                }
-            }
-            Instruction::Left => {
-                let next = (object.1 .0, object.1 .1 - 1);
-                if map[next.0][next.1] == Object::Floor {
-                    // Possible to move
-                    map[next.0][next.1] = object.0;
-                    map[object.1 .0][object.1 .1] = Object::Floor;
-                    if current_type == Object::Robot {
-                        *obj = next;
-                    }
-                }
-            }
-            Instruction::Right => {
-                let next = (object.1 .0, object.1 .1 + 1);
-                if map[next.0][next.1] == Object::Floor {
-                    // Possible to move
-                    map[next.0][next.1] = object.0;
-                    map[object.1 .0][object.1 .1] = Object::Floor;
-                    if current_type == Object::Robot {
-                        *obj = next;
-                    }
-                }
+    for &(object, from) in possible_changes.iter().rev() {
+        let next = from + instruction;
+        if map[next] == Object::Floor {
+            // Possible to move
+            map[next] = object;
+            map[from] = Object::Floor;
+            if current_type == Object::Robot {
+                *obj = next;
             }
         }
     }
 }
 
-fn handle_move_pt2(map: &mut [Vec<Object>], obj: &mut (usize, usize), instruction: &Instruction) {
-    let o = (obj.0 as isize, obj.1 as isize);
-    let coord = Coord::from_tuple(o);
-    let change = Coord::from_tuple(Instruction::to_coord(instruction));
-
-    let next_coord = coord + change;
+fn handle_move_pt2(map: &mut Grid<Object>, obj: &mut Coord, instruction: Direction4) {
+    let coord = *obj;
+    let next_coord = coord + instruction;
 
     // Slightly different approach due to the need to check two coordinates
 
-    match map[next_coord.x as usize][next_coord.y as usize] {
+    match map[next_coord] {
         Object::Floor => {
             // Just move
-            map[next_coord.x as usize][next_coord.y as usize] = Object::Robot;
-            map[obj.0][obj.1] = Object::Floor;
-            *obj = (next_coord.x as usize, next_coord.y as usize);
+            map[next_coord] = Object::Robot;
+            map[coord] = Object::Floor;
+            *obj = next_coord;
         }
         b @ Object::BoxLeft | b @ Object::BoxRight => {
             let mut movable = true;
@@ -165,67 +111,46 @@ fn handle_move_pt2(map: &mut [Vec<Object>], obj: &mut (usize, usize), instructio
 
             if b == Object::BoxLeft {
                 // Push the box part on the right
-                possible_changes.push(
-                    next_coord + Coord::from_tuple(Instruction::to_coord(&Instruction::Right)),
-                );
+                possible_changes.push(next_coord + Direction4::Right);
             } else {
                 // Push the box part on the left
-                possible_changes.push(
-                    next_coord + Coord::from_tuple(Instruction::to_coord(&Instruction::Left)),
-                );
+                possible_changes.push(next_coord + Direction4::Left);
             }
 
             match instruction {
-                Instruction::Up | Instruction::Down => {
+                Direction4::Up | Direction4::Down => {
                     let mut move_search = possible_changes.clone();
 
                     while move_search.len() > 1 {
                         let mut next_search = vec![];
                         // Checking for pairs
                         for m in move_search {
-                            let next = m + Coord::from_tuple(Instruction::to_coord(instruction));
+                            let next = m + instruction;
 
-                            match map[next.x as usize][next.y as usize] {
+                            match map[next] {
                                 Object::Wall => {
                                     // Can't move
                                     movable = false;
                                     next_search.clear();
                                 }
-                                b @ Object::BoxLeft | b @ Object::BoxRight => {
-                                    if !possible_changes.contains(&next) {
-                                        // Part of a box could've been already added
-                                        // See:
-                                        //       []
-                                        //      [][]  <- here, inner box parts
-                                        //       @
-                                        next_search.push(next);
-                                        possible_changes.push(next);
-                                        if b == Object::BoxLeft {
-                                            // Add right side
-                                            next_search.push(
-                                                next + Coord::from_tuple(Instruction::to_coord(
-                                                    &Instruction::Right,
-                                                )),
-                                            );
-                                            possible_changes.push(
-                                                next + Coord::from_tuple(Instruction::to_coord(
-                                                    &Instruction::Right,
-                                                )),
-                                            );
-                                        } else {
-                                            // Add left side
-                                            next_search.push(
-                                                next + Coord::from_tuple(Instruction::to_coord(
-                                                    &Instruction::Left,
-                                                )),
-                                            );
-                                            possible_changes.push(
-                                                next + Coord::from_tuple(Instruction::to_coord(
-                                                    &Instruction::Left,
-                                                )),
-                                            );
-                                        }
-                                    }
+                                // Part of a box could've been already added
+                                // See:
+                                //       []
+                                //      [][]  <- here, inner box parts
+                                //       @
+                                b @ Object::BoxLeft | b @ Object::BoxRight
+                                    if !possible_changes.contains(&next) =>
+                                {
+                                    next_search.push(next);
+                                    possible_changes.push(next);
+                                    // Add the other side
+                                    let other = if b == Object::BoxLeft {
+                                        next + Direction4::Right
+                                    } else {
+                                        next + Direction4::Left
+                                    };
+                                    next_search.push(other);
+                                    possible_changes.push(other);
                                 }
                                 _ => {}
                             }
@@ -234,21 +159,17 @@ fn handle_move_pt2(map: &mut [Vec<Object>], obj: &mut (usize, usize), instructio
                     }
                 }
 
-                Instruction::Left | Instruction::Right => {
+                Direction4::Left | Direction4::Right => {
                     // This is much easier, the same as pt1 (just the different approach)
                     // skip the first box because it's already added above
-                    let mut next = next_coord
-                        + Coord::from_tuple(Instruction::to_coord(instruction))
-                        + Coord::from_tuple(Instruction::to_coord(instruction));
+                    let mut next = next_coord + instruction + instruction;
 
-                    while [Object::BoxLeft, Object::BoxRight]
-                        .contains(&map[next.x as usize][next.y as usize])
-                    {
+                    while [Object::BoxLeft, Object::BoxRight].contains(&map[next]) {
                         possible_changes.push(next);
-                        next += Coord::from_tuple(Instruction::to_coord(instruction));
+                        next += instruction;
                     }
                     // Found the first non-box
-                    if map[next.x as usize][next.y as usize] != Object::Floor {
+                    if map[next] != Object::Floor {
                         movable = false;
                     }
                 }
@@ -256,211 +177,221 @@ fn handle_move_pt2(map: &mut [Vec<Object>], obj: &mut (usize, usize), instructio
             // Move the possible boxes / robot in reverse order
             if movable {
                 for &c in possible_changes.iter().rev() {
-                    let to = c + Coord::from_tuple(Instruction::to_coord(instruction));
-                    map[to.x as usize][to.y as usize] = map[c.x as usize][c.y as usize];
-                    map[c.x as usize][c.y as usize] = Object::Floor;
+                    map[c + instruction] = map[c];
+                    map[c] = Object::Floor;
                 }
-                *obj = (next_coord.x as usize, next_coord.y as usize);
+                *obj = next_coord;
             }
         }
         _ => {}
     }
 }
 
-pub fn solve_1(input: &str) -> u32 {
-    let data = fs::read_to_string(input).expect("Can't open file");
+pub struct Input {
+    map: Grid<Object>,
+    instructions: Vec<Direction4>,
+}
 
-    let mut robot = (0, 0);
-    let mut map: Vec<Vec<Object>> = data
+pub fn parse(data: &str) -> Result<Input> {
+    let [map, moves] = sections(data)[..] else {
+        return Err(Error::invalid(
+            "expected the map and the moves separated by a blank line",
+        ));
+    };
+
+    let map = Grid::parse(map.text, |c| match c {
+        '#' => Some(Object::Wall),
+        'O' => Some(Object::Box),
+        '@' => Some(Object::Robot),
+        '.' => Some(Object::Floor),
+        _ => None,
+    })?;
+
+    // Moves never check the bounds of the map, only the walls around it
+    let last = map.rows().saturating_sub(1);
+    for i in 0..map.rows() {
+        let mut row = map.row(i);
+        let walled = if i == 0 || i == last {
+            row.all(|obj| *obj == Object::Wall)
+        } else {
+            row.next() == Some(&Object::Wall) && row.last() == Some(&Object::Wall)
+        };
+        if !walled {
+            return Err(Error::parse(
+                i + 1,
+                1,
+                "the map has to be surrounded by walls",
+            ));
+        }
+    }
+    let robots = map.positions(&Object::Robot).count();
+    if robots != 1 {
+        return Err(Error::invalid(format!(
+            "expected exactly one robot, found {robots}"
+        )));
+    }
+
+    let instructions = moves
         .lines()
-        .enumerate()
-        .take_while(|(_, line)| !line.is_empty())
-        .map(|(row, line)| {
+        .map(|line| {
             line.chars()
-                .enumerate()
-                .map(|(col, c)| match c {
-                    '#' => Object::Wall,
-                    'O' => Object::Box,
-                    '@' => {
-                        robot = (row, col);
-                        Object::Robot
-                    }
-                    '.' => Object::Floor,
-                    _ => unreachable!("Invalid object"),
+                .map(|(col, c)| {
+                    // Only arrows, not the other ways of writing a direction
+                    Direction4::from_char(c)
+                        .filter(|direction| direction.to_char() == c)
+                        .ok_or_else(|| line.error(col, format!("unknown instruction '{c}'")))
                 })
-                .collect()
+                .collect::<Result<Vec<_>>>()
         })
-        .collect();
+        .flatten_ok()
+        .collect::<Result<_>>()?;
 
-    let instructions: Vec<Instruction> = data
-        .lines()
-        .skip_while(|line| !line.is_empty())
-        .skip(1)
-        .flat_map(|line| {
-            line.chars().map(|c| match c {
-                '<' => Instruction::Left,
-                '^' => Instruction::Up,
-                'v' => Instruction::Down,
-                '>' => Instruction::Right,
-                _ => unreachable!("Invalid instruction"),
-            })
-        })
-        .collect();
+    Ok(Input { map, instructions })
+}
 
-    'outer: for (i, row) in map.iter().enumerate() {
-        for (j, obj) in row.iter().enumerate() {
-            if *obj == Object::Robot {
-                robot = (i, j);
-                break 'outer;
-            }
-        }
-    }
+pub fn part_1(input: &Input) -> Result<u32> {
+    let mut map = input.map.clone();
+    // parse() made sure there's one
+    let mut robot = map.find(&Object::Robot).unwrap();
 
-    for i in instructions {
-        handle_move(&mut map, &mut robot, &i);
-
-        // Uncomment for visualization
-        //
-        //print!("{esc}c", esc = 27 as char); // clear screen
-        //std::thread::sleep(time::Duration::from_millis(50));
-        //for row in map {
-        //    for obj in row {
-        //        print!("{:?}", obj);
-        //    }
-        //    println!();
-        //}
+    for (step, &i) in input.instructions.iter().enumerate() {
+        handle_move(&mut map, &mut robot, i);
+        show(&map, step + 1, i);
     }
 
     let mut sum = 0;
-    for (i, row) in map.iter().enumerate() {
-        for (j, obj) in row.iter().enumerate() {
-            match obj {
-                Object::Box => sum += (100 * i + j) as u32,
-                _ => continue,
+    for (coord, obj) in map.iter() {
+        match obj {
+            Object::Box => {
+                hook::fact(|| {
+                    Fact::new("box")
+                        .with("at", coord)
+                        .with("gps", 100 * coord.x + coord.y)
+                });
+                sum += (100 * coord.x + coord.y) as u32
             }
+            _ => continue,
         }
     }
 
-    sum
+    Ok(sum)
 }
 
-pub fn solve_2(input: &str) -> u32 {
-    let data = fs::read_to_string(input).expect("Can't open file");
-    let mut robot = (0, 0);
-    let mut map: Vec<Vec<Object>> = Vec::new();
-    for (row, line) in data.lines().enumerate() {
-        map.push(Vec::new());
-        if line.is_empty() {
-            break;
-        }
-        for (col, c) in line.chars().enumerate() {
-            let curr = match c {
-                '#' => Object::Wall,
-                'O' => Object::Box,
-                '@' => {
-                    robot = (row, col);
-                    Object::Robot
-                }
-                '.' => Object::Floor,
-                _ => unreachable!("Invalid object"),
-            };
-            if curr == Object::Box {
-                map[row].push(Object::BoxLeft);
-                map[row].push(Object::BoxRight);
-            } else if curr == Object::Robot {
-                map[row].push(Object::Robot);
-                map[row].push(Object::Floor);
-            } else {
-                map[row].push(curr);
-                map[row].push(curr);
-            }
-        }
-    }
-
-    let instructions: Vec<Instruction> = data
-        .lines()
-        .skip_while(|line| !line.is_empty())
-        .skip(1)
-        .flat_map(|line| {
-            line.chars().map(|c| match c {
-                '<' => Instruction::Left,
-                '^' => Instruction::Up,
-                'v' => Instruction::Down,
-                '>' => Instruction::Right,
-                _ => unreachable!("Invalid instruction"),
-            })
+pub fn part_2(input: &Input) -> Result<u32> {
+    // Everything except the robot is twice as wide
+    let rows = (0..input.map.rows())
+        .map(|i| {
+            input
+                .map
+                .row(i)
+                .flat_map(|obj| match obj {
+                    Object::Box => [Object::BoxLeft, Object::BoxRight],
+                    Object::Robot => [Object::Robot, Object::Floor],
+                    other => [*other, *other],
+                })
+                .collect()
         })
         .collect();
+    let mut map = Grid::from_rows(rows)?;
+    let mut robot = map.find(&Object::Robot).unwrap();
+
+    for (step, &i) in input.instructions.iter().enumerate() {
+        handle_move_pt2(&mut map, &mut robot, i);
+        show(&map, step + 1, i);
+    }
 
-    'outer: for (i, row) in map.iter().enumerate() {
-        for (j, obj) in row.iter().enumerate() {
-            if *obj == Object::Robot {
-                robot = (i, j);
-                break 'outer;
+    let mut sum = 0;
+    for (coord, obj) in map.iter() {
+        match obj {
+            Object::Box | Object::BoxLeft => {
+                hook::fact(|| {
+                    Fact::new("box")
+                        .with("at", coord)
+                        .with("gps", 100 * coord.x + coord.y)
+                });
+                sum += (100 * coord.x + coord.y) as u32
             }
+            _ => continue,
         }
     }
 
-    //print!("{esc}c", esc = 27 as char); // clear screen
-    //for row in &map {
-    //    for obj in row {
-    //        print!("{:?}", obj);
-    //    }
-    //    println!();
-    //}
-
-    for i in instructions {
-        handle_move_pt2(&mut map, &mut robot, &i);
-
-        // Uncomment for visualization
-        //
-        //std::thread::sleep(time::Duration::from_millis(3000));
-        //print!("{esc}c", esc = 27 as char); // clear screen
-        //for row in &map {
-        //    for obj in row {
-        //        print!("{:?}", obj);
-        //    }
-        //    println!();
-        //}
-    }
+    Ok(sum)
+}
 
-    let mut sum = 0;
-    for (i, row) in map.iter().enumerate() {
-        for (j, obj) in row.iter().enumerate() {
-            match obj {
-                Object::Box | Object::BoxLeft => sum += (100 * i + j) as u32,
-                _ => continue,
-            }
+/// A `size` by `size` warehouse and `size` lines of moves
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let size = size.max(3);
+    let mut map = Grid::new(size, size, '#');
+    for (coord, tile) in map.iter_mut() {
+        let inside = (1..size - 1).contains(&(coord.x as usize))
+            && (1..size - 1).contains(&(coord.y as usize));
+        if inside && rng.chance(0.95) {
+            *tile = if rng.chance(0.25) { 'O' } else { '.' };
         }
     }
+    let robot = Coord::from_usize(
+        rng.range(1..=size as i64 - 2) as usize,
+        rng.range(1..=size as i64 - 2) as usize,
+    );
+    map[robot] = '@';
+
+    let mut text = format!("{map}\n\n");
+    for _ in 0..size {
+        text.extend((0..70).map(|_| rng.pick(&Direction4::ALL).to_char()));
+        text += "\n";
+    }
+    text
+}
 
-    sum
+pub fn solve_1(input: &str) -> Result<u32> {
+    let data = fs::read_to_string(input)?;
+    part_1(&parse(&data)?)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    #[test]
-    fn part1() {
-        let result = solve_1("src/day15/test1.txt");
-        assert_eq!(result, 2028);
+pub fn solve_2(input: &str) -> Result<u32> {
+    let data = fs::read_to_string(input)?;
+    part_2(&parse(&data)?)
+}
+
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    const DAY: u8 = 15;
+
+    type Input = Input;
+
+    fn parse(data: &str) -> Result<Self::Input> {
+        parse(data)
     }
 
-    #[test]
-    fn part1_2() {
-        let result = solve_1("src/day15/test2.txt");
-        assert_eq!(result, 10092);
+    fn part_1(input: &Self::Input) -> Result<impl Display> {
+        part_1(input)
     }
 
-    #[test]
-    fn part2() {
-        let result = solve_2("src/day15/test3.txt");
-        assert_eq!(result, 618);
+    fn part_2(input: &Self::Input) -> Result<impl Display> {
+        part_2(input)
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        generate(rng, size)
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
     #[test]
-    fn part2_2() {
-        let result = solve_2("src/day15/test2.txt");
-        assert_eq!(result, 9021);
+    fn malformed() {
+        let error = parse("####\n#@.#\n####\n\n<>x\n").err().unwrap();
+        assert_eq!(
+            error.to_string(),
+            "line 5, column 3: unknown instruction 'x'"
+        );
+        let error = parse("####\n#@..\n####\n\n<>\n").err().unwrap();
+        assert_eq!(
+            error.to_string(),
+            "line 2, column 1: the map has to be surrounded by walls"
+        );
     }
 }
@@ -1,11 +1,29 @@
-use std::cmp::{Ordering, Reverse};
+//! Day 16: Reindeer Maze
+//!
+//! ```
+//! use aoc2024::day16;
+//!
+//! let input = day16::parse(&std::fs::read_to_string("src/day16/test1.txt")?)?;
+//! assert_eq!(day16::part_1(&input)?, 7036);
+//! # Ok::<(), aoc2024::error::Error>(())
+//! ```
+
 // It's that time of the year
 // Hey Dijkstra
-use std::collections::{BinaryHeap, HashMap, HashSet};
-use std::fs;
-use std::ops::Add;
-
-use aoc2024::util::coord::Coord;
+use std::collections::HashSet;
+use std::{fmt::Display, fs};
+
+use crate::error::{Error, Result};
+use crate::solution::Solution;
+use crate::util::coord::Coord;
+use crate::util::direction::Direction4;
+use crate::util::grid::Grid;
+use crate::util::hook;
+use crate::util::random;
+use crate::util::random::Rng;
+use crate::util::search::{self, Paths};
+use crate::util::trace::Fact;
+use crate::util::viz::{Color, Frame};
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 enum Object {
@@ -15,279 +33,167 @@ enum Object {
     Start,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
-}
-
-impl Direction {
-    fn turn_right(&self) -> Self {
-        match self {
-            Direction::Up => Direction::Right,
-            Direction::Down => Direction::Left,
-            Direction::Left => Direction::Up,
-            Direction::Right => Direction::Down,
-        }
-    }
-    fn turn_left(&self) -> Self {
-        match self {
-            Direction::Up => Direction::Left,
-            Direction::Down => Direction::Right,
-            Direction::Left => Direction::Down,
-            Direction::Right => Direction::Up,
-        }
-    }
-}
-
-#[derive(Clone, Eq, PartialEq)]
-struct Node {
-    cost: usize,
-    position: Coord,
-    dir: Direction,
-    tiles: Vec<Coord>,
-}
-
-impl PartialOrd for Node {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
-    }
-}
+/// Where the reindeer is and which way it's facing
+type Node = (Coord, Direction4);
 
-impl Ord for Node {
-    fn cmp(&self, other: &Self) -> Ordering {
-        self.cost.cmp(&other.cost)
-    }
+// Dijkstra's shortest path algorithm.
+fn shortest_paths(map: &Grid<Object>, start: Coord, direction: Direction4) -> Paths<Node, usize> {
+    search::dijkstra((start, direction), |&(position, dir): &Node| {
+        // Forward, or turn and move at once
+        [(dir, 1), (dir.turn_right(), 1001), (dir.turn_left(), 1001)]
+            .into_iter()
+            .map(move |(dir, cost)| ((position + dir, dir), cost))
+            .filter(|((next, _), _)| map[*next] != Object::Wall)
+    })
 }
 
-impl Add<Direction> for Coord {
-    type Output = Coord;
-
-    fn add(self, rhs: Direction) -> Self::Output {
-        match rhs {
-            Direction::Up => Coord {
-                x: self.x - 1,
-                y: self.y,
-            },
-            Direction::Down => Coord {
-                x: self.x + 1,
-                y: self.y,
-            },
-            Direction::Left => Coord {
-                x: self.x,
-                y: self.y - 1,
-            },
-            Direction::Right => Coord {
-                x: self.x,
-                y: self.y + 1,
-            },
-        }
-    }
+/// The cheapest way to the goal, from whichever direction, and the ways of reaching it that cheap
+fn cheapest(input: &Input, paths: &Paths<Node, usize>) -> Result<(usize, Vec<Node>)> {
+    let ends = Direction4::ALL.map(|dir| (input.goal, dir));
+    let min = ends
+        .iter()
+        .filter_map(|end| paths.cost(end))
+        .min()
+        .ok_or_else(|| Error::no_solution("the end tile can't be reached"))?;
+    let cheapest_ends = ends
+        .into_iter()
+        .filter(|end| paths.cost(end) == Some(min))
+        .collect::<Vec<_>>();
+    hook::fact(|| {
+        let facing = cheapest_ends
+            .iter()
+            .map(|(_, dir)| format!("{dir:?}"))
+            .collect::<Vec<_>>();
+        Fact::new("cheapest")
+            .with("score", min)
+            .with("facing", facing.join(" "))
+    });
+    Ok((min, cheapest_ends))
 }
 
-// Dijkstra's shortest path algorithm.
-fn shortest_path(
-    map: &[Vec<Object>],
+pub struct Input {
+    map: Grid<Object>,
     start: Coord,
     goal: Coord,
-    direction: Direction,
-) -> (usize, HashMap<usize, Vec<Vec<Coord>>>) {
-    let mut heap = BinaryHeap::new();
-    //let mut visited = HashSet::new();
-    let mut distances: HashMap<(Coord, Direction), usize> = HashMap::new();
-    let mut shortest = usize::MAX;
-    let mut all_tiles = HashMap::new();
-
-    // We're at start, with a zero cost
-    heap.push(Reverse(Node {
-        cost: 0,
-        position: start,
-        dir: direction,
-        tiles: vec![start],
-    }));
-
-    while let Some(Reverse(Node {
-        cost,
-        position,
-        dir,
-        tiles,
-    })) = heap.pop()
-    {
-        //visited.insert((position, dir));
-        if position == goal {
-            all_tiles
-                .entry(cost)
-                .and_modify(|t: &mut Vec<Vec<Coord>>| t.push(tiles.clone()))
-                .or_insert(vec![tiles.clone()]);
-            shortest = shortest.min(cost);
-            continue;
-        }
-
-        if let Some(known_cost) = distances.get(&(position, dir)) {
-            // Already found a better one
-            if cost > *known_cost {
-                continue;
-            }
-        }
-        distances
-            .entry((position, dir))
-            .and_modify(|c| *c = cost)
-            .or_insert(cost);
+}
 
-        let next_forward = position + dir;
-        let mut next_tiles = tiles.clone();
-        next_tiles.push(next_forward);
-        if map[next_forward.x as usize][next_forward.y as usize] != Object::Wall {
-            heap.push(Reverse(Node {
-                cost: cost + 1,
-                position: next_forward,
-                dir,
-                tiles: next_tiles,
-            }));
+pub fn parse(data: &str) -> Result<Input> {
+    let mut start = None;
+    let mut goal = None;
+    let map = Grid::parse_with(data, |coord, c| match c {
+        '#' => Some(Object::Wall),
+        '.' => Some(Object::Path),
+        'E' => {
+            goal = Some(coord);
+            Some(Object::Goal)
         }
-
-        let dir_right = dir.turn_right();
-        let next_right = position + dir_right;
-        next_tiles = tiles.clone();
-        next_tiles.push(next_right);
-        if map[next_right.x as usize][next_right.y as usize] != Object::Wall {
-            heap.push(Reverse(Node {
-                cost: cost + 1001,
-                position: next_right,
-                dir: dir_right,
-                tiles: next_tiles,
-            }));
+        'S' => {
+            start = Some(coord);
+            Some(Object::Start)
         }
-
-        let dir_left = dir.turn_left();
-        let next_left = position + dir_left;
-        next_tiles = tiles.clone();
-        next_tiles.push(next_left);
-        if map[next_left.x as usize][next_left.y as usize] != Object::Wall {
-            heap.push(Reverse(Node {
-                cost: cost + 1001,
-                position: next_left,
-                dir: dir_left,
-                tiles: next_tiles,
-            }));
+        _ => None,
+    })?;
+
+    // The search never checks the bounds of the map, only the walls around it
+    let last = map.rows().saturating_sub(1);
+    for i in 0..map.rows() {
+        let mut row = map.row(i);
+        let walled = if i == 0 || i == last {
+            row.all(|obj| *obj == Object::Wall)
+        } else {
+            row.next() == Some(&Object::Wall) && row.last() == Some(&Object::Wall)
+        };
+        if !walled {
+            return Err(Error::parse(
+                i + 1,
+                1,
+                "the maze has to be surrounded by walls",
+            ));
         }
     }
 
-    (shortest, all_tiles)
+    let start = start.ok_or_else(|| Error::invalid("the maze has no start tile"))?;
+    let goal = goal.ok_or_else(|| Error::invalid("the maze has no end tile"))?;
+    Ok(Input { map, start, goal })
 }
 
-pub fn solve_1(input: &str) -> usize {
-    let data = fs::read_to_string(input).expect("Can't open file");
-    let mut pos = Coord::from_tuple((0, 0));
-    let mut start = Coord::from_tuple((0, 0));
-    let mut goal = Coord::from_tuple((0, 0));
-    let map: Vec<Vec<Object>> = data
-        .lines()
-        .enumerate()
-        .map(|(x, line)| {
-            line.chars()
-                .enumerate()
-                .map(|(y, c)| match c {
-                    '#' => Object::Wall,
-                    '.' => Object::Path,
-                    'E' => {
-                        goal = Coord::from_tuple((x as isize, y as isize));
-                        Object::Goal
-                    }
-                    'S' => {
-                        pos = Coord::from_tuple((x as isize, y as isize));
-                        start = Coord::from_tuple((x as isize, y as isize));
-                        Object::Start
-                    }
-                    _ => unreachable!(),
-                })
-                .collect()
-        })
-        .collect();
-
-    let dir = Direction::Right;
-
-    let (min, _) = shortest_path(&map, start, goal, dir);
-    min
+pub fn part_1(input: &Input) -> Result<usize> {
+    let paths = shortest_paths(&input.map, input.start, Direction4::Right);
+    let (min, _) = cheapest(input, &paths)?;
+    Ok(min)
 }
 
-pub fn solve_2(input: &str) -> usize {
-    let data = fs::read_to_string(input).expect("Can't open file");
-    let mut pos = Coord::from_tuple((0, 0));
-    let mut start = Coord::from_tuple((0, 0));
-    let mut goal = Coord::from_tuple((0, 0));
-    let map: Vec<Vec<Object>> = data
-        .lines()
-        .enumerate()
-        .map(|(x, line)| {
-            line.chars()
-                .enumerate()
-                .map(|(y, c)| match c {
-                    '#' => Object::Wall,
-                    '.' => Object::Path,
-                    'E' => {
-                        goal = Coord::from_tuple((x as isize, y as isize));
-                        Object::Goal
-                    }
-                    'S' => {
-                        pos = Coord::from_tuple((x as isize, y as isize));
-                        start = Coord::from_tuple((x as isize, y as isize));
-                        Object::Start
-                    }
-                    _ => unreachable!(),
-                })
-                .collect()
-        })
+pub fn part_2(input: &Input) -> Result<usize> {
+    let paths = shortest_paths(&input.map, input.start, Direction4::Right);
+    let (_, ends) = cheapest(input, &paths)?;
+    // Tiles, no matter which way the reindeer was facing on them
+    let tiles: HashSet<Coord> = paths
+        .on_cheapest_paths(ends)
+        .into_iter()
+        .map(|(position, _)| position)
         .collect();
+    hook::fact(|| Fact::new("best paths").with("tiles", tiles.len()));
+    hook::frame(|| {
+        Frame::new(&input.map, |object| match object {
+            Object::Wall => '#',
+            Object::Path => '.',
+            Object::Goal => 'E',
+            Object::Start => 'S',
+        })
+        .with_title(format!("{} tiles on the best paths", tiles.len()))
+        .color('#', Color::BrightBlack)
+        .highlight(tiles.iter().copied(), Color::Green)
+    });
+    Ok(tiles.len())
+}
 
-    let dir = Direction::Right;
-
-    let (min, all_steps) = shortest_path(&map, start, goal, dir);
-    let mut sum_set = HashSet::new();
-    for (len, steps) in all_steps {
-        if len == min {
-            for s in steps {
-                for c in s {
-                    sum_set.insert(c);
-                }
-            }
+/// A `size` by `size` maze, with some loops so that there's more than one best path
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let maze = random::maze(rng, size.max(5), size.max(5));
+    let (rows, cols) = (maze.rows(), maze.cols());
+    let mut map = maze.map(|&floor| if floor { '.' } else { '#' });
+    for (coord, tile) in map.iter_mut() {
+        let (x, y) = (coord.x as usize, coord.y as usize);
+        let between_floors = (x % 2 == 1) != (y % 2 == 1);
+        if between_floors && x > 0 && y > 0 && x < rows - 1 && y < cols - 1 && rng.chance(0.05) {
+            *tile = '.';
         }
     }
-    sum_set.len()
+    map[Coord::from_usize(rows - 2, 1)] = 'S';
+    map[Coord::from_usize(1, cols - 2)] = 'E';
+    format!("{map}\n")
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    #[test]
-    fn part1() {
-        let result = solve_1("src/day16/test1.txt");
-        assert_eq!(result, 7036);
-    }
+pub fn solve_1(input: &str) -> Result<usize> {
+    let data = fs::read_to_string(input)?;
+    part_1(&parse(&data)?)
+}
+
+pub fn solve_2(input: &str) -> Result<usize> {
+    let data = fs::read_to_string(input)?;
+    part_2(&parse(&data)?)
+}
+
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    const DAY: u8 = 16;
+
+    type Input = Input;
 
-    #[test]
-    fn part1_2() {
-        let result = solve_1("src/day16/test2.txt");
-        assert_eq!(result, 11048);
+    fn parse(data: &str) -> Result<Self::Input> {
+        parse(data)
     }
 
-    #[test]
-    fn part1_3() {
-        let result = solve_1("src/day16/test3.txt");
-        assert_eq!(result, 21148);
+    fn part_1(input: &Self::Input) -> Result<impl Display> {
+        part_1(input)
     }
 
-    #[test]
-    fn part2() {
-        let result = solve_2("src/day16/test1.txt");
-        assert_eq!(result, 45);
+    fn part_2(input: &Self::Input) -> Result<impl Display> {
+        part_2(input)
     }
 
-    #[test]
-    fn part2_2() {
-        let result = solve_2("src/day16/test2.txt");
-        assert_eq!(result, 64);
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        generate(rng, size)
     }
 }
@@ -1,4 +1,25 @@
-use std::{collections::HashSet, fs};
+//! Day 17: Chronospatial Computer
+//!
+//! ```
+//! use aoc2024::day17;
+//!
+//! let input = day17::parse(&std::fs::read_to_string("src/day17/test1.txt")?)?;
+//! assert_eq!(day17::part_1(&input)?, "4,6,3,5,6,3,5,2,1,0");
+//! # Ok::<(), aoc2024::error::Error>(())
+//! ```
+
+use std::{collections::HashSet, fmt::Display, fs};
+
+use crate::error::{Error, Result};
+use crate::solution::Solution;
+use crate::util::hook;
+use crate::util::input::lines;
+use crate::util::parse::Record;
+use crate::util::random::Rng;
+use crate::util::trace::Fact;
+
+#[cfg(feature = "reference")]
+pub mod reference;
 
 #[repr(u8)]
 #[allow(non_camel_case_types)]
@@ -39,6 +60,13 @@ fn get_combo(operand: u8, reg_a: u64, reg_b: u64, reg_c: u64) -> u64 {
     }
 }
 
+/// `num` divided by 2 to the power of `op`, for the dv instructions. Nothing is left of it once
+/// the power doesn't fit in a register.
+fn divide(num: u64, op: u64) -> u64 {
+    num.checked_shr(op.try_into().unwrap_or(u32::MAX))
+        .unwrap_or(0)
+}
+
 fn handle_instruction(
     opcode: Instructions,
     operand: u8,
@@ -53,20 +81,17 @@ fn handle_instruction(
         Instructions::adv => {
             let num = *reg_a;
             let op = get_combo(operand, *reg_a, *reg_b, *reg_c);
-            let den = 2u64.pow(op as u32);
-            *reg_a = num / den;
+            *reg_a = divide(num, op);
         }
         Instructions::bdv => {
             let num = *reg_a;
             let op = get_combo(operand, *reg_a, *reg_b, *reg_c);
-            let den = 2u64.pow(op as u32);
-            *reg_b = num / den;
+            *reg_b = divide(num, op);
         }
         Instructions::cdv => {
             let num = *reg_a;
             let op = get_combo(operand, *reg_a, *reg_b, *reg_c);
-            let den = 2u64.pow(op as u32);
-            *reg_c = num / den;
+            *reg_c = divide(num, op);
         }
         Instructions::bxl => {
             *reg_b ^= operand as u64;
@@ -90,64 +115,102 @@ fn handle_instruction(
     new_ic
 }
 
-pub fn solve_1(input: &str) -> String {
-    let data = fs::read_to_string(input).expect("Can't open file");
-    let mut it = data.lines();
-    let mut s = it.next().unwrap();
-    let mut reg_a = s.split(": ").collect::<Vec<_>>()[1].parse::<u64>().unwrap();
-    s = it.next().unwrap();
-    let mut reg_b = s.split(": ").collect::<Vec<_>>()[1].parse::<u64>().unwrap();
-    s = it.next().unwrap();
-    let mut reg_c = s.split(": ").collect::<Vec<_>>()[1].parse::<u64>().unwrap();
-    it.next();
-    s = it.next().unwrap();
-    let inst_split = s.split(": ").collect::<Vec<_>>()[1];
-    let inst = inst_split
-        .split(',')
-        .map(|c| c.parse::<u8>().unwrap())
-        .collect::<Vec<_>>();
-    let mut out = vec![];
+/// How many instructions a program gets to run before we stop waiting for it to halt, the real
+/// ones take a few hundred at most
+const MAX_STEPS: usize = 1_000_000;
+
+/// Runs `program` from the start until it halts, and returns the registers it halts with
+fn execute(
+    program: &[u8],
+    registers: (u64, u64, u64),
+    out: &mut Vec<u8>,
+) -> Result<(u64, u64, u64)> {
+    let (mut reg_a, mut reg_b, mut reg_c) = registers;
     let mut ic = 0;
 
-    while ic < inst.len() {
+    // Also halts on an opcode without its operand, which a jump to the last one would read
+    for _ in 0..MAX_STEPS {
+        if ic + 1 >= program.len() {
+            return Ok((reg_a, reg_b, reg_c));
+        }
         ic = handle_instruction(
-            inst[ic].into(),
-            inst[ic + 1],
+            program[ic].into(),
+            program[ic + 1],
             &mut reg_a,
             &mut reg_b,
             &mut reg_c,
             ic,
-            &mut out,
-        )
+            out,
+        );
     }
+    Err(Error::no_solution("the program doesn't halt"))
+}
+
+pub struct Input {
+    reg_a: u64,
+    reg_b: u64,
+    reg_c: u64,
+    program: Vec<u8>,
+}
+
+pub fn parse(data: &str) -> Result<Input> {
+    let record = Record::parse(lines(data).filter(|line| !line.text.is_empty()), ":")?;
+    let (line, inst_split) = record.field("Program")?;
+    let program = inst_split
+        .split(',')
+        .map(|c| match line.parse::<u8>(c)? {
+            op @ 0..=7 => Ok(op),
+            _ => Err(line.error_at(c, format!("'{c}' is not a 3-bit number"))),
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    if program.len() % 2 != 0 {
+        return Err(line.error(line.text.len(), "the last instruction has no operand"));
+    }
+    for (i, pair) in program.chunks(2).enumerate() {
+        let combo = matches!(pair[0], 0 | 2 | 5 | 6 | 7);
+        if combo && pair[1] == 7 {
+            return Err(Error::invalid(format!(
+                "instruction {i} uses the reserved combo operand 7"
+            )));
+        }
+    }
+
+    Ok(Input {
+        reg_a: record.get("Register A")?,
+        reg_b: record.get("Register B")?,
+        reg_c: record.get("Register C")?,
+        program,
+    })
+}
+
+pub fn part_1(input: &Input) -> Result<String> {
+    let mut out = vec![];
+    let (reg_a, reg_b, reg_c) = execute(
+        &input.program,
+        (input.reg_a, input.reg_b, input.reg_c),
+        &mut out,
+    )?;
     // Some format!() magic might work with {:?}, out, but it could just be worse performance anyway
-    out.iter()
+    let output = out
+        .iter()
         .map(|c| c.to_string())
         .collect::<Vec<_>>()
-        .join(",")
+        .join(",");
+    hook::fact(|| {
+        Fact::new("halted")
+            .with("output", &output)
+            .with("a", reg_a)
+            .with("b", reg_b)
+            .with("c", reg_c)
+    });
+    Ok(output)
 }
 
-pub fn solve_2(input: &str) -> u64 {
-    let data = fs::read_to_string(input).expect("Can't open file");
-    let mut it = data.lines();
-    let mut s = it.next().unwrap();
-    let _ = s.split(": ").collect::<Vec<_>>()[1].parse::<u64>().unwrap(); // reg_a not used here
-    let mut reg_a: u64;
-    s = it.next().unwrap();
-    let mut reg_b = s.split(": ").collect::<Vec<_>>()[1].parse::<u64>().unwrap();
-    s = it.next().unwrap();
-    let mut reg_c = s.split(": ").collect::<Vec<_>>()[1].parse::<u64>().unwrap();
-    it.next();
-    s = it.next().unwrap();
-    let program = s.split(": ").collect::<Vec<_>>()[1];
-    let inst = program
-        .split(',')
-        .map(|c| c.parse::<u8>().unwrap())
-        .collect::<Vec<_>>();
+pub fn part_2(input: &Input) -> Result<u64> {
+    // The initial value of A is what we are looking for
+    let inst = &input.program;
     let mut out = vec![];
-    let mut ic = 0;
-    let reg_b_orig = reg_b;
-    let reg_c_orig = reg_c;
 
     // Due to that super-not-suspicious left shift by 3 bits, we can work our way in reverse order.
     // If we multiply by 8 (right shift by 3), we have three bits' worth of possible values for the
@@ -164,47 +227,135 @@ pub fn solve_2(input: &str) -> u64 {
 
         for a in &possible_as {
             let next = 8 * a; // shift by 3
-            for candidate in next..next + 8 {
-                reg_a = candidate;
-                while ic < inst.len() {
-                    ic = handle_instruction(
-                        inst[ic].into(),
-                        inst[ic + 1],
-                        &mut reg_a,
-                        &mut reg_b,
-                        &mut reg_c,
-                        ic,
-                        &mut out,
-                    )
-                }
-                if out[0] == *required_out {
+                              // A can't start with a 0, the output would be too short
+            for candidate in next.max(1)..next + 8 {
+                execute(inst, (candidate, input.reg_b, input.reg_c), &mut out)?;
+                if out.first() == Some(required_out) {
                     next_possible.insert(candidate);
                 }
-                // Reset registers
                 out.clear();
-                reg_b = reg_b_orig;
-                reg_c = reg_c_orig;
-                ic = 0;
             }
         }
+        hook::fact(|| {
+            Fact::new("outputs")
+                .with("digit", required_out)
+                .with("candidates", next_possible.len())
+        });
         possible_as = next_possible;
     }
 
-    *possible_as.iter().min().unwrap()
+    possible_as
+        .iter()
+        .min()
+        .copied()
+        .ok_or_else(|| Error::no_solution("the program can't output itself"))
+}
+
+/// A program like the real ones, which outputs itself for some A. Their loop has 16 numbers, a
+/// `size` below that leaves out instructions of it, down to the 10 of one that only XORs the
+/// octal digits of A, and below 10 it's the example's 6. The A of part 1 has as many octal digits
+/// as the program has numbers.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    loop {
+        let program = if size < 10 {
+            "0,3,5,4,3,0".to_string()
+        } else {
+            let mut mix = [format!("4,{}", rng.below(8)), format!("1,{}", rng.below(8))];
+            if rng.chance(0.5) {
+                mix.reverse();
+            }
+            let extra: Vec<String> = ["7,5".to_string()]
+                .into_iter()
+                .chain(mix)
+                .take((size.min(16) - 10) / 2)
+                .collect();
+            let k1 = rng.below(8);
+            let extra = extra
+                .iter()
+                .map(|inst| format!("{inst},"))
+                .collect::<String>();
+            format!("2,4,1,{k1},{extra}0,3,5,5,3,0")
+        };
+        let digits = program.split(',').count();
+        let reg_a = rng.next_u64() >> (64 - 3 * digits);
+        let text =
+            format!("Register A: {reg_a}\nRegister B: 0\nRegister C: 0\n\nProgram: {program}\n");
+        // Not every program has an A that makes it output itself
+        if parse(&text).and_then(|input| part_2(&input)).is_ok() {
+            return text;
+        }
+    }
+}
+
+pub fn solve_1(input: &str) -> Result<String> {
+    let data = fs::read_to_string(input)?;
+    part_1(&parse(&data)?)
+}
+
+pub fn solve_2(input: &str) -> Result<u64> {
+    let data = fs::read_to_string(input)?;
+    part_2(&parse(&data)?)
+}
+
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    const DAY: u8 = 17;
+
+    type Input = Input;
+
+    fn parse(data: &str) -> Result<Self::Input> {
+        parse(data)
+    }
+
+    fn part_1(input: &Self::Input) -> Result<impl Display> {
+        part_1(input)
+    }
+
+    fn part_2(input: &Self::Input) -> Result<impl Display> {
+        part_2(input)
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        generate(rng, size)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+
+    #[test]
+    fn part2_output_too_short() {
+        // A=14191461453922 outputs all of it but the last 0
+        let input = parse(
+            "Register A: 0\nRegister B: 0\nRegister C: 0\n\nProgram: 2,4,1,0,7,5,1,0,4,2,0,3,5,5,3,0\n",
+        )
+        .unwrap();
+        assert!(part_2(&input).is_err());
+    }
+
     #[test]
-    fn part1() {
-        let result = solve_1("src/day17/test1.txt");
-        assert_eq!(result, "4,6,3,5,6,3,5,2,1,0");
+    fn part1_edge_cases() {
+        // Jumps to the last number, which has no operand after it
+        let input =
+            parse("Register A: 1\nRegister B: 0\nRegister C: 0\n\nProgram: 5,4,3,3\n").unwrap();
+        assert_eq!(part_1(&input).unwrap(), "1");
+        // A divided by 2^100, and by 2^(2^40) which doesn't even fit in the shift
+        for b in ["100", "1099511627776"] {
+            let input = parse(&format!(
+                "Register A: 9\nRegister B: {b}\nRegister C: 0\n\nProgram: 0,5,5,4\n"
+            ))
+            .unwrap();
+            assert_eq!(part_1(&input).unwrap(), "0");
+        }
     }
 
     #[test]
-    fn part2() {
-        let result = solve_2("src/day17/test2.txt");
-        assert_eq!(result, 117440);
+    fn never_halts() {
+        // Jumps back to the start as long as A isn't 0, which nothing changes
+        let input = parse("Register A: 1\nRegister B: 0\nRegister C: 0\n\nProgram: 3,0\n").unwrap();
+        assert!(part_1(&input).is_err());
+        assert!(part_2(&input).is_err());
     }
 }
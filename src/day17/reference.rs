@@ -0,0 +1,64 @@
+//! Slow but simple answers: a separate interpreter, and trying every A in turn for part 2, which
+//! only works for short programs
+
+use crate::error::{Error, Result};
+
+use super::Input;
+
+/// Runs the program from the start, returning everything it outputs
+fn run(program: &[u8], mut a: u64, mut b: u64, mut c: u64) -> Vec<u8> {
+    let mut out = vec![];
+    let mut ip = 0;
+    while ip + 1 < program.len() {
+        let operand = program[ip + 1] as u64;
+        let combo = match operand {
+            0..=3 => operand,
+            4 => a,
+            5 => b,
+            _ => c,
+        };
+        // A divided by 2 to the power of the combo operand
+        let divided = a
+            .checked_shr(combo.try_into().unwrap_or(u32::MAX))
+            .unwrap_or(0);
+        match program[ip] {
+            0 => a = divided,
+            1 => b ^= operand,
+            2 => b = combo % 8,
+            3 if a != 0 => {
+                ip = operand as usize;
+                continue;
+            }
+            3 => {}
+            4 => b ^= c,
+            5 => out.push((combo % 8) as u8),
+            6 => b = divided,
+            _ => c = divided,
+        }
+        ip += 2;
+    }
+    out
+}
+
+pub fn part_1(input: &Input) -> Result<String> {
+    let out = run(&input.program, input.reg_a, input.reg_b, input.reg_c);
+    Ok(out.iter().map(u8::to_string).collect::<Vec<_>>().join(","))
+}
+
+/// Longest program to try every A for. An A that outputs a program has about an octal digit per
+/// number of it, so the 16 numbers of the real ones are far too many.
+const MAX_LEN: usize = 8;
+
+/// Tries every A from 1 up, until A has an octal digit more than the program has numbers
+pub fn part_2(input: &Input) -> Result<u64> {
+    let program = &input.program;
+    if program.len() > MAX_LEN {
+        return Err(Error::no_solution(format!(
+            "{} numbers are too many to try every A",
+            program.len()
+        )));
+    }
+    (1..8u64.pow(program.len() as u32))
+        .find(|&a| run(program, a, input.reg_b, input.reg_c) == *program)
+        .ok_or_else(|| Error::no_solution("the program can't output itself"))
+}
@@ -1,193 +1,230 @@
-use aoc2024::util::coord::Coord;
-#[allow(unused_imports)]
-use std::{fs, time};
-
-// These are inclusive
-#[cfg(test)]
-const SIZE: usize = 6;
-#[cfg(not(test))]
-const SIZE: usize = 70;
-
-fn astar(start: Coord, end: Coord, map: &[[bool; SIZE + 1]; SIZE + 1]) -> Option<Vec<Coord>> {
-    let mut open_set = vec![start];
-    let mut came_from = vec![vec![Coord::new(0, 0); SIZE + 1]; SIZE + 1];
-    let mut g_score = vec![vec![usize::MAX; SIZE + 1]; SIZE + 1];
-    let mut f_score = vec![vec![usize::MAX; SIZE + 1]; SIZE + 1];
-    g_score[start.x as usize][start.y as usize] = 0;
-    f_score[start.x as usize][start.y as usize] = start.distance(&end);
-
-    while !open_set.is_empty() {
-        let binding = open_set.clone();
-        let current = binding
-            .iter()
-            .min_by_key(|&coord| f_score[coord.x as usize][coord.y as usize])
-            .unwrap();
-        if *current == end {
-            let mut path = vec![*current];
-            let mut current = *current;
-            while current != start {
-                current = came_from[current.x as usize][current.y as usize];
-                path.push(current);
-            }
-            path.reverse();
-            return Some(path);
-        }
+//! Day 18: RAM Run
+//!
+//! ```
+//! use aoc2024::day18;
+//!
+//! let input = day18::parse(&std::fs::read_to_string("src/day18/test1.txt")?)?;
+//! assert_eq!((input.size(), input.fallen_bytes()), (6, day18::KILOBYTE));
+//! assert_eq!(day18::part_1(&input, 6, 12)?, 22);
+//! # Ok::<(), aoc2024::error::Error>(())
+//! ```
+
+use crate::error::{Error, Result};
+use crate::solution::{Params, Solution};
+use crate::util::coord::Coord;
+use crate::util::grid::Grid;
+use crate::util::hook;
+use crate::util::input::lines;
+use crate::util::random::Rng;
+use crate::util::search;
+use crate::util::trace::Fact;
+use crate::util::viz::{Color, Frame};
+use std::{fmt::Display, fs};
+
+pub const KILOBYTE: usize = 1024;
+
+fn astar(start: Coord, end: Coord, map: &Grid<bool>) -> Option<Vec<Coord>> {
+    let (path, _) = search::astar(
+        start,
+        |&current| {
+            map.neighbors(current)
+                .filter(|&neighbor| map[neighbor])
+                .map(|neighbor| (neighbor, 1))
+        },
+        |coord| coord.distance(&end),
+        |&coord| coord == end,
+    )?;
+    Some(path)
+}
 
-        open_set.retain(|&coord| coord != *current);
-        for neighbor in current.neighbors() {
-            if neighbor.x < 0
-                || neighbor.x > SIZE as isize
-                || neighbor.y < 0
-                || neighbor.y > SIZE as isize
-            {
-                continue;
-            }
-            if !map[neighbor.x as usize][neighbor.y as usize] {
-                continue;
-            }
-            let tentative_g_score = g_score[current.x as usize][current.y as usize] + 1;
-            if tentative_g_score < g_score[neighbor.x as usize][neighbor.y as usize] {
-                came_from[neighbor.x as usize][neighbor.y as usize] = *current;
-                g_score[neighbor.x as usize][neighbor.y as usize] = tentative_g_score;
-                f_score[neighbor.x as usize][neighbor.y as usize] =
-                    tentative_g_score + neighbor.distance(&end);
-                if !open_set.contains(&neighbor) {
-                    open_set.push(neighbor);
-                }
-            }
-        }
+fn never_cut_off() -> Error {
+    Error::no_solution("the exit is never cut off")
+}
+
+pub struct Input {
+    bytes: Vec<(usize, usize)>,
+    size: usize,
+    fallen_bytes: usize,
+}
+
+impl Input {
+    /// Largest coordinate of the memory space, 70 for the real one and 6 for the example. The
+    /// puzzle text gives it, the input only has the bytes, so this is the largest one they use.
+    pub fn size(&self) -> usize {
+        self.size
     }
 
-    None
+    /// How many bytes have fallen in part 1, a kilobyte for the real input. The example only
+    /// looks at the first 12, which its answers file gives.
+    pub fn fallen_bytes(&self) -> usize {
+        self.fallen_bytes
+    }
 }
 
-pub fn solve_1(input: &str) -> usize {
-    #[cfg(test)]
-    const FALLEN_BYTES: usize = 12;
-    #[cfg(not(test))]
-    const FALLEN_BYTES: usize = 1024;
-    let data = fs::read_to_string(input).expect("Can't open file");
-    let mut map = [[true; SIZE + 1]; SIZE + 1];
-    for line in data.lines().take(FALLEN_BYTES) {
-        let coords: Vec<&str> = line.split(',').collect();
-        let (x, y) = (
-            coords[0].parse::<usize>().unwrap(),
-            coords[1].parse::<usize>().unwrap(),
-        );
+pub fn parse(data: &str) -> Result<Input> {
+    let bytes = lines(data)
+        .map(|line| {
+            let (x, y) = line.split_once(",")?;
+            Ok((line.parse(x)?, line.parse(y)?))
+        })
+        .collect::<Result<Vec<(usize, usize)>>>()?;
+    let size = bytes.iter().map(|&(x, y)| x.max(y)).max().unwrap_or(0);
+    Ok(Input {
+        bytes,
+        size,
+        fallen_bytes: KILOBYTE,
+    })
+}
+
+/// Memory space after the first `fallen_bytes` bytes, checking that every byte falls inside of it
+fn corrupt(input: &Input, size: usize, fallen_bytes: usize) -> Result<Grid<bool>> {
+    if let Some((x, y)) = input.bytes.iter().find(|(x, y)| *x > size || *y > size) {
+        return Err(Error::invalid(format!(
+            "byte {x},{y} is outside of the memory space"
+        )));
+    }
+    let mut map = Grid::new(size + 1, size + 1, true);
+    for &(x, y) in input.bytes.iter().take(fallen_bytes) {
         // The given coordinates are y,x in our case
-        map[y][x] = false;
+        map[Coord::new(y as isize, x as isize)] = false;
     }
+    Ok(map)
+}
+
+/// Frame of the memory space after the byte at `fallen` fell, with the last path found
+fn show(map: &Grid<bool>, path: &[Coord], fallen: Coord) {
+    hook::frame(|| {
+        Frame::new(map, |&open| if open { '.' } else { '#' })
+            .with_title(format!("Byte fell at {},{}", fallen.y, fallen.x))
+            .color('.', Color::White)
+            .color('#', Color::Red)
+            .highlight(path.iter().copied(), Color::Green)
+            .mark(fallen, '!', Color::Yellow)
+    });
+}
+
+pub fn part_1(input: &Input, size: usize, fallen_bytes: usize) -> Result<usize> {
+    let map = corrupt(input, size, fallen_bytes)?;
 
     let start = Coord::new(0, 0);
-    let end = Coord::new(SIZE as isize, SIZE as isize);
-    let path = astar(start, end, &map).unwrap();
+    let end = Coord::new(size as isize, size as isize);
+    let path =
+        astar(start, end, &map).ok_or_else(|| Error::no_solution("the exit can't be reached"))?;
 
-    path.len() - 1
+    hook::fact(|| Fact::new("exit").with("steps", path.len() - 1));
+    Ok(path.len() - 1)
 }
 
-pub fn solve_2(input: &str) -> (usize, usize) {
-    #[cfg(test)]
-    const FALLEN_BYTES: usize = 12;
-    #[cfg(not(test))]
-    const FALLEN_BYTES: usize = 1024;
-    let data = fs::read_to_string(input).expect("Can't open file");
-    let mut map = [[true; SIZE + 1]; SIZE + 1];
-    for line in data.lines().take(FALLEN_BYTES) {
-        let coords: Vec<&str> = line.split(',').collect();
-        let (x, y) = (
-            coords[0].parse::<usize>().unwrap(),
-            coords[1].parse::<usize>().unwrap(),
-        );
-        // The given coordinates are y,x in our case
-        map[y][x] = false;
-    }
+pub fn part_2(input: &Input, size: usize, fallen_bytes: usize) -> Result<(usize, usize)> {
+    let mut map = corrupt(input, size, fallen_bytes)?;
 
     let start = Coord::new(0, 0);
-    let end = Coord::new(SIZE as isize, SIZE as isize);
-    let mut lines = data.lines().skip(FALLEN_BYTES);
-    let mut next_line: Vec<&str>;
+    let end = Coord::new(size as isize, size as isize);
+    let mut bytes = input.bytes.iter().skip(fallen_bytes);
     let (mut x, mut y) = (0, 0);
     let mut next_coord;
 
     while let Some(coords) = astar(start, end, &map) {
-        next_line = lines.next().unwrap().split(',').collect();
-        (x, y) = (
-            next_line[0].parse::<usize>().unwrap(),
-            next_line[1].parse::<usize>().unwrap(),
-        );
-        map[y][x] = false;
+        (x, y) = *bytes.next().ok_or_else(never_cut_off)?;
         next_coord = Coord::new(y as isize, x as isize);
+        map[next_coord] = false;
 
         while !coords.contains(&next_coord) {
             // Skip re-searching all paths if the fallen coordinate is not on our route
-            next_line = lines.next().unwrap().split(',').collect();
-            (x, y) = (
-                next_line[0].parse::<usize>().unwrap(),
-                next_line[1].parse::<usize>().unwrap(),
-            );
-            map[y][x] = false;
+            (x, y) = *bytes.next().ok_or_else(never_cut_off)?;
             next_coord = Coord::new(y as isize, x as isize);
+            map[next_coord] = false;
 
-            // Visualization
-            //std::thread::sleep(time::Duration::from_millis(2000));
-            //print!("{esc}c", esc = 27 as char); // clear screen
-            //for (yy, row) in map.iter().enumerate() {
-            //    for (xx, tile) in row.iter().enumerate() {
-            //        if yy == y && xx == x {
-            //            print!("\u{1b}[0;33m");
-            //            print!("!");
-            //        } else if coords.contains(&Coord::new(yy as isize, xx as isize)) {
-            //            print!("\u{1b}[0;32m");
-            //            print!("X");
-            //        } else if *tile {
-            //            print!("\u{1b}[0;37m");
-            //            print!(".");
-            //        } else {
-            //            print!("\u{1b}[0;31m");
-            //            print!("#");
-            //        }
-            //    }
-            //    println!();
-            //}
+            show(&map, &coords, next_coord);
         }
 
-        // Visualization
-        //std::thread::sleep(time::Duration::from_millis(2000));
-        //print!("{esc}c", esc = 27 as char); // clear screen
-        //for (yy, row) in map.iter().enumerate() {
-        //    for (xx, tile) in row.iter().enumerate() {
-        //        if yy == y && xx == x {
-        //            print!("\u{1b}[0;33m");
-        //            print!("!");
-        //        } else if coords.contains(&Coord::new(yy as isize, xx as isize)) {
-        //            print!("\u{1b}[0;32m");
-        //            print!("X");
-        //        } else if *tile {
-        //            print!("\u{1b}[0;37m");
-        //            print!(".");
-        //        } else {
-        //            print!("\u{1b}[0;31m");
-        //            print!("#");
-        //        }
-        //    }
-        //    println!();
-        //}
+        show(&map, &coords, next_coord);
     }
-    (x, y)
+    hook::fact(|| Fact::new("cut off").with("byte", format!("{x},{y}")));
+    Ok((x, y))
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    #[test]
-    fn part1() {
-        let result = solve_1("src/day18/test1.txt");
-        assert_eq!(result, 22);
+/// Bytes falling into a memory space with coordinates up to `size` (70 for the real one), which
+/// cut off the exit some time after the ones that fall in part 1
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    // The kilobyte of part 1 needs room next to the way, which a 33x33 memory space has
+    let size = size.max(32);
+    // A way from one corner to the other that stays open for part 1
+    let mut way = vec![Coord::new(0, 0)];
+    let mut current = Coord::new(0, 0);
+    while current != Coord::from_usize(size, size) {
+        let down = current.x < size as isize && (current.y == size as isize || rng.chance(0.5));
+        current += if down {
+            Coord::new(1, 0)
+        } else {
+            Coord::new(0, 1)
+        };
+        way.push(current);
+    }
+    let mut cells: Vec<Coord> = Grid::new(size + 1, size + 1, ())
+        .coords()
+        .filter(|coord| !way.contains(coord))
+        .collect();
+    rng.shuffle(&mut cells);
+    let mut bytes = cells.split_off(cells.len() - KILOBYTE);
+
+    // Then a whole diagonal, which no way can get around, in between others. It's one of the far
+    // ones, which reach the edges of the memory space, so that its size is the same when parsed.
+    let diagonal = rng.range(size as i64..=2 * size as i64 - 1) as isize;
+    let (mut cut, others): (Vec<Coord>, Vec<Coord>) = cells
+        .into_iter()
+        .chain(way.into_iter().filter(|coord| coord.x > 0 || coord.y > 0))
+        .filter(|&coord| coord != Coord::from_usize(size, size))
+        .partition(|coord| coord.x + coord.y == diagonal);
+    cut.extend(others.into_iter().take(size));
+    rng.shuffle(&mut cut);
+    bytes.extend(cut);
+
+    bytes
+        .iter()
+        .map(|coord| format!("{},{}\n", coord.y, coord.x))
+        .collect()
+}
+
+pub fn solve_1(input: &str, size: usize, fallen_bytes: usize) -> Result<usize> {
+    let data = fs::read_to_string(input)?;
+    part_1(&parse(&data)?, size, fallen_bytes)
+}
+
+pub fn solve_2(input: &str, size: usize, fallen_bytes: usize) -> Result<(usize, usize)> {
+    let data = fs::read_to_string(input)?;
+    part_2(&parse(&data)?, size, fallen_bytes)
+}
+
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    const DAY: u8 = 18;
+
+    type Input = Input;
+
+    fn parse(data: &str) -> Result<Self::Input> {
+        parse(data)
+    }
+
+    fn parse_with(data: &str, params: &Params) -> Result<Self::Input> {
+        params.check(&["size", "fallen_bytes"])?;
+        let mut input = parse(data)?;
+        input.size = params.get("size").unwrap_or(input.size);
+        input.fallen_bytes = params.get("fallen_bytes").unwrap_or(input.fallen_bytes);
+        Ok(input)
+    }
+
+    fn part_1(input: &Self::Input) -> Result<impl Display> {
+        part_1(input, input.size, input.fallen_bytes)
+    }
+
+    fn part_2(input: &Self::Input) -> Result<impl Display> {
+        let (x, y) = part_2(input, input.size, input.fallen_bytes)?;
+        Ok(format!("{x},{y}"))
     }
 
-    #[test]
-    fn part2() {
-        let result = solve_2("src/day18/test2.txt");
-        assert_eq!(result, (6, 1));
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        generate(rng, size)
     }
 }
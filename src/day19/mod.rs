@@ -1,4 +1,21 @@
-use std::{collections::HashMap, fs};
+//! Day 19: Linen Layout
+//!
+//! ```
+//! use aoc2024::day19;
+//!
+//! let input = day19::parse(&std::fs::read_to_string("src/day19/test1.txt")?)?;
+//! assert_eq!(day19::part_1(&input)?, 6);
+//! # Ok::<(), aoc2024::error::Error>(())
+//! ```
+
+use std::{collections::HashMap, fmt::Display, fs};
+
+use crate::error::{Error, Result};
+use crate::solution::Solution;
+use crate::util::hook;
+use crate::util::input::sections;
+use crate::util::random::Rng;
+use crate::util::trace::Fact;
 
 fn possible_designs<'a>(
     pattern: &'a str,
@@ -27,49 +44,140 @@ fn possible_designs<'a>(
     sum
 }
 
-pub fn solve_1(input: &str) -> u64 {
-    let data = fs::read_to_string(input).expect("Can't open file");
-    let (towels_str, designs_str) = data.split_once("\r\n\r\n").unwrap();
-    let towels: Vec<&str> = towels_str.split(", ").collect();
-    let designs: Vec<&str> = designs_str.lines().collect();
+pub struct Input {
+    towels: Vec<String>,
+    designs: Vec<String>,
+}
+
+pub fn parse(data: &str) -> Result<Input> {
+    let [towels, designs] = sections(data)[..] else {
+        return Err(Error::invalid(
+            "expected a blank line between towels and designs",
+        ));
+    };
+    let towels: Vec<String> = towels.text.split(", ").map(String::from).collect();
+    // An empty towel would match forever
+    if let Some(i) = towels.iter().position(String::is_empty) {
+        return Err(Error::invalid(format!("towel {} is empty", i + 1)));
+    }
+    let designs = designs.lines().map(|line| line.text.to_string()).collect();
+    Ok(Input { towels, designs })
+}
+
+pub fn part_1(input: &Input) -> Result<u64> {
+    let towels: Vec<&str> = input.towels.iter().map(String::as_str).collect();
     let mut cache = HashMap::new();
 
     // Iterate through all wanted designs
     // Filter out anything that's not possible
     // For pt1, we simply count how many designs are possible
-    designs
+    Ok(input
+        .designs
         .iter()
-        .filter(|design| possible_designs(design, &towels, &mut cache) != 0)
-        .count() as u64
+        .filter(|design| explained(design, possible_designs(design, &towels, &mut cache)) != 0)
+        .count() as u64)
 }
 
-pub fn solve_2(input: &str) -> u64 {
-    let data = fs::read_to_string(input).expect("Can't open file");
-    let (towels_str, designs_str) = data.split_once("\r\n\r\n").unwrap();
-    let towels: Vec<&str> = towels_str.split(", ").collect();
-    let designs: Vec<&str> = designs_str.lines().collect();
+pub fn part_2(input: &Input) -> Result<u64> {
+    let towels: Vec<&str> = input.towels.iter().map(String::as_str).collect();
     let mut cache = HashMap::new();
 
     // Iterate through all wanted designs
     // For pt2, sum all possibilities
-    designs
+    Ok(input
+        .designs
         .iter()
-        .map(|design| possible_designs(design, &towels, &mut cache))
-        .sum()
+        .map(|design| explained(design, possible_designs(design, &towels, &mut cache)))
+        .sum())
+}
+
+/// The `ways` a design can be made, told to the observers
+fn explained(design: &str, ways: u64) -> u64 {
+    hook::fact(|| {
+        Fact::new("design")
+            .with("design", design)
+            .with("ways", ways)
+    });
+    ways
+}
+
+/// `size` designs and about `size / 4` towels, most of the designs possible
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    fn stripes(rng: &mut Rng, len: usize) -> String {
+        (0..len)
+            .map(|_| *rng.pick(&['w', 'u', 'b', 'r', 'g']))
+            .collect()
+    }
+
+    // Without one of the single stripes, not every design can be made
+    let missing = rng.pick(&["w", "u", "b", "r", "g"]).to_string();
+    let mut towels: Vec<String> = vec![];
+    while towels.len() < (size / 4).max(3) {
+        let len = rng.range(1..=8) as usize;
+        let towel = stripes(rng, len);
+        if towel != missing && !towels.contains(&towel) {
+            towels.push(towel);
+        }
+    }
+
+    let mut designs = vec![];
+    for _ in 0..size.max(1) {
+        let len = rng.range(10..=40) as usize;
+        let mut design = String::new();
+        if rng.chance(0.7) {
+            while design.len() < len {
+                design += rng.pick(&towels).as_str();
+            }
+        } else {
+            design = stripes(rng, len);
+        }
+        designs.push(design);
+    }
+    format!("{}\n\n{}\n", towels.join(", "), designs.join("\n"))
+}
+
+pub fn solve_1(input: &str) -> Result<u64> {
+    let data = fs::read_to_string(input)?;
+    part_1(&parse(&data)?)
+}
+
+pub fn solve_2(input: &str) -> Result<u64> {
+    let data = fs::read_to_string(input)?;
+    part_2(&parse(&data)?)
+}
+
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    const DAY: u8 = 19;
+
+    type Input = Input;
+
+    fn parse(data: &str) -> Result<Self::Input> {
+        parse(data)
+    }
+
+    fn part_1(input: &Self::Input) -> Result<impl Display> {
+        part_1(input)
+    }
+
+    fn part_2(input: &Self::Input) -> Result<impl Display> {
+        part_2(input)
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        generate(rng, size)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    #[test]
-    fn part1() {
-        let result = solve_1("src/day19/test1.txt");
-        assert_eq!(result, 6);
-    }
 
     #[test]
-    fn part2() {
-        let result = solve_2("src/day19/test2.txt");
-        assert_eq!(result, 0);
+    fn crlf() {
+        let data = fs::read_to_string("src/day19/test1.txt").unwrap();
+        let input = parse(&data.replace('\n', "\r\n")).unwrap();
+        assert_eq!(part_1(&input).unwrap(), 6);
     }
 }
@@ -1,65 +1,187 @@
-use std::fs;
+//! Day 2: Red-Nosed Reports
+//!
+//! ```
+//! use aoc2024::day2;
+//!
+//! let input = day2::parse(&std::fs::read_to_string("src/day2/test1.txt")?)?;
+//! assert_eq!(day2::part_1(&input)?, 2);
+//! # Ok::<(), aoc2024::error::Error>(())
+//! ```
+
+use std::{fmt::Display, fs, io::BufRead};
+
+use crate::error::Result;
+use crate::solution::Solution;
+use crate::util::hook;
+use crate::util::input::{lines, stream_lines};
+use crate::util::parse::list;
+use crate::util::random::Rng;
+use crate::util::trace::Fact;
 
 // todo: find a better name -.-
 
 fn is_safe(levels: &[u32]) -> bool {
-    let is_increasing = levels[0] < levels[1];
+    let [first, second, ..] = levels else {
+        return true;
+    };
+    let is_increasing = first < second;
     levels[..]
         .windows(2)
         .all(|w| w[0] != w[1] && w[0].abs_diff(w[1]) <= 3 && (w[0] < w[1]) == is_increasing)
 }
 
-pub fn solve_1(input: &str) -> u32 {
-    let data = fs::read_to_string(input).expect("Can't open file");
+/// The levels of a report joined with spaces, like in the puzzle text
+fn show(levels: &[u32]) -> String {
+    levels
+        .iter()
+        .map(u32::to_string)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Safe, or safe with one of the levels left out
+fn is_safe_dampened(levels: &[u32]) -> bool {
+    // The OG function
+    is_safe(levels)
+    // Or brute force through removing an index and checking that variant
+        || (0..levels.len()).any(|i| {
+            let mut variant = levels.to_vec();
+            variant.remove(i);
+            is_safe(&variant[..])}
+        )
+}
+
+pub struct Input {
+    reports: Vec<Vec<u32>>,
+}
+
+pub fn parse(data: &str) -> Result<Input> {
+    let reports = lines(data)
+        .map(|line| list(&line, line.text, " "))
+        .collect::<Result<_>>()?;
+    Ok(Input { reports })
+}
+
+/// Tells the observers if the report is safe, with and without the Problem Dampener, whichever
+/// part asks: the facts are the same for both parts and for `solve_reader`
+fn explain(levels: &[u32]) {
+    hook::fact(|| {
+        Fact::new("report")
+            .with("levels", show(levels))
+            .with("safe", is_safe(levels))
+            .with("safe_dampened", is_safe_dampened(levels))
+    });
+}
 
+pub fn part_1(input: &Input) -> Result<u32> {
     let mut safe_reports = 0;
 
-    for line in data.lines() {
-        let levels: Vec<u32> = line
-            .split_ascii_whitespace()
-            .map(|s| s.parse::<u32>().unwrap())
-            .collect();
-        if is_safe(&levels[..]) {
+    for levels in &input.reports {
+        let safe = is_safe(&levels[..]);
+        explain(levels);
+        if safe {
             safe_reports += 1;
         }
     }
 
-    safe_reports
+    Ok(safe_reports)
 }
-pub fn solve_2(input: &str) -> u32 {
-    let data = fs::read_to_string(input).expect("Can't open file");
-
-    // The same as in solve_1, but a bit less readable
-    data.lines()
-        .filter(|line| {
-            let levels = line
-                .split_ascii_whitespace()
-                .map(|s| s.parse::<u32>().unwrap())
-                .collect::<Vec<u32>>();
-            // The OG function
-            is_safe(&levels[..])
-            // Or brute force through removing an index and checking that variant
-                || (0..levels.len()).any(|i| {
-                    let mut variant = levels.clone();
-                    variant.remove(i);
-                    is_safe(&variant[..])}
-                )
+
+pub fn part_2(input: &Input) -> Result<u32> {
+    // The same as in part_1, but a bit less readable
+    Ok(input
+        .reports
+        .iter()
+        .filter(|levels| {
+            let safe = is_safe_dampened(levels);
+            explain(levels);
+            safe
         })
-        .count() as u32
+        .count() as u32)
+}
+
+/// `size` reports, about half of them safe
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut text = String::new();
+    for _ in 0..size {
+        let step = if rng.chance(0.5) { 1 } else { -1 };
+        let mut level = rng.range(25..=75);
+        let mut levels: Vec<i64> = (0..rng.range(5..=8))
+            .map(|_| {
+                level += step * rng.range(1..=3);
+                level
+            })
+            .collect();
+        if rng.chance(0.5) {
+            let i = rng.below(levels.len());
+            levels[i] = rng.range(20..=80);
+        }
+        let levels: Vec<String> = levels.iter().map(i64::to_string).collect();
+        text += &(levels.join(" ") + "\n");
+    }
+    text
+}
+
+/// Both parts from `reader` a report at a time, only the one being checked is kept
+pub fn solve_reader(reader: impl BufRead) -> Result<(u32, u32)> {
+    let (mut safe, mut dampened) = (0, 0);
+    stream_lines(reader, |line| {
+        let levels: Vec<u32> = list(&line, line.text, " ")?;
+        explain(&levels);
+        safe += u32::from(is_safe(&levels));
+        dampened += u32::from(is_safe_dampened(&levels));
+        Ok(())
+    })?;
+    Ok((safe, dampened))
+}
+
+pub fn solve_1(input: &str) -> Result<u32> {
+    let data = fs::read_to_string(input)?;
+    part_1(&parse(&data)?)
+}
+
+pub fn solve_2(input: &str) -> Result<u32> {
+    let data = fs::read_to_string(input)?;
+    part_2(&parse(&data)?)
+}
+
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    const DAY: u8 = 2;
+
+    type Input = Input;
+
+    fn parse(data: &str) -> Result<Self::Input> {
+        parse(data)
+    }
+
+    fn part_1(input: &Self::Input) -> Result<impl Display> {
+        part_1(input)
+    }
+
+    fn part_2(input: &Self::Input) -> Result<impl Display> {
+        part_2(input)
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        generate(rng, size)
+    }
+
+    fn stream(reader: &mut dyn BufRead) -> Option<Result<(impl Display, impl Display)>> {
+        Some(solve_reader(reader))
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::io;
+
     use super::*;
-    #[test]
-    fn part1() {
-        let result = solve_1("src/day2/test1.txt");
-        assert_eq!(result, 2);
-    }
 
     #[test]
-    fn part2() {
-        let result = solve_2("src/day2/test2.txt");
-        assert_eq!(result, 4);
+    fn reader() {
+        let file = fs::File::open("src/day2/test1.txt").unwrap();
+        assert_eq!(solve_reader(io::BufReader::new(file)).unwrap(), (2, 4));
     }
 }
@@ -1,64 +1,48 @@
-use std::{collections::BTreeMap, fs, vec};
+//! Day 20: Race Condition
+//!
+//! ```
+//! use aoc2024::day20;
+//!
+//! let input = day20::parse(&std::fs::read_to_string("src/day20/test1.txt")?)?;
+//! assert_eq!(day20::part_1(&input)?, 0);
+//! # Ok::<(), aoc2024::error::Error>(())
+//! ```
+
+use std::{collections::BTreeMap, fmt::Display, fs};
+
+use crate::error::{Error, Result};
+use crate::solution::Solution;
+use crate::util::coord::Coord;
+use crate::util::grid::Grid;
+use crate::util::hook;
+use crate::util::random;
+use crate::util::random::Rng;
+use crate::util::search;
+use crate::util::trace::Fact;
+use crate::util::viz::{Color, Frame};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum TileType {
+enum TileType {
     Wall,
     Floor,
 }
 
-fn dfs(maze: &[Vec<TileType>], start: (usize, usize), end: (usize, usize)) -> Vec<(usize, usize)> {
-    let mut stack = vec![start];
-    let mut visited = vec![vec![false; maze[0].len()]; maze.len()];
-    visited[start.0][start.1] = true;
-    let mut path = vec![(start.0, start.1)];
-    while let Some(current) = stack.pop() {
-        if current == end {
-            // Visualization
-            //for row in maze {
-            //    for tile in row {
-            //        if path.contains(&(tile.coord.0, tile.coord.1)) {
-            //            print!("\u{1b}[0;33m");
-            //        } else {
-            //            print!("\u{1b}[0;31m");
-            //        }
-            //        print!(
-            //            "{:0>2} ",
-            //            path.iter()
-            //                .position(|x| x == &(tile.coord.0, tile.coord.1))
-            //                .unwrap_or(99)
-            //        );
-            //    }
-            //    println!();
-            //}
-            //print!("\u{1b}[0;37m");
-            return path;
-        }
-        for dir in &[(0, 1), (0, -1), (1, 0), (-1, 0)] {
-            let x = current.0 as i32 + dir.0;
-            let y = current.1 as i32 + dir.1;
-            let new_coord = (x, y);
-            if new_coord.0 < 0
-                || new_coord.0 >= maze.len() as i32
-                || new_coord.1 < 0
-                || new_coord.1 >= maze[0].len() as i32
-                || visited[new_coord.0 as usize][new_coord.1 as usize]
-                || maze[new_coord.0 as usize][new_coord.1 as usize] == TileType::Wall
-            {
-                continue;
-            }
-            visited[new_coord.0 as usize][new_coord.1 as usize] = true;
-            path.push((new_coord.0 as usize, new_coord.1 as usize));
-            stack.push((new_coord.0 as usize, new_coord.1 as usize));
-        }
+/// How many cheats save each number of picoseconds, the ones that count
+fn explain(shortcuts: &BTreeMap<u64, u64>) {
+    for (&saving, &count) in shortcuts.range(100..) {
+        hook::fact(|| {
+            Fact::new("cheats")
+                .with("saving", saving)
+                .with("count", count)
+        });
     }
-    unreachable!();
 }
 
-fn search_shortcuts(path: &[(usize, usize)], cheat_len: usize) -> BTreeMap<u64, u64> {
+fn search_shortcuts(path: &[Coord], cheat_len: usize) -> BTreeMap<u64, u64> {
     let mut shortcuts = BTreeMap::new();
     for (start_time, tile) in path.iter().enumerate() {
         for (end_time, t) in path.iter().enumerate().skip(start_time + cheat_len - 1) {
-            let manhattan_dist = tile.0.abs_diff(t.0) + tile.1.abs_diff(t.1);
+            let manhattan_dist = tile.distance(t);
             if manhattan_dist > cheat_len {
                 continue;
             }
@@ -77,96 +61,141 @@ fn search_shortcuts(path: &[(usize, usize)], cheat_len: usize) -> BTreeMap<u64,
     shortcuts
 }
 
-pub fn solve_1(input: &str) -> u64 {
-    let data = fs::read_to_string(input).expect("Can't open file");
-    let mut start = (0, 0);
-    let mut end = (0, 0);
-    let maze = data
-        .lines()
-        .enumerate()
-        .map(|(row, line)| {
-            line.chars()
-                .enumerate()
-                .map(|(col, c)| match c {
-                    '#' => TileType::Wall,
-                    '.' => TileType::Floor,
-                    'S' => {
-                        start = (row, col);
-                        TileType::Floor
-                    }
-                    'E' => {
-                        end = (row, col);
-                        TileType::Floor
-                    }
-                    _ => unreachable!(),
-                })
-                .collect::<Vec<TileType>>()
+pub struct Input {
+    maze: Grid<TileType>,
+    start: Coord,
+    end: Coord,
+}
+
+pub fn parse(data: &str) -> Result<Input> {
+    let mut start = None;
+    let mut end = None;
+    let maze = Grid::parse_with(data, |coord, c| match c {
+        '#' => Some(TileType::Wall),
+        '.' => Some(TileType::Floor),
+        'S' => {
+            start = Some(coord);
+            Some(TileType::Floor)
+        }
+        'E' => {
+            end = Some(coord);
+            Some(TileType::Floor)
+        }
+        _ => None,
+    })?;
+
+    let start = start.ok_or_else(|| Error::invalid("the maze has no start tile"))?;
+    let end = end.ok_or_else(|| Error::invalid("the maze has no end tile"))?;
+    Ok(Input { maze, start, end })
+}
+
+fn race_track(input: &Input) -> Result<Vec<Coord>> {
+    // There's only a single track, the shortest way is the way
+    let maze = &input.maze;
+    let path = search::bfs(
+        input.start,
+        |&current| {
+            maze.neighbors(current)
+                .filter(|&next| maze[next] != TileType::Wall)
+        },
+        |&current| current == input.end,
+    )
+    .ok_or_else(|| Error::no_solution("the end tile can't be reached"))?;
+    hook::frame(|| {
+        Frame::new(maze, |&tile| match tile {
+            TileType::Wall => '#',
+            TileType::Floor => '.',
         })
-        .collect::<Vec<Vec<TileType>>>();
+        .with_title(format!("{} picoseconds without cheating", path.len() - 1))
+        .color('#', Color::BrightBlack)
+        .highlight(path.iter().copied(), Color::Blue)
+        .mark(input.start, 'S', Color::BrightGreen)
+        .mark(input.end, 'E', Color::BrightRed)
+    });
+    Ok(path)
+}
 
+pub fn part_1(input: &Input) -> Result<u64> {
     // Build original route
-    let path = dfs(&maze, start, end);
+    let path = race_track(input)?;
 
     let shortcuts = search_shortcuts(&path, 2);
 
-    shortcuts
+    explain(&shortcuts);
+    Ok(shortcuts
         .iter()
         .filter(|(k, _)| **k >= 100)
         .map(|(_, v)| *v)
-        .sum()
+        .sum())
 }
 
-pub fn solve_2(input: &str) -> u64 {
-    let data = fs::read_to_string(input).expect("Can't open file");
-    let mut start = (0, 0);
-    let mut end = (0, 0);
-    let maze = data
-        .lines()
-        .enumerate()
-        .map(|(row, line)| {
-            line.chars()
-                .enumerate()
-                .map(|(col, c)| match c {
-                    '#' => TileType::Wall,
-                    '.' => TileType::Floor,
-                    'S' => {
-                        start = (row, col);
-                        TileType::Floor
-                    }
-                    'E' => {
-                        end = (row, col);
-                        TileType::Floor
-                    }
-                    _ => unreachable!(),
-                })
-                .collect::<Vec<TileType>>()
-        })
-        .collect::<Vec<Vec<TileType>>>();
-
+pub fn part_2(input: &Input) -> Result<u64> {
     // Build original route
-    let path = dfs(&maze, start, end);
+    let path = race_track(input)?;
 
     let shortcuts = search_shortcuts(&path, 20);
 
-    shortcuts
+    explain(&shortcuts);
+    Ok(shortcuts
         .iter()
         .filter(|(k, _)| **k >= 100)
         .map(|(_, v)| *v)
-        .sum()
+        .sum())
+}
+
+/// A `size` by `size` race track, a single way through a maze
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let maze = random::maze(rng, size.max(5), size.max(5));
+    let (start, end) = (
+        Coord::new(1, 1),
+        Coord::from_usize(maze.rows() - 2, maze.cols() - 2),
+    );
+    // The maze has exactly one way between any two tiles, everything off of it is a wall
+    let track = search::bfs(
+        start,
+        |&current| maze.neighbors(current).filter(|&next| maze[next]),
+        |&current| current == end,
+    )
+    .unwrap_or_default();
+    let mut map = maze.map(|_| '#');
+    for coord in track {
+        map[coord] = '.';
+    }
+    map[start] = 'S';
+    map[end] = 'E';
+    format!("{map}\n")
+}
+
+pub fn solve_1(input: &str) -> Result<u64> {
+    let data = fs::read_to_string(input)?;
+    part_1(&parse(&data)?)
+}
+
+pub fn solve_2(input: &str) -> Result<u64> {
+    let data = fs::read_to_string(input)?;
+    part_2(&parse(&data)?)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    #[test]
-    fn part1() {
-        let result = solve_1("src/day20/test1.txt");
-        assert_eq!(result, 0);
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    const DAY: u8 = 20;
+
+    type Input = Input;
+
+    fn parse(data: &str) -> Result<Self::Input> {
+        parse(data)
+    }
+
+    fn part_1(input: &Self::Input) -> Result<impl Display> {
+        part_1(input)
+    }
+
+    fn part_2(input: &Self::Input) -> Result<impl Display> {
+        part_2(input)
     }
 
-    #[test]
-    fn part2() {
-        let result = solve_2("src/day20/test1.txt");
-        assert_eq!(result, 0);
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        generate(rng, size)
     }
 }
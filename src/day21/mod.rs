@@ -1,6 +1,26 @@
-use std::{collections::HashMap, fs};
+//! Day 21: Keypad Conundrum
+//!
+//! ```
+//! use aoc2024::day21;
+//!
+//! let input = day21::parse(&std::fs::read_to_string("src/day21/test1.txt")?)?;
+//! assert_eq!(day21::part_1(&input)?, 126384);
+//! # Ok::<(), aoc2024::error::Error>(())
+//! ```
 
-fn numeric_to_dir(code: &[char]) -> &'static str {
+use std::{collections::HashMap, fmt::Display, fs};
+
+use crate::error::{Error, Result};
+use crate::solution::Solution;
+use crate::util::hook;
+use crate::util::input::lines;
+use crate::util::random::Rng;
+use crate::util::trace::Fact;
+
+#[cfg(feature = "reference")]
+pub mod reference;
+
+fn numeric_to_dir(code: &[char]) -> Option<&'static str> {
     // +---+---+---+
     // | 7 | 8 | 9 |
     // +---+---+---+
@@ -11,75 +31,75 @@ fn numeric_to_dir(code: &[char]) -> &'static str {
     //     | 0 | A |
     //     +---+---+
     //
-    // Only using possible directions for the test and my real input
+    // Only using possible directions for the test and my real input, the rest is None
     let prev = code[0];
     let next = code[1];
     match prev {
         'A' => match next {
-            'A' => "A",
-            '0' => "<A",
-            '1' => "^<<A",
-            '2' => "<^A",
-            '3' => "^A",
-            '4' => "^^<<A",
-            '9' => "^^^A",
-            _ => unreachable!(),
+            'A' => Some("A"),
+            '0' => Some("<A"),
+            '1' => Some("^<<A"),
+            '2' => Some("<^A"),
+            '3' => Some("^A"),
+            '4' => Some("^^<<A"),
+            '9' => Some("^^^A"),
+            _ => None,
         },
         '0' => match next {
-            'A' => ">A",
-            '2' => "^A",
-            _ => unreachable!(),
+            'A' => Some(">A"),
+            '2' => Some("^A"),
+            _ => None,
         },
         '1' => match next {
-            'A' => ">>vA",
-            '4' => "^A",
-            '7' => "^^A",
-            _ => unreachable!(),
+            'A' => Some(">>vA"),
+            '4' => Some("^A"),
+            '7' => Some("^^A"),
+            _ => None,
         },
         '2' => match next {
-            'A' => "v>A",
-            '8' => "^^A",
-            '9' => "^^>A",
-            _ => unreachable!(),
+            'A' => Some("v>A"),
+            '8' => Some("^^A"),
+            '9' => Some("^^>A"),
+            _ => None,
         },
         '3' => match next {
-            '4' => "<<^A",
-            '7' => "<<^^A",
-            _ => unreachable!(),
+            '4' => Some("<<^A"),
+            '7' => Some("<<^^A"),
+            _ => None,
         },
         '4' => match next {
-            '0' => ">vvA",
-            '1' => "vA",
-            '5' => ">A",
-            '8' => "^>A",
-            _ => unreachable!(),
+            '0' => Some(">vvA"),
+            '1' => Some("vA"),
+            '5' => Some(">A"),
+            '8' => Some("^>A"),
+            _ => None,
         },
         '5' => match next {
-            'A' => "vv>A",
-            '6' => ">A",
-            _ => unreachable!(),
+            'A' => Some("vv>A"),
+            '6' => Some(">A"),
+            _ => None,
         },
         '6' => match next {
-            '5' => "<A",
-            'A' => "vvA",
-            _ => unreachable!(),
+            '5' => Some("<A"),
+            'A' => Some("vvA"),
+            _ => None,
         },
         '7' => match next {
-            '9' => ">>A",
-            _ => unreachable!(),
+            '9' => Some(">>A"),
+            _ => None,
         },
         '8' => match next {
-            '0' => "vvvA",
-            '5' => "vA",
-            _ => unreachable!(),
+            '0' => Some("vvvA"),
+            '5' => Some("vA"),
+            _ => None,
         },
         '9' => match next {
-            'A' => "vvvA",
-            '6' => "vA",
-            '8' => "<A",
-            _ => unreachable!(),
+            'A' => Some("vvvA"),
+            '6' => Some("vA"),
+            '8' => Some("<A"),
+            _ => None,
         },
-        _ => unreachable!(),
+        _ => None,
     }
 }
 
@@ -138,18 +158,32 @@ fn dir_to_dir(code: &[char]) -> &'static str {
     }
 }
 
-pub fn solve_1(input: &str) -> u64 {
-    let mut sum = 0;
-    let data = fs::read_to_string(input).expect("Can't open file");
-    let codes = data
-        .lines()
-        .map(|l| {
-            let l2 = "A".to_string() + l;
-            l2.chars().collect()
+pub struct Input {
+    codes: Vec<Vec<char>>,
+}
+
+pub fn parse(data: &str) -> Result<Input> {
+    let codes = lines(data)
+        .map(|line| {
+            let digits = line.text.strip_suffix('A').unwrap_or(line.text);
+            if digits.is_empty() || digits.len() == line.text.len() {
+                return Err(line.error(
+                    1,
+                    format!("expected a code like 029A, found '{}'", line.text),
+                ));
+            }
+            line.parse::<u64>(digits)?;
+            let l2 = "A".to_string() + line.text;
+            Ok(l2.chars().collect())
         })
-        .collect::<Vec<Vec<char>>>();
+        .collect::<Result<Vec<Vec<char>>>>()?;
+    Ok(Input { codes })
+}
 
-    for code in codes {
+pub fn part_1(input: &Input) -> Result<u64> {
+    let mut sum = 0;
+
+    for code in &input.codes {
         // Parse the original number, without leading or trailing 'A's
         let mut code_str = code.iter().collect::<String>();
         code_str.remove(0);
@@ -159,8 +193,12 @@ pub fn solve_1(input: &str) -> u64 {
         let mut moves: HashMap<(char, char), u64> = HashMap::new();
         let dirs = code
             .windows(2)
-            .map(|w| numeric_to_dir(w))
-            .collect::<String>();
+            .map(|w| {
+                numeric_to_dir(w).ok_or_else(|| {
+                    Error::no_solution(format!("moving from {} to {} is not supported", w[0], w[1]))
+                })
+            })
+            .collect::<Result<String>>()?;
         let dirs_v = dirs.chars().collect::<Vec<char>>();
         // Add first layer of moves
         // Start from A, keep track of the first direction separately
@@ -202,22 +240,20 @@ pub fn solve_1(input: &str) -> u64 {
             .iter()
             .sum::<u64>();
         moves.clear();
+        hook::fact(|| {
+            Fact::new("code")
+                .with("code", code_num)
+                .with("presses", part_sum)
+                .with("complexity", part_sum * code_num)
+        });
         sum += part_sum * code_num;
     }
-    sum
+    Ok(sum)
 }
 
-pub fn solve_2(input: &str) -> u64 {
+pub fn part_2(input: &Input) -> Result<u64> {
     let mut sum = 0;
-    let data = fs::read_to_string(input).expect("Can't open file");
-    let codes = data
-        .lines()
-        .map(|l| {
-            let l2 = "A".to_string() + l;
-            l2.chars().collect()
-        })
-        .collect::<Vec<Vec<char>>>();
-    for code in codes {
+    for code in &input.codes {
         // Parse the original number, without leading or trailing 'A's
         let mut code_str = code.iter().collect::<String>();
         code_str.remove(0);
@@ -227,8 +263,12 @@ pub fn solve_2(input: &str) -> u64 {
         let mut moves: HashMap<(char, char), u64> = HashMap::new();
         let dirs = code
             .windows(2)
-            .map(|w| numeric_to_dir(w))
-            .collect::<String>();
+            .map(|w| {
+                numeric_to_dir(w).ok_or_else(|| {
+                    Error::no_solution(format!("moving from {} to {} is not supported", w[0], w[1]))
+                })
+            })
+            .collect::<Result<String>>()?;
         let dirs_v = dirs.chars().collect::<Vec<char>>();
         // Add first layer of moves
         // Start from A, keep track of the first direction separately
@@ -270,23 +310,61 @@ pub fn solve_2(input: &str) -> u64 {
             .iter()
             .sum::<u64>();
         moves.clear();
+        hook::fact(|| {
+            Fact::new("code")
+                .with("code", code_num)
+                .with("presses", part_sum)
+                .with("complexity", part_sum * code_num)
+        });
         sum += part_sum * code_num;
     }
-    sum
+    Ok(sum)
+}
+
+/// `size` door codes, the ones whose moves are known
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let known: Vec<String> = (0..1000)
+        .map(|code| format!("{code:03}A"))
+        .filter(|code| {
+            let code: Vec<char> = ("A".to_string() + code).chars().collect();
+            code.windows(2).all(|w| numeric_to_dir(w).is_some())
+        })
+        .collect();
+    (0..size.max(1))
+        .map(|_| rng.pick(&known).clone() + "\n")
+        .collect()
+}
+
+pub fn solve_1(input: &str) -> Result<u64> {
+    let data = fs::read_to_string(input)?;
+    part_1(&parse(&data)?)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    #[test]
-    fn part1() {
-        let result = solve_1("src/day21/test1.txt");
-        assert_eq!(result, 126384);
+pub fn solve_2(input: &str) -> Result<u64> {
+    let data = fs::read_to_string(input)?;
+    part_2(&parse(&data)?)
+}
+
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    const DAY: u8 = 21;
+
+    type Input = Input;
+
+    fn parse(data: &str) -> Result<Self::Input> {
+        parse(data)
+    }
+
+    fn part_1(input: &Self::Input) -> Result<impl Display> {
+        part_1(input)
+    }
+
+    fn part_2(input: &Self::Input) -> Result<impl Display> {
+        part_2(input)
     }
 
-    #[test]
-    fn part2() {
-        let result = solve_1("src/day21/test2.txt");
-        assert_eq!(result, 1972);
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        generate(rng, size)
     }
 }
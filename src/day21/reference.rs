@@ -0,0 +1,110 @@
+//! Slow but simple answers: instead of the hand-made tables of moves, every shortest way
+//! between two keys is tried on every keypad
+
+use std::collections::HashMap;
+
+use crate::error::Result;
+
+use super::Input;
+
+const NUMERIC: [&str; 4] = ["789", "456", "123", " 0A"];
+const DIRECTIONAL: [&str; 2] = [" ^A", "<v>"];
+
+fn position(keypad: &[&str], key: char) -> (i32, i32) {
+    keypad
+        .iter()
+        .enumerate()
+        .find_map(|(row, keys)| keys.find(key).map(|col| (row as i32, col as i32)))
+        .unwrap_or_else(|| panic!("no key '{key}'"))
+}
+
+/// Every order of the moves from one key to the other that doesn't cross the gap, each
+/// followed by pressing A
+fn ways(keypad: &[&str], from: char, to: char) -> Vec<String> {
+    let (start, end, gap) = (
+        position(keypad, from),
+        position(keypad, to),
+        position(keypad, ' '),
+    );
+    let mut ways = vec![];
+    let mut stack = vec![(start, String::new())];
+    while let Some((current, moves)) = stack.pop() {
+        if current == gap {
+            continue;
+        }
+        if current == end {
+            ways.push(moves + "A");
+            continue;
+        }
+        let (row, col) = current;
+        if row != end.0 {
+            let step = (end.0 - row).signum();
+            let arrow = if step > 0 { 'v' } else { '^' };
+            stack.push(((row + step, col), format!("{moves}{arrow}")));
+        }
+        if col != end.1 {
+            let step = (end.1 - col).signum();
+            let arrow = if step > 0 { '>' } else { '<' };
+            stack.push(((row, col + step), format!("{moves}{arrow}")));
+        }
+    }
+    ways
+}
+
+/// Fewest presses of the human to get `keys` pressed on a directional keypad by the last of
+/// `robots` robots, each moved by the keys the one before it presses (or the human, if none)
+fn presses(keys: &str, robots: usize, cache: &mut HashMap<(String, usize), u64>) -> u64 {
+    if robots == 0 {
+        return keys.len() as u64;
+    }
+    if let Some(&cached) = cache.get(&(keys.to_string(), robots)) {
+        return cached;
+    }
+    let mut total = 0;
+    let mut from = 'A';
+    for to in keys.chars() {
+        total += ways(&DIRECTIONAL, from, to)
+            .iter()
+            .map(|way| presses(way, robots - 1, cache))
+            .min()
+            .unwrap_or(0);
+        from = to;
+    }
+    cache.insert((keys.to_string(), robots), total);
+    total
+}
+
+fn complexities(input: &Input, robots: usize) -> u64 {
+    let mut cache = HashMap::new();
+    input
+        .codes
+        .iter()
+        .map(|code| {
+            // The codes start with the A the robot starts on
+            let number: u64 = code[1..code.len() - 1]
+                .iter()
+                .collect::<String>()
+                .parse()
+                .unwrap();
+            let length: u64 = code
+                .windows(2)
+                .map(|w| {
+                    ways(&NUMERIC, w[0], w[1])
+                        .iter()
+                        .map(|way| presses(way, robots, &mut cache))
+                        .min()
+                        .unwrap_or(0)
+                })
+                .sum();
+            number * length
+        })
+        .sum()
+}
+
+pub fn part_1(input: &Input) -> Result<u64> {
+    Ok(complexities(input, 2))
+}
+
+pub fn part_2(input: &Input) -> Result<u64> {
+    Ok(complexities(input, 25))
+}
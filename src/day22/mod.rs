@@ -1,8 +1,27 @@
+//! Day 22: Monkey Market
+//!
+//! ```
+//! use aoc2024::day22;
+//!
+//! let input = day22::parse(&std::fs::read_to_string("src/day22/test1.txt")?)?;
+//! assert_eq!(day22::part_1(&input)?, 37327623);
+//! # Ok::<(), aoc2024::error::Error>(())
+//! ```
+
 use std::{
     collections::{HashMap, HashSet},
+    fmt::Display,
     fs,
+    io::BufRead,
 };
 
+use crate::error::{Error, Result};
+use crate::solution::Solution;
+use crate::util::hook;
+use crate::util::input::{lines, stream_lines};
+use crate::util::random::Rng;
+use crate::util::trace::Fact;
+
 const PRUNE: u64 = 16777216;
 
 fn calculate_secrets(start: &u64, count: u64) -> u64 {
@@ -41,67 +60,152 @@ fn calculate_price(start: &mut u64) -> i8 {
     (price % 10) as i8 // Get last digit
 }
 
-pub fn solve_1(input: &str) -> u64 {
-    let data = fs::read_to_string(input).expect("Can't open file");
-    let initial_secrets = data
-        .lines()
-        .map(|line| line.parse::<u64>().unwrap())
-        .collect::<Vec<u64>>();
+/// Adds what the buyer starting with `secret` pays after every sequence of 4 price changes
+fn add_prices(mut secret: u64, price_map: &mut HashMap<(i8, i8, i8, i8), i64>) {
+    // Keep track of found sequences at new buyers
+    let mut sequence_found: HashSet<(i8, i8, i8, i8)> = HashSet::new();
+    // The first value is the initial secret's last digit
+    let start = (secret % 10) as i8;
+    let mut seq = (0..2000)
+        .map(|_| calculate_price(&mut secret))
+        .collect::<Vec<i8>>();
+    seq.insert(0, start);
+
+    for w in seq.windows(5) {
+        let diffs = (w[1] - w[0], w[2] - w[1], w[3] - w[2], w[4] - w[3]);
+        let price = w[4];
+        if sequence_found.insert(diffs) {
+            // If we find a sequence again, that price will never be reached
+            // because "after the hiding spot is sold, the monkey will move on to the next buyer"
+            // So we only need to update a sequence if it's the first occurence at another vendor
+            price_map
+                .entry(diffs)
+                .and_modify(|e| *e += price as i64)
+                .or_insert(price as i64);
+        }
+    }
+}
 
-    initial_secrets
+fn best_price(price_map: &HashMap<(i8, i8, i8, i8), i64>) -> Result<i64> {
+    let (changes, bananas) = price_map
         .iter()
-        .map(|s| calculate_secrets(s, 2000))
-        .sum()
+        .max_by_key(|(_, bananas)| **bananas)
+        .ok_or_else(|| Error::no_solution("there are no buyers"))?;
+    hook::fact(|| {
+        Fact::new("best sequence")
+            .with("changes", format!("{changes:?}"))
+            .with("bananas", bananas)
+    });
+    Ok(*bananas)
 }
 
-pub fn solve_2(input: &str) -> i64 {
-    let data = fs::read_to_string(input).expect("Can't open file");
-    let initial_secrets = data
-        .lines()
-        .map(|line| line.parse::<u64>().unwrap())
-        .collect::<Vec<u64>>();
+pub struct Input {
+    secrets: Vec<u64>,
+}
+
+pub fn parse(data: &str) -> Result<Input> {
+    let secrets = lines(data)
+        .map(|line| line.parse::<u64>(line.text))
+        .collect::<Result<_>>()?;
+    Ok(Input { secrets })
+}
+
+pub fn part_1(input: &Input) -> Result<u64> {
+    let initial_secrets = &input.secrets;
+
+    Ok(initial_secrets
+        .iter()
+        .map(|s| {
+            let last = calculate_secrets(s, 2000);
+            hook::fact(|| Fact::new("buyer").with("secret", s).with("2000th", last));
+            last
+        })
+        .sum())
+}
+
+pub fn part_2(input: &Input) -> Result<i64> {
     // Collect sequence -> price data in a map
     let mut price_map = HashMap::new();
 
-    for mut secret in initial_secrets {
-        // Keep track of found sequences at new buyers
-        let mut sequence_found: HashSet<(i8, i8, i8, i8)> = HashSet::new();
-        // The first value is the initial secret's last digit
-        let start = (secret % 10) as i8;
-        let mut seq = (0..2000)
-            .map(|_| calculate_price(&mut secret))
-            .collect::<Vec<i8>>();
-        seq.insert(0, start);
-
-        for w in seq.windows(5) {
-            let diffs = (w[1] - w[0], w[2] - w[1], w[3] - w[2], w[4] - w[3]);
-            let price = w[4];
-            if sequence_found.insert(diffs) {
-                // If we find a sequence again, that price will never be reached
-                // because "after the hiding spot is sold, the monkey will move on to the next buyer"
-                // So we only need to update a sequence if it's the first occurence at another vendor
-                price_map
-                    .entry(diffs)
-                    .and_modify(|e| *e += price as i64)
-                    .or_insert(price as i64);
-            }
-        }
+    for secret in input.secrets.iter().copied() {
+        add_prices(secret, &mut price_map);
+    }
+    best_price(&price_map)
+}
+
+/// `size` initial secrets
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    (0..size)
+        .map(|_| format!("{}\n", rng.range(1..=PRUNE as i64 - 1)))
+        .collect()
+}
+
+/// Both parts from `reader` a buyer at a time. Only the prices of the sequences are kept, and
+/// there are at most 19^4 of those however many buyers there are.
+pub fn solve_reader(reader: impl BufRead) -> Result<(u64, i64)> {
+    let mut sum = 0;
+    let mut price_map = HashMap::new();
+    stream_lines(reader, |line| {
+        let secret: u64 = line.parse(line.text)?;
+        sum += calculate_secrets(&secret, 2000);
+        add_prices(secret, &mut price_map);
+        Ok(())
+    })?;
+    Ok((sum, best_price(&price_map)?))
+}
+
+pub fn solve_1(input: &str) -> Result<u64> {
+    let data = fs::read_to_string(input)?;
+    part_1(&parse(&data)?)
+}
+
+pub fn solve_2(input: &str) -> Result<i64> {
+    let data = fs::read_to_string(input)?;
+    part_2(&parse(&data)?)
+}
+
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    const DAY: u8 = 22;
+
+    type Input = Input;
+
+    fn parse(data: &str) -> Result<Self::Input> {
+        parse(data)
+    }
+
+    fn part_1(input: &Self::Input) -> Result<impl Display> {
+        part_1(input)
+    }
+
+    fn part_2(input: &Self::Input) -> Result<impl Display> {
+        part_2(input)
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        generate(rng, size)
+    }
+
+    fn stream(reader: &mut dyn BufRead) -> Option<Result<(impl Display, impl Display)>> {
+        Some(solve_reader(reader))
     }
-    *price_map.values().max().unwrap()
 }
 
 #[cfg(test)]
 mod tests {
+    use std::io;
+
     use super::*;
-    #[test]
-    fn part1() {
-        let result = solve_1("src/day22/test1.txt");
-        assert_eq!(result, 37327623);
-    }
 
     #[test]
-    fn part2() {
-        let result = solve_2("src/day22/test2.txt");
-        assert_eq!(result, 23);
+    fn reader() {
+        let file = fs::File::open("src/day22/test2.txt").unwrap();
+        let data = fs::read_to_string("src/day22/test2.txt").unwrap();
+        let input = parse(&data).unwrap();
+        assert_eq!(
+            solve_reader(io::BufReader::new(file)).unwrap(),
+            (part_1(&input).unwrap(), 23)
+        );
     }
 }
@@ -1,9 +1,28 @@
+//! Day 23: LAN Party
+//!
+//! ```
+//! use aoc2024::day23;
+//!
+//! let input = day23::parse(&std::fs::read_to_string("src/day23/test1.txt")?)?;
+//! assert_eq!(day23::part_1(&input)?, 7);
+//! # Ok::<(), aoc2024::error::Error>(())
+//! ```
+
 use itertools::Itertools;
 use std::{
     collections::{HashMap, HashSet},
+    fmt::Display,
     fs,
 };
 
+use crate::error::{Error, Result};
+use crate::solution::Solution;
+use crate::util::hook;
+use crate::util::input::lines;
+use crate::util::random::Rng;
+use crate::util::trace::Fact;
+use std::collections::BTreeSet;
+
 // Taken from rosettacode
 fn bron_kerbosch_v2<'a>(
     r: &HashSet<&'a str>,
@@ -113,13 +132,24 @@ fn bron_kerbosch_v2_vec<'a>(
     }
 }
 
-pub fn solve_1(input: &str) -> u32 {
-    let data = fs::read_to_string(input).expect("Can't open file");
+pub struct Input {
+    connections: Vec<(String, String)>,
+}
 
-    let graph = data.lines().fold(
+pub fn parse(data: &str) -> Result<Input> {
+    let connections = lines(data)
+        .map(|line| {
+            let (node1, node2) = line.split_once("-")?;
+            Ok((node1.to_string(), node2.to_string()))
+        })
+        .collect::<Result<_>>()?;
+    Ok(Input { connections })
+}
+
+pub fn part_1(input: &Input) -> Result<u32> {
+    let graph = input.connections.iter().fold(
         HashMap::new(),
-        |mut graph: HashMap<&str, HashSet<&str>>, line| {
-            let (node1, node2) = line.split_once('-').unwrap();
+        |mut graph: HashMap<&str, HashSet<&str>>, (node1, node2)| {
             graph.entry(node1).or_default().insert(node2);
             graph.entry(node2).or_default().insert(node1);
             graph
@@ -155,17 +185,24 @@ pub fn solve_1(input: &str) -> u32 {
     // Extend original and retain only three-cliques
     cliques.extend(new_3_cliques);
     cliques.retain(|c| c.len() == 3);
-
-    cliques.len() as u32
+    hook::fact(|| {
+        Fact::new("triangles").with("with_t", cliques.len()).with(
+            "example",
+            cliques
+                .iter()
+                .min()
+                .map(|c| c.join(","))
+                .unwrap_or_default(),
+        )
+    });
+
+    Ok(cliques.len() as u32)
 }
 
-pub fn solve_2(input: &str) -> String {
-    let data = fs::read_to_string(input).expect("Can't open file");
-
-    let graph = data.lines().fold(
+pub fn part_2(input: &Input) -> Result<String> {
+    let graph = input.connections.iter().fold(
         HashMap::new(),
-        |mut graph: HashMap<&str, HashSet<&str>>, line| {
-            let (node1, node2) = line.split_once('-').unwrap();
+        |mut graph: HashMap<&str, HashSet<&str>>, (node1, node2)| {
             graph.entry(node1).or_default().insert(node2);
             graph.entry(node2).or_default().insert(node1);
             graph
@@ -184,25 +221,87 @@ pub fn solve_2(input: &str) -> String {
     // Sort by individual clique length, will be ascending
     cliques.sort_by_key(|c| c.len());
     // Largest clique is the last one
-    let mut largest = cliques[cliques.len() - 1].clone();
+    let mut largest = cliques
+        .last()
+        .cloned()
+        .ok_or_else(|| Error::no_solution("there's no LAN party of 3 or more computers"))?;
     // Sort by computer names for the password
     largest.sort();
+    hook::fact(|| {
+        Fact::new("largest party")
+            .with("computers", largest.len())
+            .with("cliques", cliques.len())
+    });
 
-    largest.iter().join(",")
+    Ok(largest.iter().join(","))
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    #[test]
-    fn part1() {
-        let result = solve_1("src/day23/test1.txt");
-        assert_eq!(result, 7);
+/// A network of `size` computers with a LAN party hidden in it
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut names: Vec<String> = (b'a'..=b'z')
+        .flat_map(|a| (b'a'..=b'z').map(move |b| String::from_utf8(vec![a, b]).unwrap()))
+        .collect();
+    rng.shuffle(&mut names);
+    let n = size.clamp(4, names.len());
+    names.truncate(n);
+
+    let mut edges = BTreeSet::new();
+    for i in 0..n {
+        for _ in 0..2 {
+            let j = rng.below(n);
+            if i != j {
+                edges.insert((i.min(j), i.max(j)));
+            }
+        }
+    }
+    let party = (n / 4).clamp(5.min(n), 13);
+    for i in 0..party {
+        for j in i + 1..party {
+            edges.insert((i, j));
+        }
+    }
+
+    let mut lines: Vec<String> = edges
+        .into_iter()
+        .map(|(i, j)| {
+            let (a, b) = if rng.chance(0.5) { (i, j) } else { (j, i) };
+            format!("{}-{}\n", names[a], names[b])
+        })
+        .collect();
+    rng.shuffle(&mut lines);
+    lines.concat()
+}
+
+pub fn solve_1(input: &str) -> Result<u32> {
+    let data = fs::read_to_string(input)?;
+    part_1(&parse(&data)?)
+}
+
+pub fn solve_2(input: &str) -> Result<String> {
+    let data = fs::read_to_string(input)?;
+    part_2(&parse(&data)?)
+}
+
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    const DAY: u8 = 23;
+
+    type Input = Input;
+
+    fn parse(data: &str) -> Result<Self::Input> {
+        parse(data)
+    }
+
+    fn part_1(input: &Self::Input) -> Result<impl Display> {
+        part_1(input)
+    }
+
+    fn part_2(input: &Self::Input) -> Result<impl Display> {
+        part_2(input)
     }
 
-    #[test]
-    fn part2() {
-        let result = solve_2("src/day23/test2.txt");
-        assert_eq!(result, "co,de,ka,ta");
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        generate(rng, size)
     }
 }
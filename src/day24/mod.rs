@@ -1,7 +1,30 @@
-use std::{collections::HashMap, fs};
+//! Day 24: Crossed Wires
+//!
+//! ```
+//! use aoc2024::day24;
+//!
+//! let input = day24::parse(&std::fs::read_to_string("src/day24/test1.txt")?)?;
+//! assert_eq!(day24::part_1(&input)?, 4);
+//! # Ok::<(), aoc2024::error::Error>(())
+//! ```
 
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Display,
+    fs,
+};
+
+use crate::error::{Error, Result};
+use crate::solution::Solution;
+use crate::util::hook;
+use crate::util::input::sections;
+use crate::util::random::Rng;
+use crate::util::trace::Fact;
 use itertools::Itertools;
 
+#[cfg(feature = "reference")]
+pub mod reference;
+
 #[allow(clippy::upper_case_acronyms)]
 #[derive(Clone)]
 enum GateType {
@@ -47,23 +70,91 @@ fn get_wire_value<'a>(
     *value_map.get(wire).unwrap()
 }
 
-pub fn solve_1(input: &str) -> u64 {
-    let data = fs::read_to_string(input).expect("Can't open file");
-    let (inputs, circuit) = data.split_once("\r\n\r\n").unwrap();
-    // Maps the wire's name to its value
-    let mut value_map: HashMap<&str, u8> = inputs
+struct Gate {
+    lhs: String,
+    op: String,
+    rhs: String,
+    out: String,
+}
+
+pub struct Input {
+    wires: Vec<(String, u8)>,
+    gates: Vec<Gate>,
+}
+
+pub fn parse(data: &str) -> Result<Input> {
+    let [inputs, circuit] = sections(data)[..] else {
+        return Err(Error::invalid(
+            "expected a blank line between wires and gates",
+        ));
+    };
+    let wires: Vec<(String, u8)> = inputs
         .lines()
         .map(|line| {
-            let (wire, value) = line.split_once(": ").unwrap();
-            (wire, value.parse::<u8>().unwrap())
+            let (wire, value) = line.split_once(": ")?;
+            match line.parse::<u8>(value)? {
+                bit @ (0 | 1) => Ok((wire.to_string(), bit)),
+                _ => Err(line.error_at(value, format!("expected 0 or 1, found '{value}'"))),
+            }
         })
-        .collect();
-    // Maps the _output_ wire to the gate and two input wires before it
-    let wire_map: HashMap<&str, (GateType, &str, &str)> = circuit
+        .collect::<Result<_>>()?;
+
+    let gates: Vec<Gate> = circuit
         .lines()
         .map(|line| {
-            let data: Vec<_> = line.split_ascii_whitespace().collect();
-            (data[4], (GateType::from(data[1]), data[0], data[2]))
+            let (lhs, op, rhs, _, out) = line
+                .text
+                .split_whitespace()
+                .collect_tuple()
+                .ok_or_else(|| line.error(1, "expected a gate like 'x00 AND y00 -> z00'"))?;
+            if !matches!(op, "AND" | "OR" | "XOR") {
+                return Err(line.error_at(op, format!("unknown gate '{op}'")));
+            }
+            Ok(Gate {
+                lhs: lhs.to_string(),
+                op: op.to_string(),
+                rhs: rhs.to_string(),
+                out: out.to_string(),
+            })
+        })
+        .collect::<Result<_>>()?;
+
+    let defined: HashSet<&str> = wires
+        .iter()
+        .map(|(wire, _)| wire.as_str())
+        .chain(gates.iter().map(|gate| gate.out.as_str()))
+        .collect();
+    for gate in &gates {
+        for wire in [&gate.lhs, &gate.rhs] {
+            if !defined.contains(wire.as_str()) {
+                return Err(Error::invalid(format!("wire {wire} has no value")));
+            }
+        }
+    }
+
+    Ok(Input { wires, gates })
+}
+
+pub fn part_1(input: &Input) -> Result<u64> {
+    // Maps the wire's name to its value
+    let mut value_map: HashMap<&str, u8> = input
+        .wires
+        .iter()
+        .map(|(wire, value)| (wire.as_str(), *value))
+        .collect();
+    // Maps the _output_ wire to the gate and two input wires before it
+    let wire_map: HashMap<&str, (GateType, &str, &str)> = input
+        .gates
+        .iter()
+        .map(|gate| {
+            (
+                gate.out.as_str(),
+                (
+                    GateType::from(&gate.op),
+                    gate.lhs.as_str(),
+                    gate.rhs.as_str(),
+                ),
+            )
         })
         .collect();
     let mut z_wires: Vec<(&str, u8)> = wire_map
@@ -74,6 +165,7 @@ pub fn solve_1(input: &str) -> u64 {
         .collect();
     z_wires.sort();
     z_wires.reverse();
+    hook::fact(|| Fact::new("z wires").with("count", z_wires.len()));
 
     u64::from_str_radix(
         z_wires
@@ -83,18 +175,18 @@ pub fn solve_1(input: &str) -> u64 {
             .as_str(),
         2,
     )
-    .unwrap()
+    .map_err(|_| Error::no_solution("the z wires don't make a 64-bit number"))
 }
 
-pub fn solve_2(input: &str) -> String {
-    let data = fs::read_to_string(input).expect("Can't open file");
-    let (_, circuit) = data.split_once("\r\n\r\n").unwrap();
-
-    let gates = circuit
-        .lines()
-        .map(|line| {
-            let (lhs, op, rhs, _, out) = line.split_whitespace().collect_tuple().unwrap();
-            (out, (lhs, rhs, op))
+pub fn part_2(input: &Input) -> Result<String> {
+    let gates = input
+        .gates
+        .iter()
+        .map(|gate| {
+            (
+                gate.out.as_str(),
+                (gate.lhs.as_str(), gate.rhs.as_str(), gate.op.as_str()),
+            )
         })
         .collect::<HashMap<_, _>>();
 
@@ -102,10 +194,13 @@ pub fn solve_2(input: &str) -> String {
     // [x XOR y] XOR carry_in -> z
     // [x AND y] OR [carry_in AND (the above)] -> carry_out
 
-    // zXX output can only be after an XOR (except z45, the last)
+    // The last z wire is the carry out of the highest bit, z45 for the real inputs
+    let last_z = format!("z{:02}", input.wires.len() / 2);
+
+    // zXX output can only be after an XOR (except the last)
     let error_1 = gates
         .iter()
-        .filter(|(&out, (_, _, op))| out.starts_with('z') && out != "z45" && !op.eq(&"XOR"))
+        .filter(|(&out, (_, _, op))| out.starts_with('z') && *out != last_z && !op.eq(&"XOR"))
         .collect::<HashMap<_, _>>();
     // non-z output, NOT x,y input -> must not be XOR
     let error_2 = gates
@@ -163,41 +258,139 @@ pub fn solve_2(input: &str) -> String {
         .collect::<HashMap<_, _>>();
 
     let mut wrong_wires = vec![];
-    for (e, _) in error_1 {
-        wrong_wires.push(e);
+    for (errors, reason) in [
+        (error_1, "z wire not out of an XOR"),
+        (error_2, "XOR of carries not into a z wire"),
+        (error_3, "x XOR y into an OR"),
+        (error_4, "x AND y not only into an OR"),
+    ] {
+        for (e, _) in errors {
+            hook::fact(|| Fact::new("swapped").with("wire", e).with("reason", reason));
+            wrong_wires.push(e);
+        }
+    }
+    wrong_wires.sort();
+    Ok(wrong_wires.iter().join(","))
+}
+
+/// A `size`-bit ripple-carry adder with four pairs of swapped outputs, like the real ones which
+/// have 45 bits. It has at least 7, for the swaps to be on different bits, and at most 63, for
+/// the sum to fit in part 1's answer.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let width = size.clamp(7, 63);
+
+    // Every bit after the first: s = x ^ y, a = x & y, z = s ^ c, t = s & c, carry = a | t
+    let mut used = HashSet::new();
+    let mut name = |rng: &mut Rng| loop {
+        let name: String = (0..3)
+            .map(|_| char::from(b'a' + rng.below(23) as u8))
+            .collect();
+        if used.insert(name.clone()) {
+            return name;
+        }
+    };
+    let mut s = vec![String::new(); width];
+    let mut a = vec![String::new(); width];
+    let mut t = vec![String::new(); width];
+    let z: Vec<String> = (0..width).map(|i| format!("z{i:02}")).collect();
+    let mut carry = vec![String::new(); width];
+    for i in 1..width {
+        (s[i], a[i], t[i]) = (name(rng), name(rng), name(rng));
     }
-    for (e, _) in error_2 {
-        wrong_wires.push(e);
+    for (i, wire) in carry.iter_mut().enumerate() {
+        *wire = if i == width - 1 {
+            format!("z{width:02}")
+        } else {
+            name(rng)
+        };
     }
-    for (e, _) in error_3 {
-        wrong_wires.push(e);
+
+    let mut gates = vec![
+        ("x00".to_string(), "XOR", "y00".to_string(), z[0].clone()),
+        (
+            "x00".to_string(),
+            "AND",
+            "y00".to_string(),
+            carry[0].clone(),
+        ),
+    ];
+    for i in 1..width {
+        let (x, y) = (format!("x{i:02}"), format!("y{i:02}"));
+        gates.push((x.clone(), "XOR", y.clone(), s[i].clone()));
+        gates.push((x, "AND", y, a[i].clone()));
+        gates.push((s[i].clone(), "XOR", carry[i - 1].clone(), z[i].clone()));
+        gates.push((s[i].clone(), "AND", carry[i - 1].clone(), t[i].clone()));
+        gates.push((a[i].clone(), "OR", t[i].clone(), carry[i].clone()));
     }
-    for (e, _) in error_4 {
-        wrong_wires.push(e);
+
+    // Swapped outputs, the wires reading them stay where they were. All on different bits, to
+    // be found by the checks of part 2.
+    let mut bits: Vec<usize> = (2..width - 1).collect();
+    rng.shuffle(&mut bits);
+    for &i in &bits[..4] {
+        let (first, second) = match rng.below(3) {
+            0 => (&z[i], &carry[i]),
+            1 => (&z[i], &t[i]),
+            _ => (&s[i], &a[i]),
+        };
+        for (_, _, _, out) in &mut gates {
+            if out == first {
+                *out = second.clone();
+            } else if out == second {
+                *out = first.clone();
+            }
+        }
     }
-    wrong_wires.sort();
-    wrong_wires.iter().join(",")
+    rng.shuffle(&mut gates);
+
+    let mut text = String::new();
+    for wire in ['x', 'y'] {
+        for i in 0..width {
+            text += &format!("{wire}{i:02}: {}\n", rng.below(2));
+        }
+    }
+    text += "\n";
+    for (lhs, op, rhs, out) in gates {
+        let (lhs, rhs) = if rng.chance(0.5) {
+            (lhs, rhs)
+        } else {
+            (rhs, lhs)
+        };
+        text += &format!("{lhs} {op} {rhs} -> {out}\n");
+    }
+    text
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    #[test]
-    fn part1() {
-        let result = solve_1("src/day24/test1.txt");
-        assert_eq!(result, 4);
+pub fn solve_1(input: &str) -> Result<u64> {
+    let data = fs::read_to_string(input)?;
+    part_1(&parse(&data)?)
+}
+
+pub fn solve_2(input: &str) -> Result<String> {
+    let data = fs::read_to_string(input)?;
+    part_2(&parse(&data)?)
+}
+
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    const DAY: u8 = 24;
+
+    type Input = Input;
+
+    fn parse(data: &str) -> Result<Self::Input> {
+        parse(data)
+    }
+
+    fn part_1(input: &Self::Input) -> Result<impl Display> {
+        part_1(input)
     }
 
-    #[test]
-    fn part1_2() {
-        let result = solve_1("src/day24/test2.txt");
-        assert_eq!(result, 2024);
+    fn part_2(input: &Self::Input) -> Result<impl Display> {
+        part_2(input)
     }
 
-    #[test]
-    fn part2() {
-        // Can't test a non-ripple-carry adder with the test data
-        //let result = solve_2("src/day24/test3.txt");
-        //assert_eq!(result, "");
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        generate(rng, size)
     }
 }
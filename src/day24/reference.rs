@@ -0,0 +1,230 @@
+//! Slow but simple answers: the circuit is simulated wire by wire, and part 2 swaps outputs
+//! until it really adds instead of looking for gates that break the pattern of an adder
+
+use std::collections::HashMap;
+
+use crate::error::{Error, Result};
+use itertools::Itertools;
+
+use super::Input;
+
+/// The gates with numbered wires, `None` for the ones that are set from the start
+struct Circuit {
+    wires: Vec<String>,
+    gates: Vec<Option<(usize, String, usize)>>,
+    x: Vec<usize>,
+    y: Vec<usize>,
+    z: Vec<usize>,
+}
+
+impl Circuit {
+    fn new(input: &Input) -> Self {
+        let mut ids: HashMap<String, usize> = HashMap::new();
+        let mut wires = vec![];
+        let mut id = |wire: &str| {
+            *ids.entry(wire.to_string()).or_insert_with(|| {
+                wires.push(wire.to_string());
+                wires.len() - 1
+            })
+        };
+        let mut defined = vec![];
+        for (wire, _) in &input.wires {
+            id(wire);
+        }
+        for gate in &input.gates {
+            defined.push((
+                id(&gate.out),
+                (id(&gate.lhs), gate.op.clone(), id(&gate.rhs)),
+            ));
+        }
+        let mut gates = vec![None; wires.len()];
+        for (out, gate) in defined {
+            gates[out] = Some(gate);
+        }
+        let numbered = |prefix: char| -> Vec<usize> {
+            (0..)
+                .map_while(|i| ids.get(&format!("{prefix}{i:02}")).copied())
+                .collect()
+        };
+        Self {
+            x: numbered('x'),
+            y: numbered('y'),
+            z: numbered('z'),
+            wires,
+            gates,
+        }
+    }
+
+    /// The value of every wire, `None` if a wire depends on itself
+    fn simulate(&self, set: &[(usize, u8)]) -> Option<Vec<u8>> {
+        fn value(
+            circuit: &Circuit,
+            wire: usize,
+            values: &mut [Option<u8>],
+            visiting: &mut [bool],
+        ) -> Option<u8> {
+            if let Some(value) = values[wire] {
+                return Some(value);
+            }
+            let (lhs, op, rhs) = circuit.gates[wire].as_ref()?;
+            if visiting[wire] {
+                return None;
+            }
+            visiting[wire] = true;
+            let (lhs, rhs) = (
+                value(circuit, *lhs, values, visiting)?,
+                value(circuit, *rhs, values, visiting)?,
+            );
+            let out = match op.as_str() {
+                "AND" => lhs & rhs,
+                "OR" => lhs | rhs,
+                _ => lhs ^ rhs,
+            };
+            values[wire] = Some(out);
+            Some(out)
+        }
+
+        let mut values = vec![None; self.wires.len()];
+        let mut visiting = vec![false; self.wires.len()];
+        for &(wire, value) in set {
+            values[wire] = Some(value);
+        }
+        (0..self.wires.len())
+            .map(|wire| value(self, wire, &mut values, &mut visiting))
+            .collect()
+    }
+
+    /// The z wires as a number when the x and y wires are set to `x` and `y`
+    fn add(&self, x: u64, y: u64) -> Option<u64> {
+        let set: Vec<(usize, u8)> = self
+            .x
+            .iter()
+            .enumerate()
+            .map(|(i, &wire)| (wire, (x >> i & 1) as u8))
+            .chain(
+                self.y
+                    .iter()
+                    .enumerate()
+                    .map(|(i, &wire)| (wire, (y >> i & 1) as u8)),
+            )
+            .collect();
+        let values = self.simulate(&set)?;
+        Some(
+            self.z
+                .iter()
+                .enumerate()
+                .map(|(i, &wire)| (values[wire] as u64) << i)
+                .sum(),
+        )
+    }
+
+    /// The lowest bit that comes out wrong for some sum, `None` if every sum is right. Every
+    /// bit is tried with all of its inputs and a carry into it.
+    fn first_wrong_bit(&self) -> Option<usize> {
+        let mut first = None;
+        for bit in 0..self.x.len() {
+            let carry = (1u64 << bit) - 1;
+            for (x, y) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+                for (x, y) in [(x << bit, y << bit), (x << bit | carry, y << bit | 1)] {
+                    let wrong = match self.add(x, y) {
+                        Some(sum) => (sum ^ (x + y)).trailing_zeros() as usize,
+                        None => bit,
+                    };
+                    if wrong < 64 {
+                        first = Some(first.map_or(wrong, |first: usize| first.min(wrong)));
+                    }
+                }
+            }
+        }
+        first
+    }
+
+    /// The highest input bit each wire depends on
+    fn bits(&self) -> Vec<Option<usize>> {
+        let mut bits = vec![None; self.wires.len()];
+        for (i, (&x, &y)) in self.x.iter().zip(&self.y).enumerate() {
+            bits[x] = Some(i);
+            bits[y] = Some(i);
+        }
+        loop {
+            let mut changed = false;
+            for (out, gate) in self.gates.iter().enumerate() {
+                if let Some((lhs, _, rhs)) = gate {
+                    let bit = bits[*lhs].max(bits[*rhs]);
+                    if bits[out] < bit {
+                        bits[out] = bit;
+                        changed = true;
+                    }
+                }
+            }
+            if !changed {
+                return bits;
+            }
+        }
+    }
+
+    fn swap(&mut self, a: usize, b: usize) {
+        self.gates.swap(a, b);
+    }
+}
+
+pub fn part_1(input: &Input) -> Result<u64> {
+    let circuit = Circuit::new(input);
+    let ids: HashMap<&str, usize> = circuit
+        .wires
+        .iter()
+        .enumerate()
+        .map(|(id, wire)| (wire.as_str(), id))
+        .collect();
+    let set: Vec<(usize, u8)> = input
+        .wires
+        .iter()
+        .map(|(wire, value)| (ids[wire.as_str()], *value))
+        .collect();
+    let values = circuit
+        .simulate(&set)
+        .ok_or_else(|| Error::no_solution("the wires go around in circles"))?;
+    Ok(circuit
+        .z
+        .iter()
+        .enumerate()
+        .map(|(i, &wire)| (values[wire] as u64) << i)
+        .sum())
+}
+
+/// Fixes the lowest wrong bit first, trying to swap the outputs of any two gates that depend on
+/// the inputs up to that bit but no higher, and goes back when the other swaps don't work out
+pub fn part_2(input: &Input) -> Result<String> {
+    fn fix(circuit: &mut Circuit, swaps: usize) -> Option<Vec<usize>> {
+        let Some(bit) = circuit.first_wrong_bit() else {
+            return (swaps == 0).then(Vec::new);
+        };
+        if swaps == 0 {
+            return None;
+        }
+        let bits = circuit.bits();
+        let candidates: Vec<usize> = (0..circuit.wires.len())
+            .filter(|&wire| circuit.gates[wire].is_some() && bits[wire] == Some(bit))
+            .collect();
+        for (&a, &b) in candidates.iter().tuple_combinations() {
+            circuit.swap(a, b);
+            if circuit.first_wrong_bit().is_none_or(|wrong| wrong > bit) {
+                if let Some(mut swapped) = fix(circuit, swaps - 1) {
+                    swapped.extend([a, b]);
+                    return Some(swapped);
+                }
+            }
+            circuit.swap(a, b);
+        }
+        None
+    }
+
+    let mut circuit = Circuit::new(input);
+    let swapped =
+        fix(&mut circuit, 4).ok_or_else(|| Error::no_solution("no 4 swaps make it add"))?;
+    Ok(swapped
+        .iter()
+        .map(|&wire| circuit.wires[wire].as_str())
+        .sorted()
+        .join(","))
+}
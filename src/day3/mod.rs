@@ -1,25 +1,58 @@
-use std::fs;
+//! Day 3: Mull It Over
+//!
+//! ```
+//! use aoc2024::day3;
+//!
+//! let input = day3::parse(&std::fs::read_to_string("src/day3/test1.txt")?)?;
+//! assert_eq!(day3::part_1(&input)?, 161);
+//! # Ok::<(), aoc2024::error::Error>(())
+//! ```
 
+use std::{fmt::Display, fs};
+
+use crate::error::Result;
+use crate::solution::Solution;
+use crate::util::hook;
+use crate::util::random::Rng;
+use crate::util::trace::Fact;
 use regex::Regex;
 
-pub fn solve_1(input: &str) -> u32 {
-    let data = fs::read_to_string(input).expect("Can't open file");
-    let re = Regex::new(r"mul\((\d+),(\d+)\)").unwrap();
+pub struct Input {
+    memory: String,
+}
+
+pub fn parse(data: &str) -> Result<Input> {
+    Ok(Input {
+        memory: data.to_string(),
+    })
+}
+
+pub fn part_1(input: &Input) -> Result<u32> {
+    let re = Regex::new(r"mul\((\d{1,3}),(\d{1,3})\)").unwrap();
 
     let sum_muls: u32 = re
-        .captures_iter(&data)
+        .captures_iter(&input.memory)
         .map(|caps| {
-            let (_, [first, second]) = caps.extract();
-            first.parse::<u32>().unwrap() * second.parse::<u32>().unwrap()
+            let (instruction, [first, second]) = caps.extract();
+            let product = first.parse::<u32>().unwrap() * second.parse::<u32>().unwrap();
+            hook::fact(|| {
+                Fact::new("mul")
+                    .with("instruction", instruction)
+                    .with("product", product)
+            });
+            product
         })
         .sum();
-    sum_muls
+    Ok(sum_muls)
 }
-pub fn solve_2(input: &str) -> u32 {
-    let data = fs::read_to_string(input).expect("Can't open file");
-    let re = Regex::new(r"mul\((\d+),(\d+)\)|do\(\)|don't\(\)").unwrap(); // Capture all possibilities to know the order
-    let re_inner = Regex::new(r"\((\d+),(\d+)\)").unwrap(); // Inner regex just for the "mul(a,b)" matches
-    let captures: Vec<&str> = re.find_iter(&data).map(|cap| cap.as_str()).collect();
+
+pub fn part_2(input: &Input) -> Result<u32> {
+    let re = Regex::new(r"mul\((\d{1,3}),(\d{1,3})\)|do\(\)|don't\(\)").unwrap(); // Capture all possibilities to know the order
+    let re_inner = Regex::new(r"\((\d{1,3}),(\d{1,3})\)").unwrap(); // Inner regex just for the "mul(a,b)" matches
+    let captures: Vec<&str> = re
+        .find_iter(&input.memory)
+        .map(|cap| cap.as_str())
+        .collect();
 
     let mut enabled = true;
     let mut sum = 0;
@@ -28,6 +61,11 @@ pub fn solve_2(input: &str) -> u32 {
             "do()" => enabled = true,
             "don't()" => enabled = false,
             _ => {
+                hook::fact(|| {
+                    Fact::new("mul")
+                        .with("instruction", cap)
+                        .with("enabled", enabled)
+                });
                 if enabled {
                     let (_, [f, s]) = re_inner.captures(cap).unwrap().extract();
                     sum += f.parse::<u32>().unwrap() * s.parse::<u32>().unwrap()
@@ -35,21 +73,71 @@ pub fn solve_2(input: &str) -> u32 {
             }
         }
     }
-    sum
+    Ok(sum)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    #[test]
-    fn part1() {
-        let result = solve_1("src/day3/test1.txt");
-        assert_eq!(result, 161);
+/// `size` lines of corrupted memory
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    const JUNK: [&str; 8] = [
+        "!",
+        "@",
+        "from()",
+        "what()",
+        "select()",
+        "]",
+        "<{",
+        "where(1,2)",
+    ];
+    let mut text = String::new();
+    for _ in 0..size {
+        for _ in 0..rng.range(20..=60) {
+            let (a, b) = (rng.range(1..=999), rng.range(1..=999));
+            text += &match rng.below(8) {
+                0 | 1 => format!("mul({a},{b})"),
+                2 => "do()".to_string(),
+                3 => "don't()".to_string(),
+                4 => format!("mul({a},{b}]"),
+                5 => format!("mul ( {a} , {b} )"),
+                6 => format!("mul({},{b})", a + 1000),
+                _ => rng.pick(&JUNK).to_string(),
+            };
+            text += *rng.pick(&JUNK);
+        }
+        text += "\n";
+    }
+    text
+}
+
+pub fn solve_1(input: &str) -> Result<u32> {
+    let data = fs::read_to_string(input)?;
+    part_1(&parse(&data)?)
+}
+
+pub fn solve_2(input: &str) -> Result<u32> {
+    let data = fs::read_to_string(input)?;
+    part_2(&parse(&data)?)
+}
+
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    const DAY: u8 = 3;
+
+    type Input = Input;
+
+    fn parse(data: &str) -> Result<Self::Input> {
+        parse(data)
+    }
+
+    fn part_1(input: &Self::Input) -> Result<impl Display> {
+        part_1(input)
+    }
+
+    fn part_2(input: &Self::Input) -> Result<impl Display> {
+        part_2(input)
     }
 
-    #[test]
-    fn part2() {
-        let result = solve_2("src/day3/test2.txt");
-        assert_eq!(result, 48);
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        generate(rng, size)
     }
 }
@@ -1,148 +1,147 @@
-use std::fs;
+//! Day 4: Ceres Search
+//!
+//! ```
+//! use aoc2024::day4;
+//!
+//! let input = day4::parse(&std::fs::read_to_string("src/day4/test1.txt")?)?;
+//! assert_eq!(day4::part_1(&input)?, 18);
+//! # Ok::<(), aoc2024::error::Error>(())
+//! ```
 
-#[cfg(not(test))]
-const SIZE: usize = 146; // 140x140 chars + 3 padding on each side
-#[cfg(test)]
-const SIZE: usize = 16; // 10x10 chars + 3 padding on each side
+use std::{fmt::Display, fs};
 
-pub fn valid_words_arr(input: &[[char; SIZE]; SIZE]) -> u32 {
+use crate::error::Result;
+use crate::solution::Solution;
+use crate::util::direction::Direction8;
+use crate::util::grid::Grid;
+use crate::util::hook;
+use crate::util::random::Rng;
+use crate::util::trace::Fact;
+
+// Directions of the words starting from their first letter: horizontal, vertical, CW and CCW
+// Words are matched forwards and backwards, so these 4 are enough
+const DIRECTIONS: [Direction8; 4] = [
+    Direction8::Right,
+    Direction8::Up,
+    Direction8::UpRight,
+    Direction8::UpLeft,
+];
+
+fn valid_words_arr(input: &Grid<char>) -> u32 {
     let mut cnt = 0;
 
-    for row in 3..(SIZE - 3) {
-        for col in 3..(SIZE - 3) {
-            match input[row][col] {
-                'X' | 'S' => {
-                    // horizontal:
-                    let word = [
-                        input[row][col],
-                        input[row][col + 1],
-                        input[row][col + 2],
-                        input[row][col + 3],
-                    ];
-                    match word {
-                        ['X', 'M', 'A', 'S'] | ['S', 'A', 'M', 'X'] => cnt += 1,
-                        _ => {}
-                    }
-                    // vertical:
-                    let word = [
-                        input[row][col],
-                        input[row - 1][col],
-                        input[row - 2][col],
-                        input[row - 3][col],
-                    ];
-                    match word {
-                        ['X', 'M', 'A', 'S'] | ['S', 'A', 'M', 'X'] => cnt += 1,
-                        _ => {}
-                    }
-                    // CW:
-                    let word = [
-                        input[row][col],
-                        input[row - 1][col + 1],
-                        input[row - 2][col + 2],
-                        input[row - 3][col + 3],
-                    ];
-                    match word {
-                        ['X', 'M', 'A', 'S'] | ['S', 'A', 'M', 'X'] => cnt += 1,
-                        _ => {}
-                    }
-                    // CCW:
-                    let word = [
-                        input[row][col],
-                        input[row - 1][col - 1],
-                        input[row - 2][col - 2],
-                        input[row - 3][col - 3],
-                    ];
-                    match word {
-                        ['X', 'M', 'A', 'S'] | ['S', 'A', 'M', 'X'] => cnt += 1,
-                        _ => {}
-                    }
+    for (coord, c) in input.iter() {
+        if let 'X' | 'S' = c {
+            for direction in DIRECTIONS {
+                // Words running off of the grid are simply shorter
+                let word: String = input
+                    .line(coord, direction.delta())
+                    .take(4)
+                    .map(|(_, c)| c)
+                    .collect();
+                if word == "XMAS" || word == "SAMX" {
+                    hook::fact(|| {
+                        Fact::new("word")
+                            .with("start", coord)
+                            .with("direction", format!("{direction:?}"))
+                            .with("letters", &word)
+                    });
+                    cnt += 1;
                 }
-                _ => {}
             }
         }
     }
     cnt
 }
 
-pub fn valid_words_pt2(input: &[[char; SIZE]; SIZE]) -> u32 {
+fn valid_words_pt2(input: &Grid<char>) -> u32 {
     let mut cnt = 0;
 
-    for row in 3..(SIZE - 3) {
-        for col in 3..(SIZE - 3) {
-            if input[row][col] == 'A' {
-                let chars = [
-                    input[row - 1][col - 1],
-                    input[row - 1][col + 1],
-                    input[row + 1][col - 1],
-                    input[row + 1][col + 1],
-                ];
-                match chars {
-                    ['M', 'M', 'S', 'S']
-                    | ['S', 'S', 'M', 'M']
-                    | ['M', 'S', 'M', 'S']
-                    | ['S', 'M', 'S', 'M'] => cnt += 1,
-                    _ => {}
-                }
+    for coord in input.positions(&'A') {
+        let corner = |direction| input.get(coord + direction).copied().unwrap_or('.');
+        let chars = [
+            corner(Direction8::UpLeft),
+            corner(Direction8::UpRight),
+            corner(Direction8::DownLeft),
+            corner(Direction8::DownRight),
+        ];
+        match chars {
+            ['M', 'M', 'S', 'S']
+            | ['S', 'S', 'M', 'M']
+            | ['M', 'S', 'M', 'S']
+            | ['S', 'M', 'S', 'M'] => {
+                hook::fact(|| Fact::new("x-mas").with("center", coord));
+                cnt += 1
             }
+            _ => {}
         }
     }
 
     cnt
 }
 
-pub fn solve_1(input: &str) -> u32 {
-    let data = fs::read_to_string(input).expect("Can't open file");
+pub struct Input {
+    grid: Grid<char>,
+}
 
+pub fn parse(data: &str) -> Result<Input> {
     // idea:
-    // 1. create padding - 4 lines at the start, 4 at the end, 4 on each side
-    // 2. remove newlines, create a very long char array
-    // 3. find "X" or "S"
-    // 4. check letters:
-    //    - 90 degrees up/down: position - N*SIZE should be either "M" "A" "S" or "A" "M" "X"
-    //    - 45 degrees CW:      position - N*(SIZE-1)
-    //    - 45 degrees CCW:     position - N*(SIZE+1)
-    // create a 2D array
-    let mut arr = [['.'; SIZE]; SIZE];
-    let mut j = 3;
-    for line in data.lines() {
-        for (i, c) in line.char_indices() {
-            arr[j][i + 3] = c
-        }
-        j += 1;
-    }
+    // 1. find "X" or "S"
+    // 2. check letters in every direction, the grid takes care of the edges
+    let grid = Grid::parse(data, Some)?;
+    Ok(Input { grid })
+}
 
-    valid_words_arr(&arr)
+pub fn part_1(input: &Input) -> Result<u32> {
+    Ok(valid_words_arr(&input.grid))
 }
-pub fn solve_2(input: &str) -> u32 {
-    let data = fs::read_to_string(input).expect("Can't open file");
 
+pub fn part_2(input: &Input) -> Result<u32> {
     // even easier
     // check for 'A' then check surroundings
-    let mut arr = [['.'; SIZE]; SIZE];
-    let mut j = 3;
-    for line in data.lines() {
-        for (i, c) in line.char_indices() {
-            arr[j][i + 3] = c
-        }
-        j += 1;
+    Ok(valid_words_pt2(&input.grid))
+}
+
+/// A `size` by `size` word search
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut text = String::new();
+    for _ in 0..size {
+        text.extend((0..size).map(|_| *rng.pick(&['X', 'M', 'A', 'S'])));
+        text += "\n";
     }
+    text
+}
+
+pub fn solve_1(input: &str) -> Result<u32> {
+    let data = fs::read_to_string(input)?;
+    part_1(&parse(&data)?)
+}
 
-    valid_words_pt2(&arr)
+pub fn solve_2(input: &str) -> Result<u32> {
+    let data = fs::read_to_string(input)?;
+    part_2(&parse(&data)?)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    const DAY: u8 = 4;
+
+    type Input = Input;
+
+    fn parse(data: &str) -> Result<Self::Input> {
+        parse(data)
+    }
+
+    fn part_1(input: &Self::Input) -> Result<impl Display> {
+        part_1(input)
+    }
 
-    #[test]
-    fn part1() {
-        let result = solve_1("src/day4/test1.txt");
-        assert_eq!(result, 18);
+    fn part_2(input: &Self::Input) -> Result<impl Display> {
+        part_2(input)
     }
 
-    #[test]
-    fn part2() {
-        let result = solve_2("src/day4/test2.txt");
-        assert_eq!(result, 9);
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        generate(rng, size)
     }
 }
@@ -1,4 +1,21 @@
-use std::{cmp::Ordering, fs};
+//! Day 5: Print Queue
+//!
+//! ```
+//! use aoc2024::day5;
+//!
+//! let input = day5::parse(&std::fs::read_to_string("src/day5/test1.txt")?)?;
+//! assert_eq!(day5::part_1(&input)?, 143);
+//! # Ok::<(), aoc2024::error::Error>(())
+//! ```
+
+use std::{cmp::Ordering, fmt::Display, fs};
+
+use crate::error::{Error, Result};
+use crate::solution::Solution;
+use crate::util::hook;
+use crate::util::input::{sections, Line};
+use crate::util::random::Rng;
+use crate::util::trace::Fact;
 
 #[derive(Clone, Debug)]
 struct Page {
@@ -20,43 +37,67 @@ impl Page {
     }
 }
 
-pub fn solve_1(input: &str) -> u32 {
-    let data = fs::read_to_string(input).expect("Can't open file");
+/// The pages of an update the way the puzzle text lists them
+fn show(pages: &[u8]) -> String {
+    pages
+        .iter()
+        .map(u8::to_string)
+        .collect::<Vec<_>>()
+        .join(",")
+}
 
-    let order_pairs: Vec<(u8, u8)> = data
+pub struct Input {
+    rules: Vec<(u8, u8)>,
+    updates: Vec<Vec<u8>>,
+}
+
+fn parse_page(line: &Line, token: &str) -> Result<u8> {
+    // every page is 2 digits
+    match line.parse::<u8>(token)? {
+        page @ 0..=99 => Ok(page),
+        _ => Err(line.error_at(token, format!("page '{token}' has more than 2 digits"))),
+    }
+}
+
+pub fn parse(data: &str) -> Result<Input> {
+    let [rules, updates] = sections(data)[..] else {
+        return Err(Error::invalid(
+            "expected the rules and the updates separated by a blank line",
+        ));
+    };
+
+    let rules: Vec<(u8, u8)> = rules
         .lines()
-        .take_while(|line| !line.is_empty())
         .map(|line| {
-            let pages: Vec<&str> = line.split('|').collect();
-
-            let first = pages[0].parse::<u8>().unwrap();
-            let second = pages[1].parse::<u8>().unwrap();
-            (first, second)
+            let (first, second) = line.split_once("|")?;
+            Ok((parse_page(&line, first)?, parse_page(&line, second)?))
         })
-        .collect();
+        .collect::<Result<_>>()?;
 
-    let page_list: Vec<Vec<u8>> = data
+    let updates: Vec<Vec<u8>> = updates
         .lines()
-        .skip_while(|line| !line.is_empty())
-        .skip(1)
         .map(|line| {
-            let list = line
+            line.text
                 .split(',')
-                .map(|no| no.parse::<u8>().unwrap())
-                .collect();
-            list
+                .map(|no| parse_page(&line, no))
+                .collect()
         })
-        .collect();
+        .collect::<Result<_>>()?;
 
+    Ok(Input { rules, updates })
+}
+
+pub fn part_1(input: &Input) -> Result<u32> {
     // Push the page orders - index by what needs to be after
     let mut orders = vec![Page::new(false, Vec::new()); 100]; // every page is 2 digits
-    for (first, second) in order_pairs {
-        orders[second as usize].add_before(first);
+    for (first, second) in &input.rules {
+        orders[*second as usize].add_before(*first);
     }
 
     // Check the page lists
-    let valid_pages: Vec<Vec<u8>> = page_list
-        .into_iter()
+    let valid_pages: Vec<&Vec<u8>> = input
+        .updates
+        .iter()
         .filter(|pages| {
             // Discard all pages from the list that are invalid
             let mut curr_orders = orders.clone();
@@ -85,49 +126,31 @@ pub fn solve_1(input: &str) -> u32 {
         .collect();
 
     // Calculate the sum of the middle elements
-    valid_pages
+    Ok(valid_pages
         .iter()
-        .map(|page_list| page_list[page_list.len() / 2] as u32)
-        .sum()
-}
-
-pub fn solve_2(input: &str) -> u32 {
-    let data = fs::read_to_string(input).expect("Can't open file");
-
-    let order_pairs: Vec<(u8, u8)> = data
-        .lines()
-        .take_while(|line| !line.is_empty())
-        .map(|line| {
-            let pages: Vec<&str> = line.split('|').collect();
-
-            let first = pages[0].parse::<u8>().unwrap();
-            let second = pages[1].parse::<u8>().unwrap();
-            (first, second)
-        })
-        .collect();
-
-    let page_list: Vec<Vec<u8>> = data
-        .lines()
-        .skip_while(|line| !line.is_empty())
-        .skip(1)
-        .map(|line| {
-            let list = line
-                .split(',')
-                .map(|no| no.parse::<u8>().unwrap())
-                .collect();
-            list
+        .map(|page_list| {
+            let middle = page_list[page_list.len() / 2];
+            hook::fact(|| {
+                Fact::new("in order")
+                    .with("update", show(page_list))
+                    .with("middle", middle)
+            });
+            middle as u32
         })
-        .collect();
+        .sum())
+}
 
+pub fn part_2(input: &Input) -> Result<u32> {
     // Push the page orders - index by what needs to be after
     let mut orders = vec![Page::new(false, Vec::new()); 100]; // every page is 2 digits
-    for (first, second) in &order_pairs {
+    for (first, second) in &input.rules {
         orders[*second as usize].add_before(*first);
     }
 
     // Check the page lists
-    let mut invalid_pages: Vec<Vec<u8>> = page_list
-        .into_iter()
+    let mut invalid_pages: Vec<Vec<u8>> = input
+        .updates
+        .iter()
         .filter(|pages| {
             // Discard all pages from the list that are invalid
             let mut curr_orders = orders.clone();
@@ -150,42 +173,99 @@ pub fn solve_2(input: &str) -> u32 {
                 })
             })
         })
+        .cloned()
         .collect();
 
     let valid_pages: Vec<Vec<u8>> = invalid_pages
         .iter_mut()
         .map(|list| {
+            let before = show(list);
             // Make it valid by sorting by the presence of the page pair order
             list.sort_by(|a, b| {
-                if order_pairs.contains(&(*a, *b)) {
+                if input.rules.contains(&(*a, *b)) {
                     Ordering::Greater
                 } else {
                     Ordering::Less
                 }
             });
+            hook::fact(|| {
+                // The sort above puts the pages back to front, which keeps the same middle
+                let ordered: Vec<u8> = list.iter().rev().copied().collect();
+                Fact::new("reordered")
+                    .with("update", &before)
+                    .with("to", show(&ordered))
+                    .with("middle", list[list.len() / 2])
+            });
             list.clone()
         })
         .collect();
 
     // Calculate the sum of the middle elements
-    valid_pages
+    Ok(valid_pages
         .iter()
         .map(|page_list| page_list[page_list.len() / 2] as u32)
-        .sum()
+        .sum())
+}
+
+/// `size` updates of up to `size / 2` pages, with a rule for every pair of pages
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    // The order of the pages is the order they are shuffled in
+    let mut pages: Vec<u8> = (10..=99).collect();
+    rng.shuffle(&mut pages);
+    pages.truncate((size / 2).clamp(5, 90));
+
+    let mut rules = vec![];
+    for (i, first) in pages.iter().enumerate() {
+        for second in &pages[i + 1..] {
+            rules.push(format!("{first}|{second}"));
+        }
+    }
+    rng.shuffle(&mut rules);
+
+    let mut updates = vec![];
+    for _ in 0..size.max(1) {
+        let mut update = pages.clone();
+        rng.shuffle(&mut update);
+        update.truncate(rng.range(1..=(update.len().min(23) as i64 - 1) / 2) as usize * 2 + 1);
+        if rng.chance(0.5) {
+            update.sort_by_key(|page| pages.iter().position(|p| p == page));
+        }
+        let update: Vec<String> = update.iter().map(u8::to_string).collect();
+        updates.push(update.join(","));
+    }
+    format!("{}\n\n{}\n", rules.join("\n"), updates.join("\n"))
+}
+
+pub fn solve_1(input: &str) -> Result<u32> {
+    let data = fs::read_to_string(input)?;
+    part_1(&parse(&data)?)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    #[test]
-    fn part1() {
-        let result = solve_1("src/day5/test1.txt");
-        assert_eq!(result, 143);
+pub fn solve_2(input: &str) -> Result<u32> {
+    let data = fs::read_to_string(input)?;
+    part_2(&parse(&data)?)
+}
+
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    const DAY: u8 = 5;
+
+    type Input = Input;
+
+    fn parse(data: &str) -> Result<Self::Input> {
+        parse(data)
+    }
+
+    fn part_1(input: &Self::Input) -> Result<impl Display> {
+        part_1(input)
+    }
+
+    fn part_2(input: &Self::Input) -> Result<impl Display> {
+        part_2(input)
     }
 
-    #[test]
-    fn part2() {
-        let result = solve_2("src/day5/test2.txt");
-        assert_eq!(result, 123);
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        generate(rng, size)
     }
 }
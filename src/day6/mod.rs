@@ -1,262 +1,231 @@
-use std::{collections::HashSet, fmt::Debug, fs};
+//! Day 6: Guard Gallivant
+//!
+//! ```
+//! use aoc2024::day6;
+//!
+//! let input = day6::parse(&std::fs::read_to_string("src/day6/test1.txt")?)?;
+//! assert_eq!(day6::part_1(&input)?, 41);
+//! # Ok::<(), aoc2024::error::Error>(())
+//! ```
 
-// Totally unnecessary enums for everything!
-#[derive(Clone, Eq, Hash, PartialEq)]
-pub enum Orientation {
-    Up = 1,
-    Down = 2,
-    Left = 4,
-    Right = 8,
-}
+use std::{
+    collections::HashSet,
+    fmt::{Debug, Display},
+    fs,
+};
 
+use crate::error::{Error, Result};
+use crate::solution::Solution;
+use crate::util::coord::Coord;
+use crate::util::direction::Direction4;
+use crate::util::grid::Grid;
+use crate::util::hook;
+use crate::util::random::Rng;
+use crate::util::trace::Fact;
+use crate::util::viz::{Color, Frame};
+
+// Totally unnecessary enums for everything!
 #[derive(Clone)]
-pub enum FloorType {
+enum FloorType {
     Safe,
     Lava, // Visited by the guard
 }
 
 #[derive(Clone)]
-pub enum Tile {
+enum Tile {
     Floor(FloorType),
     Obstacle,
-    Guard(Orientation),
+    Guard(Direction4),
 }
 
 #[derive(PartialEq)]
-pub enum Route {
+enum Route {
     InProgress,
     Ended,
     Loop,
 }
 
 impl Tile {
-    pub fn from_char(c: char) -> Self {
+    fn from_char(c: char) -> Option<Self> {
         match c {
-            '.' => Self::Floor(FloorType::Safe),
-            'X' => Self::Floor(FloorType::Lava),
-            '#' => Self::Obstacle,
-            '^' => Self::Guard(Orientation::Up),
-            '>' => Self::Guard(Orientation::Right),
-            '<' => Self::Guard(Orientation::Left),
-            'v' => Self::Guard(Orientation::Down),
-            _ => panic!("Invalid character!"),
+            '.' => Some(Self::Floor(FloorType::Safe)),
+            'X' => Some(Self::Floor(FloorType::Lava)),
+            '#' => Some(Self::Obstacle),
+            '^' | '>' | '<' | 'v' => Direction4::from_char(c).map(Self::Guard),
+            _ => None,
+        }
+    }
+
+    fn to_char(&self) -> char {
+        match self {
+            Tile::Floor(FloorType::Safe) => '.',
+            Tile::Floor(FloorType::Lava) => 'X',
+            Tile::Obstacle => '#',
+            Tile::Guard(orientation) => orientation.to_char(),
         }
     }
 
-    pub fn turn(&mut self) {
+    fn turn(&mut self) {
         if let Tile::Guard(o) = self {
-            match o {
-                Orientation::Up => *o = Orientation::Right,
-                Orientation::Down => *o = Orientation::Left,
-                Orientation::Left => *o = Orientation::Up,
-                Orientation::Right => *o = Orientation::Down,
-            }
+            *o = o.turn_right();
         }
     }
 }
 
 impl Debug for Tile {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Tile::Floor(floor_type) => match floor_type {
-                FloorType::Safe => write!(f, "."),
-                FloorType::Lava => write!(f, "X"),
-            },
-            Tile::Obstacle => write!(f, "#"),
-            Tile::Guard(orientation) => match orientation {
-                Orientation::Up => write!(f, "^"),
-                Orientation::Down => write!(f, "v"),
-                Orientation::Left => write!(f, "<"),
-                Orientation::Right => write!(f, ">"),
-            },
-        }
+        write!(f, "{}", self.to_char())
     }
 }
 
-pub fn step(
-    map: &mut [Vec<Tile>],
-    mut path: Option<&mut HashSet<(usize, usize, Orientation)>>,
-) -> Route {
-    for row in 0..map.len() {
-        for col in 0..map[row].len() {
-            let c = &map[row][col];
-            match c {
-                Tile::Guard(orientation) => {
-                    //let mut new_map = map.to_vec();
-                    match orientation {
-                        Orientation::Up => {
-                            if row == 0 {
-                                if let Some(ref mut p) = path {
-                                    p.insert((row, col, Orientation::Up));
-                                }
-                                map[row][col] = Tile::Floor(FloorType::Lava);
-                                return Route::Ended;
-                            } else {
-                                let front = &map[row - 1][col];
-                                match front {
-                                    Tile::Floor(_) => {
-                                        if let Some(ref mut p) = path {
-                                            if !p.insert((row, col, Orientation::Up)) {
-                                                return Route::Loop;
-                                            }
-                                        }
-                                        map[row - 1][col] = Tile::Guard(Orientation::Up);
-                                        map[row][col] = Tile::Floor(FloorType::Lava);
-                                    }
-                                    Tile::Obstacle => map[row][col].turn(),
-                                    Tile::Guard(_) => panic!("Guard in front of guard?"),
-                                }
-                            }
-                        }
-                        Orientation::Down => {
-                            if row == map.len() - 1 {
-                                if let Some(ref mut p) = path {
-                                    p.insert((row, col, Orientation::Down));
-                                }
-                                map[row][col] = Tile::Floor(FloorType::Lava);
-                                return Route::Ended;
-                            } else {
-                                let front = &map[row + 1][col];
-                                match front {
-                                    Tile::Floor(_) => {
-                                        if let Some(ref mut p) = path {
-                                            if !p.insert((row, col, Orientation::Down)) {
-                                                return Route::Loop;
-                                            }
-                                        }
-                                        map[row + 1][col] = Tile::Guard(Orientation::Down);
-                                        map[row][col] = Tile::Floor(FloorType::Lava);
-                                    }
-                                    Tile::Obstacle => map[row][col].turn(),
-                                    Tile::Guard(_) => panic!("Guard in front of guard?"),
-                                }
-                            }
-                        }
-                        Orientation::Left => {
-                            if col == 0 {
-                                if let Some(ref mut p) = path {
-                                    p.insert((row, col, Orientation::Left));
-                                }
-                                map[row][col] = Tile::Floor(FloorType::Lava);
-                                return Route::Ended;
-                            } else {
-                                let front = &map[row][col - 1];
-                                match front {
-                                    Tile::Floor(_) => {
-                                        if let Some(ref mut p) = path {
-                                            if !p.insert((row, col, Orientation::Left)) {
-                                                return Route::Loop;
-                                            }
-                                        }
-                                        map[row][col - 1] = Tile::Guard(Orientation::Left);
-                                        map[row][col] = Tile::Floor(FloorType::Lava);
-                                    }
-                                    Tile::Obstacle => map[row][col].turn(),
-                                    Tile::Guard(_) => panic!("Guard in front of guard?"),
-                                }
-                            }
-                        }
-                        Orientation::Right => {
-                            if col == map[row].len() - 1 {
-                                if let Some(ref mut p) = path {
-                                    p.insert((row, col, Orientation::Right));
-                                }
-                                map[row][col] = Tile::Floor(FloorType::Lava);
-                                return Route::Ended;
-                            } else {
-                                let front = &map[row][col + 1];
-                                match front {
-                                    Tile::Floor(_) => {
-                                        if let Some(ref mut p) = path {
-                                            if !p.insert((row, col, Orientation::Right)) {
-                                                return Route::Loop;
-                                            }
-                                        }
-                                        map[row][col + 1] = Tile::Guard(Orientation::Right);
-                                        map[row][col] = Tile::Floor(FloorType::Lava);
-                                    }
-                                    Tile::Obstacle => map[row][col].turn(),
-                                    Tile::Guard(_) => panic!("Guard in front of guard?"),
-                                }
-                            }
-                        }
-                    }
+fn step(map: &mut Grid<Tile>, path: Option<&mut HashSet<(Coord, Direction4)>>) -> Route {
+    let guard = map.iter().find_map(|(coord, tile)| match tile {
+        Tile::Guard(orientation) => Some((coord, *orientation)),
+        _ => None,
+    });
+    // parse() made sure there's one, and it only leaves by walking off of the map
+    let Some((coord, orientation)) = guard else {
+        return Route::Ended;
+    };
+
+    let front = coord + orientation;
+    match map.get(front) {
+        // Walked off of the map
+        None => {
+            if let Some(p) = path {
+                p.insert((coord, orientation));
+            }
+            map[coord] = Tile::Floor(FloorType::Lava);
+            return Route::Ended;
+        }
+        Some(Tile::Floor(_)) => {
+            if let Some(p) = path {
+                if !p.insert((coord, orientation)) {
+                    return Route::Loop;
+                }
+            }
+            map[front] = Tile::Guard(orientation);
+            map[coord] = Tile::Floor(FloorType::Lava);
+        }
+        // parse() lets only one guard through, but another one would be in the way all the same
+        // Turning counts as a step of the path too, a guard boxed in would turn forever
+        Some(Tile::Obstacle | Tile::Guard(_)) => {
+            if let Some(p) = path {
+                if !p.insert((coord, orientation)) {
+                    return Route::Loop;
                 }
-                _ => continue,
             }
+            map[coord].turn()
         }
     }
     Route::InProgress
 }
 
-pub fn solve_1(input: &str) -> u32 {
-    let data = fs::read_to_string(input).expect("Can't open file");
-    let mut map: Vec<Vec<Tile>> = data
-        .lines()
-        .map(|l| l.chars().map(Tile::from_char).collect())
-        .collect();
+fn never_leaves() -> Error {
+    Error::no_solution("the guard never leaves")
+}
 
-    while step(&mut map, None) == Route::InProgress {
-        //for line in &map {
-        //    println!("{:?}", line);
-        //}
-        //println!();
+pub struct Input {
+    map: Grid<Tile>,
+}
+
+pub fn parse(data: &str) -> Result<Input> {
+    let map = Grid::parse(data, Tile::from_char)?;
+
+    // step() moves the first guard it finds, a second one would never move
+    let guards = map
+        .iter()
+        .filter(|(_, tile)| matches!(tile, Tile::Guard(_)))
+        .count();
+    if guards != 1 {
+        return Err(Error::invalid(format!(
+            "expected exactly one guard, found {guards}"
+        )));
+    }
+
+    Ok(Input { map })
+}
+
+pub fn part_1(input: &Input) -> Result<u32> {
+    let mut map = input.map.clone();
+    let mut path = HashSet::new();
+
+    let mut steps = 0;
+    loop {
+        match step(&mut map, Some(&mut path)) {
+            Route::InProgress => {}
+            Route::Ended => break,
+            Route::Loop => return Err(never_leaves()),
+        }
+        steps += 1;
+        hook::frame(|| {
+            Frame::new(&map, Tile::to_char)
+                .with_title(format!("Step {steps}"))
+                .color('X', Color::Yellow)
+                .color('#', Color::BrightBlack)
+                .highlight(
+                    map.iter()
+                        .filter(|(_, tile)| matches!(tile, Tile::Guard(_)))
+                        .map(|(coord, _)| coord),
+                    Color::Red,
+                )
+        });
     }
 
     let visited: Vec<&Tile> = map
         .iter()
-        .flatten()
+        .map(|(_, tile)| tile)
         .filter(|c| matches!(c, Tile::Floor(FloorType::Lava)))
         .collect();
+    hook::fact(|| {
+        Fact::new("walked out")
+            .with("steps", steps)
+            .with("visited", visited.len())
+    });
 
-    visited.len() as u32
+    Ok(visited.len() as u32)
 }
 
-pub fn solve_2(input: &str) -> u32 {
-    let data = fs::read_to_string(input).expect("Can't open file");
-    let original_map: Vec<Vec<Tile>> = data
-        .lines()
-        .map(|l| l.chars().map(Tile::from_char).collect())
-        .collect();
+pub fn part_2(input: &Input) -> Result<u32> {
+    let original_map = &input.map;
 
     let mut map = original_map.clone();
     // Do a pass of pt1 to get the path of the guard
-    let mut path: HashSet<(usize, usize, Orientation)> = HashSet::new();
-    while step(&mut map, Some(&mut path)) == Route::InProgress {
-        //for line in &map {
-        //    println!("{:?}", line);
-        //}
-        //println!();
+    let mut path: HashSet<(Coord, Direction4)> = HashSet::new();
+    loop {
+        match step(&mut map, Some(&mut path)) {
+            Route::InProgress => {}
+            Route::Ended => break,
+            Route::Loop => return Err(never_leaves()),
+        }
     }
     // Remove the guard's starting point
-    for (row, _) in original_map.iter().enumerate() {
-        for col in 0..original_map[row].len() {
-            if let Tile::Guard(_) = original_map[row][col] {
-                // Remove all possible movements through the starting point
-                path.remove(&(row, col, Orientation::Up));
-                path.remove(&(row, col, Orientation::Down));
-                path.remove(&(row, col, Orientation::Left));
-                path.remove(&(row, col, Orientation::Right));
+    for (coord, tile) in original_map.iter() {
+        if let Tile::Guard(_) = tile {
+            // Remove all possible movements through the starting point
+            for orientation in Direction4::ALL {
+                path.remove(&(coord, orientation));
             }
         }
     }
 
     // Create another set to filter out possible duplicates due to same coordinates, different orientation
-    let filtered_path: HashSet<(usize, usize)> = path.iter().map(|p| (p.0, p.1)).collect();
+    let filtered_path: HashSet<Coord> = path.iter().map(|p| p.0).collect();
 
     // Now we have a set of possible coordinates for obstacles
     let mut cnt = 0;
 
-    for (x, y) in filtered_path {
+    for coord in filtered_path {
         let mut new_map = original_map.clone();
-        let mut new_path: HashSet<(usize, usize, Orientation)> = HashSet::new();
+        let mut new_path: HashSet<(Coord, Direction4)> = HashSet::new();
 
-        new_map[x][y] = Tile::Obstacle;
+        new_map[coord] = Tile::Obstacle;
         loop {
             match step(&mut new_map, Some(&mut new_path)) {
                 Route::InProgress => continue,
                 Route::Ended => break,
                 Route::Loop => {
+                    hook::fact(|| Fact::new("loop").with("obstacle", coord));
                     cnt += 1;
                     break;
                 }
@@ -264,21 +233,97 @@ pub fn solve_2(input: &str) -> u32 {
         }
     }
 
-    cnt
+    Ok(cnt)
+}
+
+/// A `size` by `size` lab the guard walks out of
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    // part_1 would never end if the guard walked in circles
+    fn walks_out(map: &Grid<char>, mut guard: Coord) -> bool {
+        let mut facing = Direction4::Up;
+        let mut seen = HashSet::new();
+        while seen.insert((guard, facing)) {
+            match map.get(guard + facing) {
+                None => return true,
+                Some('#') => facing = facing.turn_right(),
+                Some(_) => guard += facing,
+            }
+        }
+        false
+    }
+
+    let size = size.max(1);
+    loop {
+        let mut map = Grid::new(size, size, '.');
+        for (_, tile) in map.iter_mut() {
+            if rng.chance(0.1) {
+                *tile = '#';
+            }
+        }
+        let guard = Coord::from_usize(rng.below(size), rng.below(size));
+        map[guard] = '^';
+        if walks_out(&map, guard) {
+            return format!("{map}\n");
+        }
+    }
+}
+
+pub fn solve_1(input: &str) -> Result<u32> {
+    let data = fs::read_to_string(input)?;
+    part_1(&parse(&data)?)
+}
+
+pub fn solve_2(input: &str) -> Result<u32> {
+    let data = fs::read_to_string(input)?;
+    part_2(&parse(&data)?)
+}
+
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    const DAY: u8 = 6;
+
+    type Input = Input;
+
+    fn parse(data: &str) -> Result<Self::Input> {
+        parse(data)
+    }
+
+    fn part_1(input: &Self::Input) -> Result<impl Display> {
+        part_1(input)
+    }
+
+    fn part_2(input: &Self::Input) -> Result<impl Display> {
+        part_2(input)
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        generate(rng, size)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+
     #[test]
-    fn part1() {
-        let result = solve_1("src/day6/test1.txt");
-        assert_eq!(result, 41);
+    fn malformed() {
+        let error = parse("..#\n.^?\n").err().unwrap();
+        assert_eq!(error.to_string(), "line 2, column 3: unknown tile '?'");
+        let error = parse("..#\n...\n").err().unwrap();
+        assert_eq!(
+            error.to_string(),
+            "invalid input: expected exactly one guard, found 0"
+        );
     }
 
     #[test]
-    fn part2() {
-        let result = solve_2("src/day6/test2.txt");
-        assert_eq!(result, 6);
+    fn guard_never_leaves() {
+        // Boxed in, and walking in a circle
+        for map in [".#.\n#^#\n.#.\n", ".#..\n...#\n#^..\n..#.\n"] {
+            let input = parse(map).unwrap();
+            assert!(part_1(&input).is_err(), "{map}");
+            assert!(part_2(&input).is_err(), "{map}");
+        }
     }
 }
@@ -1,4 +1,22 @@
-use std::fs;
+//! Day 7: Bridge Repair
+//!
+//! ```
+//! use aoc2024::day7;
+//!
+//! let input = day7::parse(&std::fs::read_to_string("src/day7/test1.txt")?)?;
+//! assert_eq!(day7::part_1(&input)?, 3749);
+//! # Ok::<(), aoc2024::error::Error>(())
+//! ```
+
+use std::{fmt::Display, fs};
+
+use crate::error::Result;
+use crate::solution::Solution;
+use crate::util::hook;
+use crate::util::input::lines;
+use crate::util::parse::list;
+use crate::util::random::Rng;
+use crate::util::trace::Fact;
 
 fn calc_possible_combinations(
     numbers: &[u64],
@@ -26,7 +44,7 @@ fn calc_possible_combinations(
     if pt2 {
         // Concatenation
         // Voodoo magic
-        let conc = result * 10u64.pow(next_sum.ilog10() + 1) + next_sum;
+        let conc = result * 10u64.pow(next_sum.checked_ilog10().unwrap_or(0) + 1) + next_sum;
         calc_possible_combinations(next_batch, conc, combinations, pt2);
     }
 }
@@ -41,45 +59,125 @@ fn calculate(test_val: &u64, nums: &[u64], pt2: bool) -> u64 {
     0
 }
 
-pub fn solve_1(input: &str) -> u64 {
-    let data = fs::read_to_string(input).expect("Can't open file");
-    data.lines()
-        .map(|line| {
-            let l: Vec<&str> = line.split(':').collect();
-            let test_value = l[0].parse::<u64>().unwrap();
-            let nums_str: Vec<&str> = l[1].split_whitespace().collect();
-            let nums: Vec<u64> = nums_str.iter().map(|s| s.parse::<u64>().unwrap()).collect();
+/// `calculate`, telling the observers whether the equation could be true
+fn explained(test_val: &u64, nums: &[u64], pt2: bool) -> u64 {
+    let value = calculate(test_val, nums, pt2);
+    hook::fact(|| {
+        Fact::new("equation")
+            .with("test_value", test_val)
+            .with(
+                "numbers",
+                nums.iter()
+                    .map(u64::to_string)
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            )
+            .with("possible", value != 0)
+    });
+    value
+}
 
-            calculate(&test_value, &nums, false)
-        })
-        .sum()
+pub struct Input {
+    equations: Vec<(u64, Vec<u64>)>,
 }
-pub fn solve_2(input: &str) -> u64 {
-    let data = fs::read_to_string(input).expect("Can't open file");
-    data.lines()
-        .map(|line| {
-            let l: Vec<&str> = line.split(':').collect();
-            let test_value = l[0].parse::<u64>().unwrap();
-            let nums_str: Vec<&str> = l[1].split_whitespace().collect();
-            let nums: Vec<u64> = nums_str.iter().map(|s| s.parse::<u64>().unwrap()).collect();
 
-            calculate(&test_value, &nums, true)
+pub fn parse(data: &str) -> Result<Input> {
+    let equations = lines(data)
+        .map(|line| {
+            let (test_value, nums_str) = line.split_once(":")?;
+            let test_value = line.parse::<u64>(test_value)?;
+            let nums: Vec<u64> = list(&line, nums_str, " ")?;
+            if nums.is_empty() {
+                return Err(line.error(line.text.len() + 1, "expected at least one number"));
+            }
+            Ok((test_value, nums))
         })
-        .sum()
+        .collect::<Result<_>>()?;
+    Ok(Input { equations })
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    #[test]
-    fn part1() {
-        let result = solve_1("src/day7/test1.txt");
-        assert_eq!(result, 3749);
+pub fn part_1(input: &Input) -> Result<u64> {
+    Ok(input
+        .equations
+        .iter()
+        .map(|(test_value, nums)| explained(test_value, nums, false))
+        .sum())
+}
+
+pub fn part_2(input: &Input) -> Result<u64> {
+    Ok(input
+        .equations
+        .iter()
+        .map(|(test_value, nums)| explained(test_value, nums, true))
+        .sum())
+}
+
+/// `size` equations, about half of them possible with all three operators
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut text = String::new();
+    for _ in 0..size {
+        // Few enough digits that even concatenating all of them fits
+        let mut digits = 18;
+        let mut numbers = vec![];
+        while numbers.len() < 12 && digits > 1 {
+            let number = if rng.chance(0.8) {
+                rng.range(1..=9)
+            } else {
+                rng.range(10..=99)
+            };
+            digits -= number.to_string().len();
+            numbers.push(number as u64);
+            if numbers.len() >= 2 && rng.chance(0.15) {
+                break;
+            }
+        }
+        let target = if rng.chance(0.5) {
+            numbers[1..]
+                .iter()
+                .fold(numbers[0], |total, &n| match rng.below(3) {
+                    0 => total + n,
+                    1 => total * n,
+                    _ => format!("{total}{n}").parse().unwrap(),
+                })
+        } else {
+            rng.range(1..=999_999) as u64
+        };
+        let numbers: Vec<String> = numbers.iter().map(u64::to_string).collect();
+        text += &format!("{target}: {}\n", numbers.join(" "));
+    }
+    text
+}
+
+pub fn solve_1(input: &str) -> Result<u64> {
+    let data = fs::read_to_string(input)?;
+    part_1(&parse(&data)?)
+}
+
+pub fn solve_2(input: &str) -> Result<u64> {
+    let data = fs::read_to_string(input)?;
+    part_2(&parse(&data)?)
+}
+
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    const DAY: u8 = 7;
+
+    type Input = Input;
+
+    fn parse(data: &str) -> Result<Self::Input> {
+        parse(data)
+    }
+
+    fn part_1(input: &Self::Input) -> Result<impl Display> {
+        part_1(input)
+    }
+
+    fn part_2(input: &Self::Input) -> Result<impl Display> {
+        part_2(input)
     }
 
-    #[test]
-    fn part2() {
-        let result = solve_2("src/day7/test2.txt");
-        assert_eq!(result, 11387);
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        generate(rng, size)
     }
 }
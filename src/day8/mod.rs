@@ -1,125 +1,90 @@
+//! Day 8: Resonant Collinearity
+//!
+//! ```
+//! use aoc2024::day8;
+//!
+//! let input = day8::parse(&std::fs::read_to_string("src/day8/test1.txt")?)?;
+//! assert_eq!(day8::part_1(&input)?, 14);
+//! # Ok::<(), aoc2024::error::Error>(())
+//! ```
+
 use itertools::Itertools;
 use std::collections::HashSet;
-use std::ops::{Add, AddAssign, Sub, SubAssign};
-use std::{collections::HashMap, fs};
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-struct Position {
-    col: isize,
-    row: isize,
-}
-
-impl Position {
-    fn new(col: usize, row: usize) -> Self {
-        Self {
-            col: col as isize,
-            row: row as isize,
-        }
-    }
-
-    fn validate(&self, max_width: isize, max_height: isize) -> bool {
-        self.row >= 0 && self.row < max_height && self.col >= 0 && self.col < max_width
-    }
+use std::{collections::HashMap, fmt::Display, fs};
+
+use crate::error::Result;
+use crate::solution::Solution;
+use crate::util::coord::Coord;
+use crate::util::grid::Grid;
+use crate::util::hook;
+use crate::util::input::lines;
+use crate::util::random::Rng;
+use crate::util::trace::Fact;
+
+pub struct Input {
+    antennae: HashMap<char, Vec<Coord>>,
+    /// Number of rows and columns
+    size: Coord,
 }
 
-impl Add for Position {
-    type Output = Position;
-
-    fn add(self, other: Self) -> Self::Output {
-        Self {
-            col: self.col + other.col,
-            row: self.row + other.row,
-        }
-    }
-}
-
-impl Sub for Position {
-    type Output = Position;
-
-    fn sub(self, other: Self) -> Self::Output {
-        Self {
-            col: self.col - other.col,
-            row: self.row - other.row,
-        }
-    }
-}
-
-impl AddAssign for Position {
-    fn add_assign(&mut self, other: Self) {
-        *self = Self {
-            col: self.col + other.col,
-            row: self.row + other.row,
-        };
-    }
-}
-
-impl SubAssign for Position {
-    fn sub_assign(&mut self, other: Self) {
-        *self = Self {
-            col: self.col - other.col,
-            row: self.row - other.row,
-        }
-    }
-}
-
-pub fn solve_1(input: &str) -> u32 {
-    let data = fs::read_to_string(input).expect("Can't open file");
-
-    let mut antennae: HashMap<char, Vec<Position>> = HashMap::new();
-    for (row, line) in data.lines().enumerate() {
+pub fn parse(data: &str) -> Result<Input> {
+    let mut antennae: HashMap<char, Vec<Coord>> = HashMap::new();
+    let rows: Vec<&str> = lines(data).map(|line| line.text).collect();
+    for (row, line) in rows.iter().enumerate() {
         for (col, c) in line.char_indices() {
             match c {
                 '.' => {}
                 _ => {
                     antennae
                         .entry(c)
-                        .and_modify(|v| v.push(Position::new(row, col)))
-                        .or_insert(vec![Position::new(row, col)]);
+                        .and_modify(|v| v.push(Coord::from_usize(row, col)))
+                        .or_insert(vec![Coord::from_usize(row, col)]);
                 }
             }
         }
     }
-    let (max_width, max_height) = (data.lines().count(), data.lines().next().unwrap().len());
-    let mut antinodes: HashSet<Position> = HashSet::new();
-    for pos in antennae.values() {
+    let size = Coord::from_usize(rows.len(), rows.first().map_or(0, |row| row.len()));
+    Ok(Input { antennae, size })
+}
+
+pub fn part_1(input: &Input) -> Result<u32> {
+    let size = input.size;
+    let mut antinodes: HashSet<Coord> = HashSet::new();
+    for (frequency, pos) in &input.antennae {
         for pos_pair in pos.iter().combinations(2) {
             let (antenna_1, antenna_2) = (pos_pair[0], pos_pair[1]);
             let dist = *antenna_2 - *antenna_1;
 
             let possible_anti_1 = *antenna_2 + dist;
             let possible_anti_2 = *antenna_1 - dist;
-
-            if possible_anti_1.validate(max_width as isize, max_height as isize) {
+            hook::fact(|| {
+                let on_map = [possible_anti_1, possible_anti_2]
+                    .iter()
+                    .filter(|anti| anti.in_bounds(size))
+                    .map(Coord::to_string)
+                    .collect::<Vec<_>>();
+                Fact::new("antennas")
+                    .with("frequency", frequency)
+                    .with("at", format!("{antenna_1} {antenna_2}"))
+                    .with("antinodes", on_map.join(" "))
+            });
+
+            if possible_anti_1.in_bounds(size) {
                 antinodes.insert(possible_anti_1);
             }
-            if possible_anti_2.validate(max_width as isize, max_height as isize) {
+            if possible_anti_2.in_bounds(size) {
                 antinodes.insert(possible_anti_2);
             }
         }
     }
 
-    antinodes.len() as u32
+    Ok(antinodes.len() as u32)
 }
-pub fn solve_2(input: &str) -> u32 {
-    let data = fs::read_to_string(input).expect("Can't open file");
 
-    let mut antennae: HashMap<char, Vec<Position>> = HashMap::new();
-    for (row, line) in data.lines().enumerate() {
-        for (col, c) in line.char_indices() {
-            match c {
-                '.' => {}
-                _ => {
-                    antennae
-                        .entry(c)
-                        .and_modify(|v| v.push(Position::new(row, col)))
-                        .or_insert(vec![Position::new(row, col)]);
-                }
-            }
-        }
-    }
-    let (max_width, max_height) = (data.lines().count(), data.lines().next().unwrap().len());
-    let mut antinodes: HashSet<Position> = HashSet::new();
-    for pos in antennae.values() {
+pub fn part_2(input: &Input) -> Result<u32> {
+    let size = input.size;
+    let mut antinodes: HashSet<Coord> = HashSet::new();
+    for (frequency, pos) in &input.antennae {
         for pos_pair in pos.iter().combinations(2) {
             let (antenna_1, antenna_2) = (pos_pair[0], pos_pair[1]);
             let dist = *antenna_2 - *antenna_1;
@@ -127,32 +92,75 @@ pub fn solve_2(input: &str) -> u32 {
             let mut possible_anti_1 = *antenna_1;
             let mut possible_anti_2 = *antenna_2;
 
-            while possible_anti_1.validate(max_width as isize, max_height as isize) {
-                antinodes.insert(possible_anti_1);
+            // Antinodes no other pair has found yet
+            let mut new = 0;
+            while possible_anti_1.in_bounds(size) {
+                new += usize::from(antinodes.insert(possible_anti_1));
                 possible_anti_1 += dist;
             }
-            while possible_anti_2.validate(max_width as isize, max_height as isize) {
-                antinodes.insert(possible_anti_2);
+            while possible_anti_2.in_bounds(size) {
+                new += usize::from(antinodes.insert(possible_anti_2));
                 possible_anti_2 -= dist;
             }
+            hook::fact(|| {
+                Fact::new("antennas")
+                    .with("frequency", frequency)
+                    .with("at", format!("{antenna_1} {antenna_2}"))
+                    .with("new_antinodes", new)
+            });
+        }
+    }
+
+    Ok(antinodes.len() as u32)
+}
+
+/// A `size` by `size` map with `size / 4` frequencies
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    const FREQUENCIES: &str = "0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+    let size = size.max(2);
+    let mut map = Grid::new(size, size, '.');
+    let mut free: Vec<Coord> = map.coords().collect();
+    rng.shuffle(&mut free);
+    for frequency in FREQUENCIES.chars().take((size / 4).max(1)) {
+        for _ in 0..rng.range(2..=5) {
+            if let Some(coord) = free.pop() {
+                map[coord] = frequency;
+            }
         }
     }
+    format!("{map}\n")
+}
+
+pub fn solve_1(input: &str) -> Result<u32> {
+    let data = fs::read_to_string(input)?;
+    part_1(&parse(&data)?)
+}
 
-    antinodes.len() as u32
+pub fn solve_2(input: &str) -> Result<u32> {
+    let data = fs::read_to_string(input)?;
+    part_2(&parse(&data)?)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    #[test]
-    fn part1() {
-        let result = solve_1("src/day8/test1.txt");
-        assert_eq!(result, 14);
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    const DAY: u8 = 8;
+
+    type Input = Input;
+
+    fn parse(data: &str) -> Result<Self::Input> {
+        parse(data)
+    }
+
+    fn part_1(input: &Self::Input) -> Result<impl Display> {
+        part_1(input)
+    }
+
+    fn part_2(input: &Self::Input) -> Result<impl Display> {
+        part_2(input)
     }
 
-    #[test]
-    fn part2() {
-        let result = solve_2("src/day8/test2.txt");
-        assert_eq!(result, 34);
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        generate(rng, size)
     }
 }
@@ -1,4 +1,23 @@
-use std::{fmt::Debug, fs};
+//! Day 9: Disk Fragmenter
+//!
+//! ```
+//! use aoc2024::day9;
+//!
+//! let input = day9::parse(&std::fs::read_to_string("src/day9/test1.txt")?)?;
+//! assert_eq!(day9::part_1(&input)?, 1928);
+//! # Ok::<(), aoc2024::error::Error>(())
+//! ```
+
+use std::{
+    fmt::{Debug, Display},
+    fs,
+};
+
+use crate::error::{Error, Result};
+use crate::solution::Solution;
+use crate::util::hook;
+use crate::util::random::Rng;
+use crate::util::trace::Fact;
 
 #[derive(Clone, Copy)]
 enum Block {
@@ -25,13 +44,16 @@ impl Debug for Block {
 }
 
 fn move_block(blocks: &mut [Block]) {
+    if blocks.is_empty() {
+        return;
+    }
     let mut left = 0;
     let mut right = blocks.len() - 1;
     loop {
-        while let Block::File { id: _ } = blocks[left] {
+        while left < blocks.len() && matches!(blocks[left], Block::File { .. }) {
             left += 1;
         }
-        while let Block::Free = blocks[right] {
+        while right > 0 && matches!(blocks[right], Block::Free) {
             right -= 1;
         }
         // Break if the two pointers meet
@@ -40,17 +62,31 @@ fn move_block(blocks: &mut [Block]) {
         }
         // Found a file block that can be swapped with a free block
         blocks.swap(right, left);
+        hook::fact(|| {
+            Fact::new("moved")
+                .with("file", blocks[left].get_id())
+                .with("length", 1)
+                .with("from", right)
+                .with("to", left)
+        });
     }
 }
 
 fn move_file(blocks: &mut [Block]) {
+    if blocks.is_empty() {
+        return;
+    }
     let mut right = blocks.len() - 1;
     loop {
         // Need to scan from the start every time for free space
         let mut left = 0;
-        while let Block::Free = blocks[right] {
+        while right > 0 && matches!(blocks[right], Block::Free) {
             right -= 1;
         }
+        // Nothing is left of the first block, which has nowhere to go anyway
+        if right == 0 {
+            break;
+        }
         // Found (the end of) a file block, check its length
         // We can move the right pointer, because if it doesn't fit anywhere
         // the file stays
@@ -65,7 +101,8 @@ fn move_file(blocks: &mut [Block]) {
         }
 
         while left < right {
-            while let Block::File { id: _ } = blocks[left] {
+            // Stop at the file itself, none of the space before it was big enough
+            while left < right && matches!(blocks[left], Block::File { .. }) {
                 left += 1;
             }
             // Found a chunk of free blocks, check length
@@ -79,13 +116,21 @@ fn move_file(blocks: &mut [Block]) {
                 left += 1;
                 free_len += 1;
             }
-            if free_len >= file_len && left <= right + 1 { // One "overlap" is allowed - it's when the data starts right after the found free space
+            if free_len >= file_len && left <= right + 1 {
+                // One "overlap" is allowed - it's when the data starts right after the found free space
                 let (l, r) = blocks.split_at_mut(left);
                 let start_left = left - free_len;
                 let start_right = right - (left - 1);
 
                 l[start_left..start_left + file_len]
                     .swap_with_slice(&mut r[start_right..start_right + file_len]);
+                hook::fact(|| {
+                    Fact::new("moved")
+                        .with("file", curr_id)
+                        .with("length", file_len)
+                        .with("from", right + 1)
+                        .with("to", start_left)
+                });
                 break;
             }
         }
@@ -107,62 +152,108 @@ fn checksum(blocks: &[Block]) -> u64 {
         .sum()
 }
 
-pub fn solve_1(input: &str) -> u64 {
+pub struct Input {
+    blocks: Vec<Block>,
+}
+
+pub fn parse(data: &str) -> Result<Input> {
     // Real input is 19999 chars, i.e. it is possible to store everything in a vector
-    let data = fs::read_to_string(input).expect("Can't open file");
-    let mut blocks: Vec<Block> = data
-        .chars()
-        .enumerate()
-        .flat_map(|(i, c)| {
-            let num = c.to_digit(10).unwrap();
-            if i % 2 == 0 {
-                // Data
-                vec![Block::File { id: i / 2 }; num as usize]
-            } else {
-                // Free
-                vec![Block::Free; num as usize]
-            }
-        })
-        .collect();
+    let mut blocks = vec![];
+    for (i, c) in data.trim_end().chars().enumerate() {
+        let num = c
+            .to_digit(10)
+            .ok_or_else(|| Error::parse(1, i + 1, format!("expected a digit, found '{c}'")))?;
+        if i % 2 == 0 {
+            // Data
+            blocks.extend(vec![Block::File { id: i / 2 }; num as usize]);
+        } else {
+            // Free
+            blocks.extend(vec![Block::Free; num as usize]);
+        }
+    }
+    Ok(Input { blocks })
+}
 
+pub fn part_1(input: &Input) -> Result<u64> {
+    let mut blocks = input.blocks.clone();
     move_block(&mut blocks);
 
-    checksum(&blocks)
+    Ok(checksum(&blocks))
 }
-pub fn solve_2(input: &str) -> u64 {
-    let data = fs::read_to_string(input).expect("Can't open file");
-    let mut blocks: Vec<Block> = data
-        .chars()
-        .enumerate()
-        .flat_map(|(i, c)| {
-            let num = c.to_digit(10).unwrap();
-            if i % 2 == 0 {
-                // Data
-                vec![Block::File { id: i / 2 }; num as usize]
-            } else {
-                // Free
-                vec![Block::Free; num as usize]
-            }
-        })
-        .collect();
 
+pub fn part_2(input: &Input) -> Result<u64> {
+    let mut blocks = input.blocks.clone();
     move_file(&mut blocks);
 
-    checksum(&blocks)
+    Ok(checksum(&blocks))
+}
+
+/// A disk map of `size` files
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut text = String::new();
+    for i in 0..size {
+        if i > 0 {
+            text += &rng.range(0..=9).to_string();
+        }
+        text += &rng.range(1..=9).to_string();
+    }
+    text + "\n"
+}
+
+pub fn solve_1(input: &str) -> Result<u64> {
+    let data = fs::read_to_string(input)?;
+    part_1(&parse(&data)?)
+}
+
+pub fn solve_2(input: &str) -> Result<u64> {
+    let data = fs::read_to_string(input)?;
+    part_2(&parse(&data)?)
+}
+
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    const DAY: u8 = 9;
+
+    type Input = Input;
+
+    fn parse(data: &str) -> Result<Self::Input> {
+        parse(data)
+    }
+
+    fn part_1(input: &Self::Input) -> Result<impl Display> {
+        part_1(input)
+    }
+
+    fn part_2(input: &Self::Input) -> Result<impl Display> {
+        part_2(input)
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        generate(rng, size)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+
     #[test]
-    fn part1() {
-        let result = solve_1("src/day9/test1.txt");
-        assert_eq!(result, 1928);
+    fn part2_no_space_before_file() {
+        let input = parse("456487608\n").unwrap();
+        assert_eq!(part_2(&input).unwrap(), 2352);
     }
 
     #[test]
-    fn part2() {
-        let result = solve_2("src/day9/test2.txt");
-        assert_eq!(result, 169);
+    fn no_free_space() {
+        for disk_map in ["", "0", "1", "10", "05"] {
+            let input = parse(disk_map).unwrap();
+            assert_eq!(part_1(&input).unwrap(), 0, "{disk_map:?}");
+            assert_eq!(part_2(&input).unwrap(), 0, "{disk_map:?}");
+        }
+        // Two files and no space between them, neither moves
+        let input = parse("1012\n").unwrap();
+        assert_eq!(part_1(&input).unwrap(), 1);
+        assert_eq!(part_2(&input).unwrap(), 1);
     }
 }
@@ -1,3 +1,97 @@
+//! Advent of Code 2024, one module per day.
+//!
+//! Every `dayN` has the same functions, which take the puzzle text as it is:
+//!
+//! - `parse(&str) -> Result<Input>` checks and reads the text,
+//! - `part_1(&Input)` and `part_2(&Input)` solve the parts (days 11, 14 and 18 also take the
+//!   numbers the puzzle text gives, which are different for the examples; days 14 and 18 work
+//!   theirs out from the input, and [`solution::Day::parse_with`] takes them as
+//!   [`solution::Params`]),
+//! - `solve_1(path)` and `solve_2(path)` do both for a file,
+//! - `generate(&mut Rng, size)` makes up a random input,
+//! - days 1, 2 and 22 also have `solve_reader(BufRead)`, which solves both parts while it reads,
+//! - `Puzzle` implements [`solution::Solution`] with the numbers of the real inputs.
+//!
+//! The days also send what they find out on the way, as [`util::trace::Fact`]s, to the observers
+//! installed with [`util::hook::observe`]. Nothing is made of them unless there's one.
+//!
+//! Every day has a cargo feature, all of them on by default, and only the ones that are on are
+//! built. [`registry::days`] has those as [`solution::Day`]s, to pick one by its number:
+//!
+//! ```
+//! # #[cfg(feature = "day1")] {
+//! let day = aoc2024::registry::get(1).unwrap();
+//! let input = day.parse("3   4\n4   3\n2   5\n1   3\n3   9\n3   3\n")?;
+//! assert_eq!(input.part_1()?, "11");
+//! assert_eq!(input.part_2()?, "31");
+//! # }
+//! # Ok::<(), aoc2024::error::Error>(())
+//! ```
+
+pub mod answers;
+#[cfg(feature = "day1")]
+pub mod day1;
+#[cfg(feature = "day10")]
+pub mod day10;
+#[cfg(feature = "day11")]
+pub mod day11;
+#[cfg(feature = "day12")]
+pub mod day12;
+#[cfg(feature = "day13")]
+pub mod day13;
+#[cfg(feature = "day14")]
+pub mod day14;
+#[cfg(feature = "day15")]
+pub mod day15;
+#[cfg(feature = "day16")]
+pub mod day16;
+#[cfg(feature = "day17")]
+pub mod day17;
+#[cfg(feature = "day18")]
+pub mod day18;
+#[cfg(feature = "day19")]
+pub mod day19;
+#[cfg(feature = "day2")]
+pub mod day2;
+#[cfg(feature = "day20")]
+pub mod day20;
+#[cfg(feature = "day21")]
+pub mod day21;
+#[cfg(feature = "day22")]
+pub mod day22;
+#[cfg(feature = "day23")]
+pub mod day23;
+#[cfg(feature = "day24")]
+pub mod day24;
+#[cfg(feature = "day3")]
+pub mod day3;
+#[cfg(feature = "day4")]
+pub mod day4;
+#[cfg(feature = "day5")]
+pub mod day5;
+#[cfg(feature = "day6")]
+pub mod day6;
+#[cfg(feature = "day7")]
+pub mod day7;
+#[cfg(feature = "day8")]
+pub mod day8;
+#[cfg(feature = "day9")]
+pub mod day9;
+pub mod error;
+pub mod registry;
+pub mod solution;
+
 pub mod util {
     pub mod coord;
+    pub mod direction;
+    pub mod grid;
+    pub mod hook;
+    pub mod input;
+    pub mod parse;
+    pub mod png;
+    pub mod progress;
+    pub mod random;
+    pub mod search;
+    pub mod trace;
+    pub mod viz;
 }
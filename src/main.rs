@@ -1,175 +1,207 @@
-pub mod day1;
-pub mod day10;
-pub mod day11;
-pub mod day12;
-pub mod day13;
-pub mod day14;
-pub mod day15;
-pub mod day16;
-pub mod day17;
-pub mod day18;
-pub mod day19;
-pub mod day2;
-pub mod day20;
-pub mod day21;
-pub mod day22;
-pub mod day23;
-pub mod day24;
-pub mod day3;
-pub mod day4;
-pub mod day5;
-pub mod day6;
-pub mod day7;
-pub mod day8;
-pub mod day9;
-
-fn main() {
-    let day1_1 = day1::solve_1("src/day1/input.txt");
-    let day1_2 = day1::solve_2("src/day1/input.txt");
-    println!("Day 1/1: {day1_1}");
-    println!("Day 1/2: {day1_2}");
-    println!();
-
-    let day2_1 = day2::solve_1("src/day2/input.txt");
-    let day2_2 = day2::solve_2("src/day2/input.txt");
-    println!("Day 2/1: {day2_1}");
-    println!("Day 2/2: {day2_2}");
-    println!();
-
-    let day3_1 = day3::solve_1("src/day3/input.txt");
-    let day3_2 = day3::solve_2("src/day3/input.txt");
-    println!("Day 3/1: {day3_1}");
-    println!("Day 3/2: {day3_2}");
-    println!();
-
-    let day4_1 = day4::solve_1("src/day4/input.txt");
-    let day4_2 = day4::solve_2("src/day4/input.txt");
-    println!("Day 4/1: {day4_1}");
-    println!("Day 4/2: {day4_2}");
-    println!();
-
-    let day5_1 = day5::solve_1("src/day5/input.txt");
-    let day5_2 = day5::solve_2("src/day5/input.txt");
-    println!("Day 5/1: {day5_1}");
-    println!("Day 5/2: {day5_2}");
-    println!();
-
-    let day6_1 = day6::solve_1("src/day6/input.txt");
-    // this is way too slow - TODO redo with a proper algorithm
-    //let day6_2 = day6::solve_2("src/day6/input.txt");
-    println!("Day 6/1: {day6_1}");
-    //println!("Day 6/2: {day6_2}");
-    println!("Day 6/2: 1951");
-    println!();
-
-    let day7_1 = day7::solve_1("src/day7/input.txt");
-    let day7_2 = day7::solve_2("src/day7/input.txt");
-    println!("Day 7/1: {day7_1}");
-    println!("Day 7/2: {day7_2}");
-    println!();
-
-    let day8_1 = day8::solve_1("src/day8/input.txt");
-    let day8_2 = day8::solve_2("src/day8/input.txt");
-    println!("Day 8/1: {day8_1}");
-    println!("Day 8/2: {day8_2}");
-    println!();
-
-    let day9_1 = day9::solve_1("src/day9/input.txt");
-    let day9_2 = day9::solve_2("src/day9/input.txt");
-    println!("Day 9/1: {day9_1}");
-    println!("Day 9/2: {day9_2}");
-    println!();
-
-    let day10_1 = day10::solve_1("src/day10/input.txt");
-    let day10_2 = day10::solve_2("src/day10/input.txt");
-    println!("Day 10/1: {day10_1}");
-    println!("Day 10/2: {day10_2}");
-    println!();
-
-    let day11_1 = day11::solve_1("src/day11/input.txt", 25);
-    let day11_2 = day11::solve_2("src/day11/input.txt", 75);
-    println!("Day 11/1: {day11_1}");
-    println!("Day 11/2: {day11_2}");
-    println!();
-
-    let day12_1 = day12::solve_1("src/day12/input.txt");
-    let day12_2 = day12::solve_2("src/day12/input.txt");
-    println!("Day 12/1: {day12_1}");
-    println!("Day 12/2: {day12_2}");
-    println!();
-
-    let day13_1 = day13::solve_1("src/day13/input.txt");
-    let day13_2 = day13::solve_2("src/day13/input.txt");
-    println!("Day 13/1: {day13_1}");
-    println!("Day 13/2: {day13_2}");
-    println!();
-
-    // slow
-    //let day14_1 = day14::solve_1("src/day14/input.txt");
-    //let day14_2 = day14::solve_2("src/day14/input.txt");
-    //println!("Day 14/1: {day14_1}");
-    //println!("Day 14/2: {day14_2}");
-    println!("Day 14/1: 217328832");
-    println!("Day 14/2: 7412");
-    println!();
-
-    let day15_1 = day15::solve_1("src/day15/input.txt");
-    let day15_2 = day15::solve_2("src/day15/input.txt");
-    println!("Day 15/1: {day15_1}");
-    println!("Day 15/2: {day15_2}");
-    println!();
-
-    let day16_1 = day16::solve_1("src/day16/input.txt");
-    let day16_2 = day16::solve_2("src/day16/input.txt");
-    println!("Day 16/1: {day16_1}");
-    println!("Day 16/2: {day16_2}");
-    println!();
-
-    let day17_1 = day17::solve_1("src/day17/input.txt");
-    let day17_2 = day17::solve_2("src/day17/input.txt");
-    println!("Day 17/1: {day17_1}");
-    println!("Day 17/2: {day17_2}");
-    println!();
-
-    let day18_1 = day18::solve_1("src/day18/input.txt");
-    let day18_2 = day18::solve_2("src/day18/input.txt");
-    println!("Day 18/1: {day18_1}");
-    println!("Day 18/2: {:?}", day18_2);
-    println!();
-
-    let day19_1 = day19::solve_1("src/day19/input.txt");
-    let day19_2 = day19::solve_2("src/day19/input.txt");
-    println!("Day 19/1: {day19_1}");
-    println!("Day 19/2: {day19_2}");
-    println!();
-
-    let day20_1 = day20::solve_1("src/day20/input.txt");
-    let day20_2 = day20::solve_2("src/day20/input.txt");
-    println!("Day 20/1: {day20_1}");
-    println!("Day 20/2: {day20_2}");
-    println!();
+use std::{
+    env,
+    fs::{self, File},
+    io::{self, BufRead, BufReader},
+    path::Path,
+    process,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use aoc2024::error::Error;
+use aoc2024::registry;
+use aoc2024::solution::{Day, Params};
+use aoc2024::util::hook;
+use aoc2024::util::png::PngWriter;
+use aoc2024::util::progress::Reporter;
+use aoc2024::util::trace::Explainer;
+use aoc2024::util::viz::Terminal;
+use cli::{Command, GenerateArgs, InputSource, RunArgs, Selection};
+use runner::Outcome;
+use timing::DayTimings;
+
+mod cli;
+mod runner;
+mod scaffold;
+mod timing;
+mod verify;
+
+fn read_input(day: &Day, source: Option<&InputSource>) -> io::Result<String> {
+    match source {
+        None => fs::read_to_string(day.input_path()),
+        Some(InputSource::Path(path)) => fs::read_to_string(path),
+        Some(InputSource::Stdin) => io::read_to_string(io::stdin()),
+    }
+}
 
-    let day21_1 = day21::solve_1("src/day21/input.txt");
-    let day21_2 = day21::solve_2("src/day21/input.txt");
-    println!("Day 21/1: {day21_1}");
-    println!("Day 21/2: {day21_2}");
-    println!();
+/// `run --stream`: both parts at once while the input is read, without the runner in between
+fn stream(day: Day, args: &RunArgs) -> Result<(), String> {
+    let n = day.number;
+    let mut reader: Box<dyn BufRead> = match &args.input {
+        None => Box::new(BufReader::new(
+            File::open(day.input_path()).map_err(|e| format!("Day {n}: {}", Error::from(e)))?,
+        )),
+        Some(InputSource::Path(path)) => Box::new(BufReader::new(
+            File::open(path).map_err(|e| format!("Day {n}: {}", Error::from(e)))?,
+        )),
+        Some(InputSource::Stdin) => Box::new(io::stdin().lock()),
+    };
+
+    hook::observe(Box::new(Reporter::new(Duration::from_millis(200))));
+    let answers = day.stream(&mut reader);
+    hook::stop().map_err(|e| e.to_string())?;
+    let (part_1, part_2) = answers
+        .ok_or(format!("Day {n} can't stream its input"))?
+        .map_err(|e| format!("Day {n}: {e}"))?;
+    for (part, answer) in [(1, part_1), (2, part_2)] {
+        if args.runs_part(part) {
+            println!("Day {n}/{part}: {answer}");
+        }
+    }
+    println!();
+    Ok(())
+}
 
-    let day22_1 = day22::solve_1("src/day22/input.txt");
-    let day22_2 = day22::solve_2("src/day22/input.txt");
-    println!("Day 22/1: {day22_1}");
-    println!("Day 22/2: {day22_2}");
-    println!();
+fn run(args: &RunArgs) -> Result<(), String> {
+    let days = match args.days {
+        Selection::All => registry::days(),
+        Selection::Day(n) => {
+            vec![registry::get(n).ok_or(format!("Day {n} is not solved yet or not built in"))?]
+        }
+    };
+    if args.explain {
+        hook::observe(Box::new(Explainer));
+    }
+    if args.stream {
+        return stream(days[0], args);
+    }
+
+    // A broken day shouldn't stop the others, report it and carry on
+    let mut failures = 0;
+    let mut inputs = vec![];
+    for day in days {
+        match read_input(&day, args.input.as_ref()) {
+            Ok(data) => inputs.push((day, data, Params::default())),
+            Err(e) => {
+                eprintln!("Day {}: {}\n", day.number, Error::from(e));
+                failures += 1;
+            }
+        }
+    }
+
+    let options = runner::Options {
+        parts: [1, 2].into_iter().filter(|&p| args.runs_part(p)).collect(),
+        jobs: args.jobs,
+        timeout: args.timeout,
+    };
+    let timed_out = || format!("timed out after {:?}", args.timeout.unwrap_or_default());
+    if args.visualize {
+        hook::observe(Box::new(Terminal::new(args.delay)));
+    }
+    if let Some(dir) = &args.png {
+        let mut writer = PngWriter::new(dir)
+            .scale(args.scale)
+            .palette(args.palette.clone());
+        if let Some(frames) = &args.frames {
+            writer = writer.frames(frames.clone());
+        }
+        hook::observe(Box::new(writer));
+    }
+    let mut timings = vec![];
+    runner::run(inputs, &options, |report| {
+        let n = report.day;
+        let mut timing = match report.parse {
+            Outcome::Done(Ok(()), time) => DayTimings::new(n, time),
+            Outcome::Done(Err(e), _) => {
+                eprintln!("Day {n}: {e}\n");
+                failures += 1;
+                return;
+            }
+            Outcome::Timeout => {
+                eprintln!("Day {n}: {}\n", timed_out());
+                failures += 1;
+                return;
+            }
+        };
+        for (part, outcome) in report.parts {
+            match outcome {
+                Outcome::Done(Ok(answer), time) => {
+                    timing.set(part, time);
+                    println!("Day {n}/{part}: {answer}");
+                }
+                Outcome::Done(Err(e), time) => {
+                    timing.set(part, time);
+                    eprintln!("Day {n}/{part}: {e}");
+                    failures += 1;
+                }
+                Outcome::Timeout => {
+                    eprintln!("Day {n}/{part}: {}", timed_out());
+                    failures += 1;
+                }
+            }
+        }
+        timings.push(timing);
+        println!();
+    });
+    if let Err(e) = hook::stop() {
+        eprintln!("Can't save the frames: {e}\n");
+        failures += 1;
+    }
+
+    if args.timings {
+        print!("{}", timing::table(&timings));
+    }
+    if let Some((path, format)) = &args.timings_out {
+        fs::write(path, format.render(&timings))
+            .map_err(|e| format!("Can't write {}: {e}", path.display()))?;
+    }
+
+    match failures {
+        0 => Ok(()),
+        1 => Err("1 puzzle failed".to_string()),
+        n => Err(format!("{n} puzzles failed")),
+    }
+}
 
-    let day23_1 = day23::solve_1("src/day23/input.txt");
-    let day23_2 = day23::solve_2("src/day23/input.txt");
-    println!("Day 23/1: {day23_1}");
-    println!("Day 23/2: {day23_2}");
-    println!();
+fn generate(args: &GenerateArgs) -> Result<(), String> {
+    let day = registry::get(args.day).ok_or(format!(
+        "Day {} is not solved yet or not built in",
+        args.day
+    ))?;
+    let seed = args.seed.unwrap_or_else(|| {
+        let seed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |time| time.as_nanos() as u64);
+        // On stderr, so that it doesn't end up in the input
+        eprintln!("Seed: {seed}");
+        seed
+    });
+    print!("{}", day.generate(seed, args.size));
+    Ok(())
+}
 
-    let day24_1 = day24::solve_1("src/day24/input.txt");
-    let day24_2 = day24::solve_2("src/day24/input.txt");
-    println!("Day 24/1: {day24_1}");
-    println!("Day 24/2: {day24_2}");
-    println!();
+fn main() {
+    let command = match cli::parse(env::args().skip(1)) {
+        Ok(command) => command,
+        Err(e) => {
+            eprintln!("{e}\n\n{}", cli::USAGE);
+            process::exit(2);
+        }
+    };
+
+    let result = match command {
+        Command::Help => {
+            println!("{}", cli::USAGE);
+            Ok(())
+        }
+        Command::Run(args) => run(&args),
+        Command::Verify(args) => verify::verify(&args),
+        Command::Generate(args) => generate(&args),
+        Command::NewDay(n) => scaffold::new_day(Path::new("."), n).map(|()| {
+            println!("Created src/day{n}, add the puzzle input to src/day{n}/input.txt");
+        }),
+    };
+    if let Err(e) = result {
+        eprintln!("{e}");
+        process::exit(1);
+    }
 }
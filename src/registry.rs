@@ -0,0 +1,81 @@
+use crate::solution::Day;
+
+// Nothing to use when every day is left out
+#[allow(unused_imports)]
+use crate::*;
+
+/// Every solved day that is built in, in calendar order
+pub fn days() -> Vec<Day> {
+    vec![
+        #[cfg(feature = "day1")]
+        Day::new::<day1::Puzzle>(),
+        #[cfg(feature = "day2")]
+        Day::new::<day2::Puzzle>(),
+        #[cfg(feature = "day3")]
+        Day::new::<day3::Puzzle>(),
+        #[cfg(feature = "day4")]
+        Day::new::<day4::Puzzle>(),
+        #[cfg(feature = "day5")]
+        Day::new::<day5::Puzzle>(),
+        #[cfg(feature = "day6")]
+        Day::new::<day6::Puzzle>(),
+        #[cfg(feature = "day7")]
+        Day::new::<day7::Puzzle>(),
+        #[cfg(feature = "day8")]
+        Day::new::<day8::Puzzle>(),
+        #[cfg(feature = "day9")]
+        Day::new::<day9::Puzzle>(),
+        #[cfg(feature = "day10")]
+        Day::new::<day10::Puzzle>(),
+        #[cfg(feature = "day11")]
+        Day::new::<day11::Puzzle>(),
+        #[cfg(feature = "day12")]
+        Day::new::<day12::Puzzle>(),
+        #[cfg(feature = "day13")]
+        Day::new::<day13::Puzzle>(),
+        #[cfg(feature = "day14")]
+        Day::new::<day14::Puzzle>(),
+        #[cfg(feature = "day15")]
+        Day::new::<day15::Puzzle>(),
+        #[cfg(feature = "day16")]
+        Day::new::<day16::Puzzle>(),
+        #[cfg(feature = "day17")]
+        Day::new::<day17::Puzzle>(),
+        #[cfg(feature = "day18")]
+        Day::new::<day18::Puzzle>(),
+        #[cfg(feature = "day19")]
+        Day::new::<day19::Puzzle>(),
+        #[cfg(feature = "day20")]
+        Day::new::<day20::Puzzle>(),
+        #[cfg(feature = "day21")]
+        Day::new::<day21::Puzzle>(),
+        #[cfg(feature = "day22")]
+        Day::new::<day22::Puzzle>(),
+        #[cfg(feature = "day23")]
+        Day::new::<day23::Puzzle>(),
+        #[cfg(feature = "day24")]
+        Day::new::<day24::Puzzle>(),
+    ]
+}
+
+pub fn get(number: u8) -> Option<Day> {
+    days().into_iter().find(|day| day.number == number)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generated_inputs_parse() {
+        for day in days() {
+            for seed in 0..3 {
+                let text = day.generate(seed, 10);
+                assert_eq!(text, day.generate(seed, 10), "day {}", day.number);
+                if let Err(e) = day.parse(&text) {
+                    panic!("day {}, seed {seed}: {e}\n{text}", day.number);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,261 @@
+use std::{
+    collections::VecDeque,
+    panic::{self, AssertUnwindSafe},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc, Arc, Condvar, Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use aoc2024::error::{Error, Result};
+use aoc2024::solution::{Day, Params, Parsed};
+
+/// How a step of a day ended, with the time it took
+pub enum Outcome<T> {
+    Done(Result<T>, Duration),
+    Timeout,
+}
+
+/// Everything that happened to one day, handed out in calendar order
+pub struct Report {
+    pub day: u8,
+    pub parse: Outcome<()>,
+    /// Only the parts that were started, nothing if parsing failed
+    pub parts: Vec<(u8, Outcome<String>)>,
+}
+
+pub struct Options {
+    pub parts: Vec<u8>,
+    /// Number of worker threads, 1 solves everything one after the other
+    pub jobs: usize,
+    /// Time limit of a day, counted from the moment its parsing starts
+    pub timeout: Option<Duration>,
+}
+
+#[derive(Clone, Copy)]
+enum Task {
+    Parse(usize),
+    Solve(usize, u8),
+}
+
+impl Task {
+    fn day(self) -> usize {
+        match self {
+            Task::Parse(i) | Task::Solve(i, _) => i,
+        }
+    }
+
+    /// Stand-in for the result of a task that panicked, the panic message is already on stderr
+    fn panicked(self) -> Event {
+        let error = || Error::invalid("the solver panicked");
+        match self {
+            Task::Parse(i) => Event::Parsed(i, Outcome::Done(Err(error()), Duration::ZERO)),
+            Task::Solve(i, part) => {
+                Event::Solved(i, part, Outcome::Done(Err(error()), Duration::ZERO))
+            }
+        }
+    }
+}
+
+enum Event {
+    Started(usize),
+    Parsed(usize, Outcome<()>),
+    Solved(usize, u8, Outcome<String>),
+}
+
+struct Shared {
+    days: Vec<(Day, String, Params)>,
+    parts: Vec<u8>,
+    queue: Mutex<VecDeque<Task>>,
+    ready: Condvar,
+    parsed: Mutex<Vec<Option<Arc<dyn Parsed>>>>,
+    cancelled: Vec<AtomicBool>,
+    finished: AtomicBool,
+}
+
+impl Shared {
+    fn next_task(&self) -> Option<Task> {
+        let mut queue = self.queue.lock().unwrap();
+        loop {
+            if self.finished.load(Ordering::Relaxed) {
+                return None;
+            }
+            match queue.pop_front() {
+                Some(task) if self.cancelled[task.day()].load(Ordering::Relaxed) => continue,
+                Some(task) => return Some(task),
+                None => queue = self.ready.wait(queue).unwrap(),
+            }
+        }
+    }
+
+    fn execute(&self, task: Task) -> Event {
+        match task {
+            Task::Parse(i) => {
+                let (day, data, params) = &self.days[i];
+                let start = Instant::now();
+                let parsed = day.parse_with(data, params);
+                let time = start.elapsed();
+                let parsed = parsed.map(|puzzle| {
+                    self.parsed.lock().unwrap()[i] = Some(Arc::from(puzzle));
+                    // The parts of a day go first, so days are finished (and reported) in order
+                    let mut queue = self.queue.lock().unwrap();
+                    for &part in self.parts.iter().rev() {
+                        queue.push_front(Task::Solve(i, part));
+                    }
+                    self.ready.notify_all();
+                });
+                Event::Parsed(i, Outcome::Done(parsed, time))
+            }
+            Task::Solve(i, part) => {
+                let puzzle = self.parsed.lock().unwrap()[i].clone().unwrap();
+                let start = Instant::now();
+                let answer = match part {
+                    1 => puzzle.part_1(),
+                    _ => puzzle.part_2(),
+                };
+                Event::Solved(i, part, Outcome::Done(answer, start.elapsed()))
+            }
+        }
+    }
+}
+
+fn spawn_worker(shared: &Arc<Shared>, events: &mpsc::Sender<Event>) {
+    let shared = Arc::clone(shared);
+    let events = events.clone();
+    thread::spawn(move || {
+        while let Some(task) = shared.next_task() {
+            if events.send(Event::Started(task.day())).is_err() {
+                return;
+            }
+            let event = panic::catch_unwind(AssertUnwindSafe(|| shared.execute(task)))
+                .unwrap_or_else(|_| task.panicked());
+            if events.send(event).is_err() {
+                return;
+            }
+        }
+    });
+}
+
+#[derive(Default)]
+struct DayState {
+    started: Option<Instant>,
+    running: usize,
+    parse: Option<Outcome<()>>,
+    parts: Vec<(u8, Outcome<String>)>,
+    timed_out: bool,
+    reported: bool,
+}
+
+impl DayState {
+    fn is_finished(&self, parts: usize) -> bool {
+        match &self.parse {
+            _ if self.timed_out || self.reported => true,
+            Some(Outcome::Done(Ok(()), _)) => self.parts.len() == parts,
+            Some(_) => true,
+            None => false,
+        }
+    }
+}
+
+/// Solves the given days, with their puzzle texts and parameters, on `options.jobs` threads and calls `report` for each of them in calendar order.
+/// A day running over the timeout is reported as such; its thread can't be stopped, so it's left running
+/// in the background and a new worker takes its place.
+pub fn run(days: Vec<(Day, String, Params)>, options: &Options, mut report: impl FnMut(Report)) {
+    let count = days.len();
+    let shared = Arc::new(Shared {
+        queue: Mutex::new((0..count).map(Task::Parse).collect()),
+        ready: Condvar::new(),
+        parsed: Mutex::new(vec![None; count]),
+        cancelled: (0..count).map(|_| AtomicBool::new(false)).collect(),
+        finished: AtomicBool::new(false),
+        parts: options.parts.clone(),
+        days,
+    });
+    let (tx, rx) = mpsc::channel();
+    for _ in 0..options.jobs.max(1) {
+        spawn_worker(&shared, &tx);
+    }
+
+    let mut states: Vec<DayState> = (0..count).map(|_| DayState::default()).collect();
+    let mut next = 0;
+    while next < count {
+        let deadline = options.timeout.and_then(|timeout| {
+            states
+                .iter()
+                .filter(|state| !state.is_finished(options.parts.len()))
+                .filter_map(|state| state.started)
+                .min()
+                .map(|started| started + timeout)
+        });
+        let event = match deadline {
+            Some(deadline) => rx.recv_timeout(deadline.saturating_duration_since(Instant::now())),
+            None => rx.recv().map_err(|_| mpsc::RecvTimeoutError::Disconnected),
+        };
+
+        match event {
+            Ok(Event::Started(i)) => {
+                states[i].started.get_or_insert_with(Instant::now);
+                states[i].running += 1;
+            }
+            Ok(Event::Parsed(i, outcome)) => {
+                states[i].running -= 1;
+                if !states[i].timed_out && !states[i].reported {
+                    states[i].parse = Some(outcome);
+                }
+            }
+            Ok(Event::Solved(i, part, outcome)) => {
+                states[i].running -= 1;
+                if !states[i].timed_out && !states[i].reported {
+                    states[i].parts.push((part, outcome));
+                }
+            }
+            // The workers never hang up, `tx` is still here
+            Err(_) => {}
+        }
+
+        if let Some(timeout) = options.timeout {
+            for (i, state) in states.iter_mut().enumerate() {
+                let overdue = state
+                    .started
+                    .is_some_and(|started| started.elapsed() >= timeout);
+                if overdue && !state.is_finished(options.parts.len()) {
+                    state.timed_out = true;
+                    shared.cancelled[i].store(true, Ordering::Relaxed);
+                    // The stuck threads are lost, replace them
+                    for _ in 0..state.running {
+                        spawn_worker(&shared, &tx);
+                    }
+                }
+            }
+        }
+
+        while next < count && states[next].is_finished(options.parts.len()) {
+            let state = &mut states[next];
+            state.reported = true;
+            let parse = state.parse.take().unwrap_or(Outcome::Timeout);
+            let mut parts = std::mem::take(&mut state.parts);
+            parts.sort_by_key(|(part, _)| *part);
+            if state.timed_out && matches!(parse, Outcome::Done(Ok(()), _)) {
+                let solved: Vec<u8> = parts.iter().map(|(part, _)| *part).collect();
+                parts.extend(
+                    options
+                        .parts
+                        .iter()
+                        .filter(|part| !solved.contains(part))
+                        .map(|&part| (part, Outcome::Timeout)),
+                );
+            }
+            report(Report {
+                day: shared.days[next].0.number,
+                parse,
+                parts,
+            });
+            next += 1;
+        }
+    }
+
+    shared.finished.store(true, Ordering::Relaxed);
+    shared.ready.notify_all();
+}
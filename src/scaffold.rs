@@ -0,0 +1,152 @@
+use std::{fs, path::Path};
+
+const TEMPLATE: &str = "day_mod_template.rs";
+
+/// Creates `src/dayN` from the template in the repository at `root` and registers it in lib.rs,
+/// the registry and the features in Cargo.toml. Nothing is written if the day exists or a file
+/// can't be updated.
+pub fn new_day(root: &Path, n: u8) -> Result<(), String> {
+    let dir = root.join(format!("src/day{n}"));
+    if dir.exists() {
+        return Err(format!("Day {n} already exists in {}", dir.display()));
+    }
+
+    let read = |path: &str| {
+        fs::read_to_string(root.join(path)).map_err(|e| format!("Can't read {path}: {e}"))
+    };
+    let module = read(TEMPLATE)?.replace("XX", &n.to_string());
+    let updates = [
+        ("src/lib.rs", add_module(&read("src/lib.rs")?, n)),
+        (
+            "src/registry.rs",
+            add_to_registry(&read("src/registry.rs")?, n),
+        ),
+        ("Cargo.toml", add_feature(&read("Cargo.toml")?, n)),
+    ];
+    for (path, update) in &updates {
+        if update.is_none() {
+            return Err(format!("Can't find where day {n} goes in {path}"));
+        }
+    }
+
+    let write = |path: &Path, contents: &str| {
+        fs::write(path, contents).map_err(|e| format!("Can't write {}: {e}", path.display()))
+    };
+    fs::create_dir_all(&dir).map_err(|e| format!("Can't create {}: {e}", dir.display()))?;
+    write(&dir.join("mod.rs"), &module)?;
+    for file in ["test1.txt", "test2.txt", "input.txt"] {
+        write(&dir.join(file), "")?;
+    }
+    for (path, update) in updates {
+        write(&root.join(path), &update.unwrap())?;
+    }
+    Ok(())
+}
+
+/// Inserts `entry` in front of the first line whose key is bigger than `new`, or right after the
+/// entry of the last keyed line. `None` if no line has a key at all.
+fn insert_sorted<K: Ord>(
+    source: &str,
+    entry: &str,
+    new: K,
+    key: impl Fn(&str) -> Option<K>,
+) -> Option<String> {
+    let lines: Vec<&str> = source.lines().collect();
+    let keyed: Vec<(usize, K)> = lines
+        .iter()
+        .enumerate()
+        .filter_map(|(i, line)| key(line.trim()).map(|k| (i, k)))
+        .collect();
+    let at = match keyed.iter().find(|(_, k)| *k > new) {
+        Some((i, _)) => *i,
+        None => keyed.last()?.0 + entry.lines().count(),
+    };
+    let first = lines[keyed[0].0];
+    let indent = &first[..first.len() - first.trim_start().len()];
+
+    let mut result: Vec<String> = lines[..at].iter().map(|line| line.to_string()).collect();
+    result.extend(entry.lines().map(|line| format!("{indent}{line}")));
+    result.extend(lines[at..].iter().map(|line| line.to_string()));
+    Some(result.join("\n") + "\n")
+}
+
+/// The number of the day a `#[cfg(feature = "dayN")]` line is for
+fn feature_day(line: &str) -> Option<u8> {
+    line.strip_prefix("#[cfg(feature = \"day")?
+        .strip_suffix("\")]")?
+        .parse()
+        .ok()
+}
+
+/// `pub mod dayN;` and its feature in lib.rs, sorted by name like rustfmt does
+fn add_module(lib: &str, n: u8) -> Option<String> {
+    insert_sorted(
+        lib,
+        &format!("#[cfg(feature = \"day{n}\")]\npub mod day{n};"),
+        format!("day{n}"),
+        |line| feature_day(line).map(|day| format!("day{day}")),
+    )
+}
+
+/// `Day::new::<dayN::Puzzle>(),` and its feature in the registry, in calendar order
+fn add_to_registry(registry: &str, n: u8) -> Option<String> {
+    insert_sorted(
+        registry,
+        &format!("#[cfg(feature = \"day{n}\")]\nDay::new::<day{n}::Puzzle>(),"),
+        n,
+        feature_day,
+    )
+}
+
+/// The `dayN` feature in Cargo.toml, on by default
+fn add_feature(manifest: &str, n: u8) -> Option<String> {
+    let manifest = insert_sorted(manifest, &format!("\"day{n}\","), n, |line| {
+        line.strip_prefix("\"day")?
+            .strip_suffix("\",")?
+            .parse()
+            .ok()
+    })?;
+    insert_sorted(&manifest, &format!("day{n} = []"), n, |line| {
+        line.strip_prefix("day")?.split_once(" = [")?.0.parse().ok()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registration() {
+        let lib = "pub mod answers;\n#[cfg(feature = \"day1\")]\npub mod day1;\n\
+                   #[cfg(feature = \"day2\")]\npub mod day2;\npub mod error;\n";
+        assert_eq!(
+            add_module(lib, 10).unwrap(),
+            "pub mod answers;\n#[cfg(feature = \"day1\")]\npub mod day1;\n\
+             #[cfg(feature = \"day10\")]\npub mod day10;\n\
+             #[cfg(feature = \"day2\")]\npub mod day2;\npub mod error;\n"
+        );
+
+        let registry = "    vec![\n        #[cfg(feature = \"day1\")]\n        Day::new::<day1::Puzzle>(),\n    ]\n";
+        assert_eq!(
+            add_to_registry(registry, 2).unwrap(),
+            "    vec![\n        #[cfg(feature = \"day1\")]\n        Day::new::<day1::Puzzle>(),\n\
+             \x20       #[cfg(feature = \"day2\")]\n        Day::new::<day2::Puzzle>(),\n    ]\n"
+        );
+
+        let manifest = "[features]\ndefault = [\n    \"day1\",\n    \"day3\",\n]\n\
+                        day1 = []\nday3 = [\"dep:regex\"]\nreference = []\n";
+        assert_eq!(
+            add_feature(manifest, 4).unwrap(),
+            "[features]\ndefault = [\n    \"day1\",\n    \"day3\",\n    \"day4\",\n]\n\
+             day1 = []\nday3 = [\"dep:regex\"]\nday4 = []\nreference = []\n"
+        );
+
+        assert_eq!(add_module("fn main() {}\n", 1), None);
+    }
+
+    #[test]
+    fn existing_day() {
+        let error = new_day(Path::new("."), 1).unwrap_err();
+        assert!(error.starts_with("Day 1 already exists"));
+    }
+}
@@ -0,0 +1,146 @@
+use std::{fmt::Display, io::BufRead};
+
+use crate::error::{Error, Result};
+use crate::util::random::Rng;
+
+/// Numbers the puzzle text gives besides the input, by name: how many times the stones blink on
+/// day 11, how big the map is on days 14 and 18. The examples have different ones.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Params(Vec<(String, usize)>);
+
+impl Params {
+    pub fn set(&mut self, name: &str, value: usize) {
+        match self.0.iter_mut().find(|(key, _)| key == name) {
+            Some((_, old)) => *old = value,
+            None => self.0.push((name.to_string(), value)),
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<usize> {
+        self.0
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|&(_, value)| value)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, usize)> {
+        self.0.iter().map(|(name, value)| (name.as_str(), *value))
+    }
+
+    /// Fails on the first parameter that isn't one of the `known` ones of a day
+    pub fn check(&self, known: &[&str]) -> Result<()> {
+        match self
+            .0
+            .iter()
+            .find(|(name, _)| !known.contains(&name.as_str()))
+        {
+            Some((name, _)) => Err(Error::invalid(format!("unknown parameter '{name}'"))),
+            None => Ok(()),
+        }
+    }
+}
+
+/// One day of the calendar: parse the puzzle text once, then solve both parts from it
+pub trait Solution {
+    /// Day of the month, starting from 1
+    const DAY: u8;
+
+    type Input;
+
+    fn parse(data: &str) -> Result<Self::Input>;
+
+    /// `parse` with some of the numbers the puzzle text gives set by `params`, instead of the
+    /// ones of the real input. The days without any reject every parameter.
+    fn parse_with(data: &str, params: &Params) -> Result<Self::Input> {
+        params.check(&[])?;
+        Self::parse(data)
+    }
+
+    fn part_1(input: &Self::Input) -> Result<impl Display>;
+    fn part_2(input: &Self::Input) -> Result<impl Display>;
+
+    /// A random, valid puzzle text. What `size` means is up to the day (lines, rows of a map, ...),
+    /// bigger is bigger.
+    fn generate(rng: &mut Rng, size: usize) -> String;
+
+    /// Both parts straight from `reader`, a line at a time instead of parsing the whole text
+    /// first. `None` for the days that need all of it anyway.
+    fn stream(_reader: &mut dyn BufRead) -> Option<Result<(impl Display, impl Display)>> {
+        None::<Result<(String, String)>>
+    }
+}
+
+/// A parsed input with its solvers attached, so different days can live in the same list
+/// (and be solved on any thread)
+pub trait Parsed: Send + Sync {
+    fn part_1(&self) -> Result<String>;
+    fn part_2(&self) -> Result<String>;
+}
+
+struct ParsedInput<S: Solution>(S::Input);
+
+impl<S: Solution> Parsed for ParsedInput<S>
+where
+    S::Input: Send + Sync,
+{
+    fn part_1(&self) -> Result<String> {
+        S::part_1(&self.0).map(|answer| answer.to_string())
+    }
+
+    fn part_2(&self) -> Result<String> {
+        S::part_2(&self.0).map(|answer| answer.to_string())
+    }
+}
+
+/// Both answers of a streaming day, if it is one
+type Streamed = Option<Result<(String, String)>>;
+
+/// Registry entry for a single day
+#[derive(Clone, Copy)]
+pub struct Day {
+    pub number: u8,
+    parse: fn(&str, &Params) -> Result<Box<dyn Parsed>>,
+    generate: fn(&mut Rng, usize) -> String,
+    stream: fn(&mut dyn BufRead) -> Streamed,
+}
+
+impl Day {
+    pub fn new<S>() -> Self
+    where
+        S: Solution + 'static,
+        S::Input: Send + Sync + 'static,
+    {
+        Self {
+            number: S::DAY,
+            parse: |data, params| Ok(Box::new(ParsedInput::<S>(S::parse_with(data, params)?))),
+            generate: S::generate,
+            stream: |reader| {
+                S::stream(reader)
+                    .map(|answers| answers.map(|(a, b)| (a.to_string(), b.to_string())))
+            },
+        }
+    }
+
+    pub fn parse(&self, data: &str) -> Result<Box<dyn Parsed>> {
+        self.parse_with(data, &Params::default())
+    }
+
+    pub fn parse_with(&self, data: &str, params: &Params) -> Result<Box<dyn Parsed>> {
+        (self.parse)(data, params)
+    }
+
+    /// A random puzzle text, the same for the same seed and size
+    pub fn generate(&self, seed: u64, size: usize) -> String {
+        (self.generate)(&mut Rng::new(seed), size)
+    }
+
+    /// Both answers from `reader` without reading all of it first, `None` if the day can't
+    pub fn stream(&self, reader: &mut dyn BufRead) -> Streamed {
+        (self.stream)(reader)
+    }
+
+    /// The checked-in puzzle input of this day
+    pub fn input_path(&self) -> String {
+        format!("src/day{}/input.txt", self.number)
+    }
+}
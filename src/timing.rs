@@ -0,0 +1,174 @@
+use std::{fmt::Write, path::Path, time::Duration};
+
+/// Wall time of one day, parts that weren't run are `None`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DayTimings {
+    pub day: u8,
+    pub parse: Duration,
+    pub part_1: Option<Duration>,
+    pub part_2: Option<Duration>,
+}
+
+impl DayTimings {
+    pub fn new(day: u8, parse: Duration) -> Self {
+        Self {
+            day,
+            parse,
+            part_1: None,
+            part_2: None,
+        }
+    }
+
+    pub fn set(&mut self, part: u8, time: Duration) {
+        match part {
+            1 => self.part_1 = Some(time),
+            _ => self.part_2 = Some(time),
+        }
+    }
+
+    pub fn total(&self) -> Duration {
+        self.parse + self.part_1.unwrap_or_default() + self.part_2.unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    Json,
+    Csv,
+}
+
+impl Format {
+    /// Guessed from the file extension, `None` if it's neither .json nor .csv
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "json" => Some(Self::Json),
+            "csv" => Some(Self::Csv),
+            _ => None,
+        }
+    }
+
+    pub fn render(self, timings: &[DayTimings]) -> String {
+        match self {
+            Self::Json => to_json(timings),
+            Self::Csv => to_csv(timings),
+        }
+    }
+}
+
+fn sum(timings: &[DayTimings]) -> Duration {
+    timings.iter().map(DayTimings::total).sum()
+}
+
+fn ms(time: Duration) -> String {
+    format!("{:.3}", time.as_secs_f64() * 1000.0)
+}
+
+/// Slowest day first, with the grand total at the bottom
+pub fn table(timings: &[DayTimings]) -> String {
+    let mut sorted = timings.to_vec();
+    sorted.sort_by_key(|t| std::cmp::Reverse(t.total()));
+
+    let cell = |time: Option<Duration>| time.map_or("-".to_string(), |t| format!("{} ms", ms(t)));
+    let mut table = format!(
+        "{:>5} {:>14} {:>14} {:>14} {:>14}\n",
+        "Day", "Parse", "Part 1", "Part 2", "Total"
+    );
+    for t in &sorted {
+        let _ = writeln!(
+            table,
+            "{:>5} {:>14} {:>14} {:>14} {:>14}",
+            t.day,
+            cell(Some(t.parse)),
+            cell(t.part_1),
+            cell(t.part_2),
+            cell(Some(t.total())),
+        );
+    }
+    let _ = writeln!(table, "{:>5} {:>59}", "All", cell(Some(sum(timings))));
+    table
+}
+
+/// One row per day in calendar order, times in milliseconds, parts that weren't run are empty
+pub fn to_csv(timings: &[DayTimings]) -> String {
+    let cell = |time: Option<Duration>| time.map_or(String::new(), ms);
+    let mut csv = "day,parse_ms,part1_ms,part2_ms,total_ms\n".to_string();
+    for t in timings {
+        let _ = writeln!(
+            csv,
+            "{},{},{},{},{}",
+            t.day,
+            ms(t.parse),
+            cell(t.part_1),
+            cell(t.part_2),
+            ms(t.total())
+        );
+    }
+    csv
+}
+
+/// Same as the CSV, plus the total of everything
+pub fn to_json(timings: &[DayTimings]) -> String {
+    let cell = |time: Option<Duration>| time.map_or("null".to_string(), ms);
+    let days: Vec<String> = timings
+        .iter()
+        .map(|t| {
+            format!(
+                "    {{\"day\": {}, \"parse_ms\": {}, \"part1_ms\": {}, \"part2_ms\": {}, \"total_ms\": {}}}",
+                t.day,
+                ms(t.parse),
+                cell(t.part_1),
+                cell(t.part_2),
+                ms(t.total())
+            )
+        })
+        .collect();
+    format!(
+        "{{\n  \"days\": [\n{}\n  ],\n  \"total_ms\": {}\n}}\n",
+        days.join(",\n"),
+        ms(sum(timings))
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn timings() -> Vec<DayTimings> {
+        let mut day1 = DayTimings::new(1, Duration::from_micros(100));
+        day1.set(1, Duration::from_millis(2));
+        day1.set(2, Duration::from_millis(3));
+        let mut day2 = DayTimings::new(2, Duration::from_micros(1500));
+        day2.set(2, Duration::from_millis(10));
+        vec![day1, day2]
+    }
+
+    #[test]
+    fn csv() {
+        assert_eq!(
+            to_csv(&timings()),
+            "day,parse_ms,part1_ms,part2_ms,total_ms\n\
+             1,0.100,2.000,3.000,5.100\n\
+             2,1.500,,10.000,11.500\n"
+        );
+    }
+
+    #[test]
+    fn json() {
+        assert_eq!(
+            to_json(&timings()),
+            "{\n  \"days\": [\n    \
+             {\"day\": 1, \"parse_ms\": 0.100, \"part1_ms\": 2.000, \"part2_ms\": 3.000, \"total_ms\": 5.100},\n    \
+             {\"day\": 2, \"parse_ms\": 1.500, \"part1_ms\": null, \"part2_ms\": 10.000, \"total_ms\": 11.500}\n  \
+             ],\n  \"total_ms\": 16.600\n}\n"
+        );
+    }
+
+    #[test]
+    fn slowest_first() {
+        let table = table(&timings());
+        let rows: Vec<&str> = table.lines().map(str::trim_start).collect();
+        assert!(rows[1].starts_with("2 "));
+        assert!(rows[2].starts_with("1 "));
+        assert!(rows[3].starts_with("All") && rows[3].ends_with("16.600 ms"));
+    }
+}
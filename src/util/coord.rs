@@ -1,4 +1,9 @@
-use std::ops::{Add, AddAssign, Sub, SubAssign};
+use std::{
+    fmt::{self, Display},
+    ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign},
+};
+
+use crate::util::direction::Direction8;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Coord {
@@ -7,7 +12,7 @@ pub struct Coord {
 }
 
 impl Coord {
-    pub fn new(x: isize, y: isize) -> Self {
+    pub const fn new(x: isize, y: isize) -> Self {
         Self { x, y }
     }
 
@@ -15,8 +20,33 @@ impl Coord {
         Self { x, y }
     }
 
+    pub fn from_usize(x: usize, y: usize) -> Self {
+        Self::new(x as isize, y as isize)
+    }
+
+    /// Both components as indices, `None` if either of them is negative
+    pub fn to_usize(&self) -> Option<(usize, usize)> {
+        Some((usize::try_from(self.x).ok()?, usize::try_from(self.y).ok()?))
+    }
+
+    /// Manhattan distance, the number of steps between the two on a grid
     pub fn distance(&self, other: &Self) -> usize {
-        ((self.x - other.x).abs() + (self.y - other.y).abs()) as usize
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+
+    /// Chebyshev distance, the number of steps when diagonal ones are allowed too
+    pub fn chebyshev_distance(&self, other: &Self) -> usize {
+        self.x.abs_diff(other.x).max(self.y.abs_diff(other.y))
+    }
+
+    /// Inside of the rectangle from (0, 0) up to, but not including, `size`
+    pub fn in_bounds(&self, size: Self) -> bool {
+        (0..size.x).contains(&self.x) && (0..size.y).contains(&self.y)
+    }
+
+    /// Wrapped around into the rectangle from (0, 0) to `size`, like on a torus
+    pub fn wrap(&self, size: Self) -> Self {
+        Self::new(self.x.rem_euclid(size.x), self.y.rem_euclid(size.y))
     }
 
     pub fn neighbors(&self) -> [Self; 4] {
@@ -27,6 +57,18 @@ impl Coord {
             Self::new(self.x + 1, self.y),
         ]
     }
+
+    /// The 4 neighbors and the diagonal ones, clockwise starting from `x - 1`
+    pub fn neighbors8(&self) -> [Self; 8] {
+        Direction8::ALL.map(|direction| *self + direction)
+    }
+}
+
+/// `(x, y)`, like `Coord::new` takes them
+impl Display for Coord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
 }
 
 impl Add for Coord {
@@ -58,3 +100,44 @@ impl SubAssign for Coord {
         self.y -= rhs.y;
     }
 }
+
+impl Mul<isize> for Coord {
+    type Output = Self;
+
+    fn mul(self, rhs: isize) -> Self::Output {
+        Self::new(self.x * rhs, self.y * rhs)
+    }
+}
+
+impl Neg for Coord {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self::new(-self.x, -self.y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arithmetic() {
+        let a = Coord::new(3, -2);
+        assert_eq!(a * 3, Coord::new(9, -6));
+        assert_eq!(-a, Coord::new(-3, 2));
+        assert_eq!(a.to_usize(), None);
+        assert_eq!(Coord::new(3, 2).to_usize(), Some((3, 2)));
+        assert_eq!(a.distance(&Coord::new(0, 0)), 5);
+        assert_eq!(a.chebyshev_distance(&Coord::new(0, 0)), 3);
+    }
+
+    #[test]
+    fn bounds() {
+        let size = Coord::new(11, 7);
+        assert!(Coord::new(10, 6).in_bounds(size));
+        assert!(!Coord::new(11, 0).in_bounds(size));
+        assert!(!Coord::new(0, -1).in_bounds(size));
+        assert_eq!(Coord::new(-1, 15).wrap(size), Coord::new(10, 1));
+    }
+}
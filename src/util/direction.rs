@@ -0,0 +1,202 @@
+use std::{
+    ops::{Add, AddAssign},
+    str::FromStr,
+};
+
+use crate::error::{Error, Result};
+use crate::util::coord::Coord;
+
+/// One of the 4 directions on a map. Up is towards the first row, like in the puzzle texts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Direction4 {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction4 {
+    /// Clockwise, starting with up
+    pub const ALL: [Self; 4] = [Self::Up, Self::Right, Self::Down, Self::Left];
+
+    pub fn turn_right(self) -> Self {
+        Self::ALL[(self as usize + 1) % 4]
+    }
+
+    pub fn turn_left(self) -> Self {
+        Self::ALL[(self as usize + 3) % 4]
+    }
+
+    pub fn reverse(self) -> Self {
+        Self::ALL[(self as usize + 2) % 4]
+    }
+
+    /// Arrows (`^v<>`), compass points (`NSEW`) or initials (`UDLR`)
+    pub fn from_char(c: char) -> Option<Self> {
+        match c {
+            '^' | 'N' | 'U' => Some(Self::Up),
+            '>' | 'E' | 'R' => Some(Self::Right),
+            'v' | 'S' | 'D' => Some(Self::Down),
+            '<' | 'W' | 'L' => Some(Self::Left),
+            _ => None,
+        }
+    }
+
+    /// The arrow pointing this way
+    pub fn to_char(self) -> char {
+        match self {
+            Self::Up => '^',
+            Self::Right => '>',
+            Self::Down => 'v',
+            Self::Left => '<',
+        }
+    }
+
+    /// One step this way, `x` being the row and `y` the column
+    pub fn delta(self) -> Coord {
+        Direction8::from(self).delta()
+    }
+}
+
+impl FromStr for Direction4 {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut chars = s.chars();
+        match (chars.next().and_then(Self::from_char), chars.next()) {
+            (Some(direction), None) => Ok(direction),
+            _ => Err(Error::invalid(format!("'{s}' is not a direction"))),
+        }
+    }
+}
+
+/// One of the 8 directions on a map, the 4 of `Direction4` and the diagonals between them
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Direction8 {
+    Up,
+    UpRight,
+    Right,
+    DownRight,
+    Down,
+    DownLeft,
+    Left,
+    UpLeft,
+}
+
+impl Direction8 {
+    /// Clockwise, starting with up
+    pub const ALL: [Self; 8] = [
+        Self::Up,
+        Self::UpRight,
+        Self::Right,
+        Self::DownRight,
+        Self::Down,
+        Self::DownLeft,
+        Self::Left,
+        Self::UpLeft,
+    ];
+
+    /// 45 degrees clockwise
+    pub fn turn_right(self) -> Self {
+        Self::ALL[(self as usize + 1) % 8]
+    }
+
+    /// 45 degrees counterclockwise
+    pub fn turn_left(self) -> Self {
+        Self::ALL[(self as usize + 7) % 8]
+    }
+
+    pub fn reverse(self) -> Self {
+        Self::ALL[(self as usize + 4) % 8]
+    }
+
+    /// One step this way, `x` being the row and `y` the column
+    pub fn delta(self) -> Coord {
+        match self {
+            Self::Up => Coord::new(-1, 0),
+            Self::UpRight => Coord::new(-1, 1),
+            Self::Right => Coord::new(0, 1),
+            Self::DownRight => Coord::new(1, 1),
+            Self::Down => Coord::new(1, 0),
+            Self::DownLeft => Coord::new(1, -1),
+            Self::Left => Coord::new(0, -1),
+            Self::UpLeft => Coord::new(-1, -1),
+        }
+    }
+}
+
+impl From<Direction4> for Direction8 {
+    fn from(direction: Direction4) -> Self {
+        Self::ALL[direction as usize * 2]
+    }
+}
+
+/// The `Direction4` ones, plus compass points like `NE`
+impl FromStr for Direction8 {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "NE" => Ok(Self::UpRight),
+            "SE" => Ok(Self::DownRight),
+            "SW" => Ok(Self::DownLeft),
+            "NW" => Ok(Self::UpLeft),
+            s => s.parse::<Direction4>().map(Self::from),
+        }
+    }
+}
+
+impl Add<Direction4> for Coord {
+    type Output = Coord;
+
+    fn add(self, rhs: Direction4) -> Coord {
+        self + rhs.delta()
+    }
+}
+
+impl AddAssign<Direction4> for Coord {
+    fn add_assign(&mut self, rhs: Direction4) {
+        *self += rhs.delta();
+    }
+}
+
+impl Add<Direction8> for Coord {
+    type Output = Coord;
+
+    fn add(self, rhs: Direction8) -> Coord {
+        self + rhs.delta()
+    }
+}
+
+impl AddAssign<Direction8> for Coord {
+    fn add_assign(&mut self, rhs: Direction8) {
+        *self += rhs.delta();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn turning() {
+        let up = Direction4::Up;
+        assert_eq!(up.turn_right(), Direction4::Right);
+        assert_eq!(up.turn_left(), Direction4::Left);
+        assert_eq!(up.reverse(), Direction4::Down);
+        assert_eq!(Direction8::Left.turn_right(), Direction8::UpLeft);
+        assert_eq!(Direction8::from(Direction4::Down), Direction8::Down);
+        assert_eq!(Coord::new(2, 2) + Direction4::Left, Coord::new(2, 1));
+    }
+
+    #[test]
+    fn parsing() {
+        assert_eq!("v".parse::<Direction4>().unwrap(), Direction4::Down);
+        assert_eq!("W".parse::<Direction4>().unwrap(), Direction4::Left);
+        assert_eq!("NW".parse::<Direction8>().unwrap(), Direction8::UpLeft);
+        assert_eq!(
+            "x".parse::<Direction4>().unwrap_err().to_string(),
+            "invalid input: 'x' is not a direction"
+        );
+    }
+}
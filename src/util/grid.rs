@@ -0,0 +1,256 @@
+use std::{
+    fmt::{self, Display},
+    ops::{Index, IndexMut},
+};
+
+use crate::error::{Error, Result};
+use crate::util::coord::Coord;
+use crate::util::input::lines;
+
+/// A rectangular map, stored row by row.
+///
+/// Like everywhere else in the puzzles, `Coord::x` is the row and `Coord::y` the column.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    rows: usize,
+    cols: usize,
+}
+
+impl<T> Grid<T> {
+    pub fn new(rows: usize, cols: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            cells: vec![fill; rows * cols],
+            rows,
+            cols,
+        }
+    }
+
+    /// Fails if the rows have different lengths
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Result<Self> {
+        let cols = rows.first().map_or(0, Vec::len);
+        if let Some(row) = rows.iter().position(|row| row.len() != cols) {
+            return Err(Error::parse(
+                row + 1,
+                1,
+                "rows of the map have different lengths",
+            ));
+        }
+        Ok(Self {
+            rows: rows.len(),
+            cols,
+            cells: rows.into_iter().flatten().collect(),
+        })
+    }
+
+    /// Parses a map of characters, one row per line. `tile` returns `None` for characters that
+    /// don't belong on the map.
+    pub fn parse(data: &str, mut tile: impl FnMut(char) -> Option<T>) -> Result<Self> {
+        Self::parse_with(data, |_, c| tile(c))
+    }
+
+    /// Same as `parse`, also passing the position of the character, e.g. to remember the start
+    pub fn parse_with(data: &str, mut tile: impl FnMut(Coord, char) -> Option<T>) -> Result<Self> {
+        let mut cells = vec![];
+        let mut cols = None;
+        let mut rows = 0;
+        for line in lines(data) {
+            for (col, c) in line.chars() {
+                let coord = Coord::new(rows as isize, col as isize - 1);
+                match tile(coord, c) {
+                    Some(cell) => cells.push(cell),
+                    None => return Err(line.error(col, format!("unknown tile '{c}'"))),
+                }
+            }
+            let width = cells.len() - rows * cols.unwrap_or(0);
+            if *cols.get_or_insert(width) != width {
+                return Err(line.error(1, "rows of the map have different lengths"));
+            }
+            rows += 1;
+        }
+        Ok(Self {
+            cells,
+            rows,
+            cols: cols.unwrap_or(0),
+        })
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn contains(&self, coord: Coord) -> bool {
+        coord.in_bounds(Coord::from_usize(self.rows, self.cols))
+    }
+
+    fn offset(&self, coord: Coord) -> Option<usize> {
+        self.contains(coord)
+            .then(|| coord.x as usize * self.cols + coord.y as usize)
+    }
+
+    pub fn get(&self, coord: Coord) -> Option<&T> {
+        self.offset(coord).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, coord: Coord) -> Option<&mut T> {
+        self.offset(coord).map(|i| &mut self.cells[i])
+    }
+
+    /// Every coordinate, row by row
+    pub fn coords(&self) -> impl Iterator<Item = Coord> {
+        let cols = self.cols;
+        (0..self.rows * cols).map(move |i| Coord::new((i / cols) as isize, (i % cols) as isize))
+    }
+
+    /// Every cell with its coordinate, row by row
+    pub fn iter(&self) -> impl Iterator<Item = (Coord, &T)> {
+        self.coords().zip(&self.cells)
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Coord, &mut T)> {
+        let cols = self.cols;
+        self.cells
+            .iter_mut()
+            .enumerate()
+            .map(move |(i, cell)| (Coord::new((i / cols) as isize, (i % cols) as isize), cell))
+    }
+
+    /// The 4 neighbours of `coord` that are on the map
+    pub fn neighbors(&self, coord: Coord) -> impl Iterator<Item = Coord> + '_ {
+        coord
+            .neighbors()
+            .into_iter()
+            .filter(|&neighbor| self.contains(neighbor))
+    }
+
+    /// The up to 8 neighbours of `coord`, diagonals included, that are on the map
+    pub fn neighbors8(&self, coord: Coord) -> impl Iterator<Item = Coord> + '_ {
+        coord
+            .neighbors8()
+            .into_iter()
+            .filter(|&neighbor| self.contains(neighbor))
+    }
+
+    pub fn row(&self, row: usize) -> impl Iterator<Item = &T> {
+        self.cells[row * self.cols..(row + 1) * self.cols].iter()
+    }
+
+    pub fn column(&self, col: usize) -> impl Iterator<Item = &T> {
+        self.cells.iter().skip(col).step_by(self.cols.max(1))
+    }
+
+    /// Cells from `start` in steps of `step` (e.g. `Coord::new(1, 1)` for a diagonal) until the edge
+    pub fn line(&self, start: Coord, step: Coord) -> impl Iterator<Item = (Coord, &T)> {
+        let mut coord = start;
+        std::iter::from_fn(move || {
+            let cell = self.get(coord)?;
+            let current = coord;
+            coord += step;
+            Some((current, cell))
+        })
+    }
+
+    /// Coordinate of the first cell equal to `value`, row by row
+    pub fn find(&self, value: &T) -> Option<Coord>
+    where
+        T: PartialEq,
+    {
+        self.positions(value).next()
+    }
+
+    /// Coordinates of all cells equal to `value`
+    pub fn positions<'a>(&'a self, value: &'a T) -> impl Iterator<Item = Coord> + 'a
+    where
+        T: PartialEq,
+    {
+        self.iter()
+            .filter(move |(_, cell)| *cell == value)
+            .map(|(coord, _)| coord)
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            cells: self.cells.iter().map(f).collect(),
+            rows: self.rows,
+            cols: self.cols,
+        }
+    }
+}
+
+impl<T> Index<Coord> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, coord: Coord) -> &T {
+        match self.get(coord) {
+            Some(cell) => cell,
+            None => panic!("{coord:?} is outside of a {}x{} grid", self.rows, self.cols),
+        }
+    }
+}
+
+impl<T> IndexMut<Coord> for Grid<T> {
+    fn index_mut(&mut self, coord: Coord) -> &mut T {
+        let (rows, cols) = (self.rows, self.cols);
+        match self.get_mut(coord) {
+            Some(cell) => cell,
+            None => panic!("{coord:?} is outside of a {rows}x{cols} grid"),
+        }
+    }
+}
+
+/// One line per row, each cell rendered next to the other
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in 0..self.rows {
+            for cell in self.row(row) {
+                write!(f, "{cell}")?;
+            }
+            if row + 1 < self.rows {
+                writeln!(f)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_and_render() {
+        let grid = Grid::parse("#.#\n.S.\r\n", Some).unwrap();
+        assert_eq!((grid.rows(), grid.cols()), (2, 3));
+        assert_eq!(grid.find(&'S'), Some(Coord::new(1, 1)));
+        assert_eq!(grid.get(Coord::new(0, 3)), None);
+        assert_eq!(grid.to_string(), "#.#\n.S.");
+
+        let neighbors: Vec<Coord> = grid.neighbors(Coord::new(0, 0)).collect();
+        assert_eq!(neighbors, [Coord::new(0, 1), Coord::new(1, 0)]);
+        let column: String = grid.column(2).collect();
+        assert_eq!(column, "#.");
+        let diagonal: String = grid
+            .line(Coord::new(0, 0), Coord::new(1, 1))
+            .map(|(_, c)| c)
+            .collect();
+        assert_eq!(diagonal, "#S");
+    }
+
+    #[test]
+    fn malformed() {
+        let error = Grid::parse("..\n.x\n", |c| (c == '.').then_some(c)).unwrap_err();
+        assert_eq!(error.to_string(), "line 2, column 2: unknown tile 'x'");
+        let error = Grid::parse("..\n.\n", Some).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 2, column 1: rows of the map have different lengths"
+        );
+    }
+}
@@ -0,0 +1,126 @@
+//! A way for the solvers to show what they're doing without knowing who, if anyone, is watching.
+//!
+//! Nothing is sent until an observer is installed, and the solvers only build what they send
+//! when there is one, so the hooks cost nothing in a normal run.
+
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Mutex,
+};
+
+use crate::error::Result;
+use crate::util::progress::Progress;
+use crate::util::trace::Fact;
+use crate::util::viz::Frame;
+
+/// Receives what the solvers send, on whichever thread they run. Everything is ignored unless the
+/// observer says otherwise.
+pub trait Observer: Send {
+    /// A picture of the puzzle, usually one step of an animation
+    fn frame(&mut self, _frame: &Frame) {}
+
+    /// How far a solver has got through its input
+    fn progress(&mut self, _progress: &Progress) {}
+
+    /// Something a solver found out on the way to its answer
+    fn fact(&mut self, _fact: &Fact) {}
+
+    /// Called once after the last puzzle, to report what went wrong on the way
+    fn finish(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+static OBSERVERS: Mutex<Vec<Box<dyn Observer>>> = Mutex::new(vec![]);
+
+/// Sends everything to `observer` from now on, along with the ones already watching
+pub fn observe(observer: Box<dyn Observer>) {
+    OBSERVERS.lock().unwrap().push(observer);
+    ACTIVE.store(true, Ordering::Relaxed);
+}
+
+/// Stops sending and finishes every observer, returning the first error of any of them
+pub fn stop() -> Result<()> {
+    ACTIVE.store(false, Ordering::Relaxed);
+    let observers = std::mem::take(&mut *OBSERVERS.lock().unwrap());
+    observers
+        .into_iter()
+        .map(|mut observer| observer.finish())
+        .fold(Ok(()), Result::and)
+}
+
+/// Whether anyone is watching, to skip preparing something expensive for nobody
+pub fn active() -> bool {
+    ACTIVE.load(Ordering::Relaxed)
+}
+
+/// Sends the frame made by `make`, which is only called if there's an observer
+pub fn frame(make: impl FnOnce() -> Frame) {
+    if !active() {
+        return;
+    }
+    let frame = make();
+    for observer in OBSERVERS.lock().unwrap().iter_mut() {
+        observer.frame(&frame);
+    }
+}
+
+/// Sends the fact made by `make`, which is only called if there's an observer
+pub fn fact(make: impl FnOnce() -> Fact) {
+    if !active() {
+        return;
+    }
+    let fact = make();
+    for observer in OBSERVERS.lock().unwrap().iter_mut() {
+        observer.fact(&fact);
+    }
+}
+
+/// Tells the observers how far a solver has got
+pub fn progress(progress: Progress) {
+    if !active() {
+        return;
+    }
+    for observer in OBSERVERS.lock().unwrap().iter_mut() {
+        observer.progress(&progress);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+    use crate::error::Error;
+    use crate::util::grid::Grid;
+
+    struct Titles(Arc<Mutex<Vec<String>>>);
+
+    impl Observer for Titles {
+        fn frame(&mut self, frame: &Frame) {
+            self.0.lock().unwrap().push(frame.title().to_string());
+        }
+
+        fn finish(&mut self) -> Result<()> {
+            Err(Error::invalid("out of film"))
+        }
+    }
+
+    #[test]
+    fn observing() {
+        let grid = Grid::new(1, 1, '.');
+        frame(|| unreachable!("nobody is watching"));
+
+        let titles = Arc::new(Mutex::new(vec![]));
+        observe(Box::new(Titles(Arc::clone(&titles))));
+        frame(|| Frame::new(&grid, |&c| c).with_title("first"));
+        frame(|| Frame::new(&grid, |&c| c).with_title("second"));
+        assert_eq!(
+            stop().unwrap_err().to_string(),
+            "invalid input: out of film"
+        );
+        frame(|| unreachable!("nobody is watching any more"));
+        assert_eq!(*titles.lock().unwrap(), ["first", "second"]);
+    }
+}
@@ -0,0 +1,251 @@
+use std::{any::type_name, borrow::Cow, io::BufRead, str::FromStr};
+
+use crate::error::{Error, Result};
+use crate::util::hook;
+use crate::util::progress::Progress;
+
+/// One line of the puzzle text, remembering where it came from for error messages
+#[derive(Debug, Clone, Copy)]
+pub struct Line<'a> {
+    /// 1-based, like in any text editor
+    pub number: usize,
+    pub text: &'a str,
+}
+
+/// Lines of the puzzle text, CRLF or LF, without trailing whitespace and the blank lines at the end
+pub fn lines(data: &str) -> impl Iterator<Item = Line<'_>> {
+    data.trim_end().lines().enumerate().map(|(i, text)| Line {
+        number: i + 1,
+        text: text.trim_end(),
+    })
+}
+
+/// The lines of `reader` one at a time, the way `lines` sees them, without ever holding more than
+/// one of them. Reports its progress through `hook::progress`.
+pub fn stream_lines(mut reader: impl BufRead, mut f: impl FnMut(Line) -> Result<()>) -> Result<()> {
+    let mut buffer = String::new();
+    let mut progress = Progress {
+        lines: 0,
+        bytes: 0,
+        done: false,
+    };
+    // Blank lines only count once something comes after them
+    let mut blank = 0;
+    loop {
+        buffer.clear();
+        let read = reader.read_line(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        progress.lines += 1;
+        progress.bytes += read as u64;
+        if progress.lines.is_multiple_of(Progress::EVERY) {
+            hook::progress(progress);
+        }
+
+        let text = buffer.trim_end();
+        if text.is_empty() {
+            blank += 1;
+            continue;
+        }
+        for number in progress.lines - blank..progress.lines {
+            f(Line { number, text: "" })?;
+        }
+        blank = 0;
+        f(Line {
+            number: progress.lines,
+            text,
+        })?;
+    }
+    hook::progress(Progress {
+        done: true,
+        ..progress
+    });
+    Ok(())
+}
+
+/// The puzzle text the way `lines` sees it, joined with LF, for parsers that look at the whole text.
+/// Doesn't allocate if the text is already like that.
+pub fn normalize(data: &str) -> Cow<'_, str> {
+    let normal = lines(data)
+        .map(|line| line.text)
+        .collect::<Vec<_>>()
+        .join("\n");
+    if normal == data {
+        Cow::Borrowed(data)
+    } else {
+        Cow::Owned(normal)
+    }
+}
+
+/// A block of the puzzle text between blank lines
+#[derive(Debug, Clone, Copy)]
+pub struct Section<'a> {
+    /// Number of the first line of the block in the whole text
+    pub first_line: usize,
+    pub text: &'a str,
+}
+
+impl<'a> Section<'a> {
+    /// Lines of the block, numbered as in the whole text
+    pub fn lines(&self) -> impl Iterator<Item = Line<'a>> {
+        let offset = self.first_line - 1;
+        lines(self.text).map(move |line| Line {
+            number: line.number + offset,
+            ..line
+        })
+    }
+}
+
+/// Splits the puzzle text at blank lines, any number of them and with either line ending
+pub fn sections(data: &str) -> Vec<Section<'_>> {
+    let mut sections = vec![];
+    // First line, start and end of the block being read
+    let mut current: Option<(usize, usize, usize)> = None;
+    let mut offset = 0;
+    for (i, raw) in data.split_inclusive('\n').enumerate() {
+        let text = raw.trim_end();
+        if text.is_empty() {
+            if let Some((first_line, start, end)) = current.take() {
+                sections.push(Section {
+                    first_line,
+                    text: &data[start..end],
+                });
+            }
+        } else {
+            let end = offset + text.len();
+            match &mut current {
+                Some((_, _, last)) => *last = end,
+                None => current = Some((i + 1, offset, end)),
+            }
+        }
+        offset += raw.len();
+    }
+    if let Some((first_line, start, end)) = current {
+        sections.push(Section {
+            first_line,
+            text: &data[start..end],
+        });
+    }
+    sections
+}
+
+/// Parse error at a byte offset of the whole puzzle text, e.g. from a regex match
+pub fn error_at_offset(data: &str, offset: usize, message: impl Into<String>) -> Error {
+    let before = &data[..offset];
+    let line = before.matches('\n').count() + 1;
+    let column = offset - before.rfind('\n').map_or(0, |i| i + 1) + 1;
+    Error::parse(line, column, message)
+}
+
+impl<'a> Line<'a> {
+    /// Parse error at a 1-based column of this line
+    pub fn error(&self, column: usize, message: impl Into<String>) -> Error {
+        Error::parse(self.number, column, message)
+    }
+
+    /// Parse error pointing at `token`, which has to be a slice of this line
+    pub fn error_at(&self, token: &str, message: impl Into<String>) -> Error {
+        self.error(self.column_of(token), message)
+    }
+
+    fn column_of(&self, token: &str) -> usize {
+        let offset = (token.as_ptr() as usize).wrapping_sub(self.text.as_ptr() as usize);
+        if offset <= self.text.len() {
+            offset + 1
+        } else {
+            1
+        }
+    }
+
+    pub fn parse<T: FromStr>(&self, token: &str) -> Result<T> {
+        token.parse().map_err(|_| {
+            self.error_at(
+                token,
+                format!("expected {}, found '{token}'", type_name::<T>()),
+            )
+        })
+    }
+
+    /// Parses the next token, complaining about the end of the line if there's none left
+    pub fn parse_next<T: FromStr>(&self, tokens: &mut impl Iterator<Item = &'a str>) -> Result<T> {
+        match tokens.next() {
+            Some(token) => self.parse(token),
+            None => Err(self.error(
+                self.text.len() + 1,
+                format!("expected {}, found the end of the line", type_name::<T>()),
+            )),
+        }
+    }
+
+    pub fn split_once(&self, delimiter: &str) -> Result<(&'a str, &'a str)> {
+        self.text
+            .split_once(delimiter)
+            .ok_or_else(|| self.error(1, format!("expected '{delimiter}' in '{}'", self.text)))
+    }
+
+    /// Characters with their 1-based columns, for maps and other character grids
+    pub fn chars(&self) -> impl Iterator<Item = (usize, char)> + 'a {
+        self.text.chars().enumerate().map(|(i, c)| (i + 1, c))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_errors() {
+        let line = lines("3   4\n5   x").nth(1).unwrap();
+        let mut tokens = line.text.split_whitespace();
+        assert_eq!(line.parse_next::<u32>(&mut tokens).unwrap(), 5);
+        let error = line.parse_next::<u32>(&mut tokens).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 2, column 5: expected u32, found 'x'"
+        );
+        let error = line.parse_next::<u32>(&mut tokens).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 2, column 6: expected u32, found the end of the line"
+        );
+    }
+
+    #[test]
+    fn line_endings() {
+        let data = "ab  \r\ncd\r\n\r\n\r\nef\r\n\r\n";
+        assert_eq!(normalize(data), "ab\ncd\n\n\nef");
+        assert!(matches!(normalize("ab\ncd"), Cow::Borrowed(_)));
+
+        let sections = sections(data);
+        assert_eq!(sections.len(), 2);
+        let first: Vec<&str> = sections[0].lines().map(|line| line.text).collect();
+        assert_eq!(first, ["ab", "cd"]);
+        let second: Vec<usize> = sections[1].lines().map(|line| line.number).collect();
+        assert_eq!(second, [5]);
+    }
+
+    #[test]
+    fn streaming() {
+        let data = "ab  \r\n\ncd\r\n\r\n\n";
+        let mut streamed = vec![];
+        stream_lines(data.as_bytes(), |line| {
+            streamed.push((line.number, line.text.to_string()));
+            Ok(())
+        })
+        .unwrap();
+        let expected: Vec<(usize, String)> = lines(data)
+            .map(|line| (line.number, line.text.to_string()))
+            .collect();
+        assert_eq!(streamed, expected);
+
+        let error = stream_lines(&b"ab\n\xff\n"[..], |_| Ok(())).unwrap_err();
+        assert!(error.to_string().contains("UTF-8"), "{error}");
+    }
+
+    #[test]
+    fn offset() {
+        let error = error_at_offset("abc\ndef\ngh", 9, "here");
+        assert_eq!(error.to_string(), "line 3, column 2: here");
+    }
+}
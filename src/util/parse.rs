@@ -0,0 +1,211 @@
+//! One-liners for the usual shapes of puzzle input, reporting errors at the right line and column.
+
+use std::str::FromStr;
+
+use crate::error::{Error, Result};
+use crate::util::input::Line;
+
+/// Number-looking parts of `text`, with a leading `-` if `signed` and it isn't a dash between two numbers
+fn numbers(text: &str, signed: bool) -> impl Iterator<Item = &str> {
+    let bytes = text.as_bytes();
+    let mut i = 0;
+    std::iter::from_fn(move || {
+        while i < bytes.len() {
+            let start = i;
+            if signed
+                && bytes[i] == b'-'
+                && bytes.get(i + 1).is_some_and(u8::is_ascii_digit)
+                && (i == 0 || !bytes[i - 1].is_ascii_digit())
+            {
+                i += 1;
+            }
+            if bytes[i].is_ascii_digit() {
+                while i < bytes.len() && bytes[i].is_ascii_digit() {
+                    i += 1;
+                }
+                return Some(&text[start..i]);
+            }
+            i += 1;
+        }
+        None
+    })
+}
+
+/// Every run of digits in the line, whatever is around them: `Button A: X+94, Y+34` gives 94 and 34
+pub fn unsigned<T: FromStr>(line: &Line) -> Result<Vec<T>> {
+    numbers(line.text, false)
+        .map(|token| line.parse(token))
+        .collect()
+}
+
+/// Like `unsigned`, but a `-` right before the digits makes them negative: `p=0,4 v=3,-3`
+pub fn signed<T: FromStr>(line: &Line) -> Result<Vec<T>> {
+    numbers(line.text, true)
+        .map(|token| line.parse(token))
+        .collect()
+}
+
+/// `text` (a part of the line) split at `delimiter`, the parts trimmed.
+/// A delimiter of `" "` splits at any amount of whitespace.
+fn tokens<'a>(text: &'a str, delimiter: &'a str) -> Box<dyn Iterator<Item = &'a str> + 'a> {
+    if delimiter == " " {
+        Box::new(text.split_whitespace())
+    } else {
+        Box::new(text.split(delimiter).map(str::trim))
+    }
+}
+
+/// A list like `75,47,61,53,29`, empty if `text` is
+pub fn list<'a, T: FromStr>(line: &Line<'a>, text: &'a str, delimiter: &str) -> Result<Vec<T>> {
+    if text.trim().is_empty() {
+        return Ok(vec![]);
+    }
+    tokens(text, delimiter)
+        .map(|token| line.parse(token))
+        .collect()
+}
+
+/// Tuples of up to 4 values of any type that can be parsed
+pub trait Tuple: Sized {
+    fn from_tokens<'a>(line: &Line<'a>, tokens: &mut impl Iterator<Item = &'a str>)
+        -> Result<Self>;
+}
+
+macro_rules! impl_tuple {
+    ($($t:ident),+) => {
+        impl<$($t: FromStr),+> Tuple for ($($t,)+) {
+            fn from_tokens<'a>(
+                line: &Line<'a>,
+                tokens: &mut impl Iterator<Item = &'a str>,
+            ) -> Result<Self> {
+                Ok(($(line.parse_next::<$t>(tokens)?,)+))
+            }
+        }
+    };
+}
+
+impl_tuple!(A);
+impl_tuple!(A, B);
+impl_tuple!(A, B, C);
+impl_tuple!(A, B, C, D);
+
+/// Exactly as many values as the tuple has, like `let (x, y): (usize, usize) = tuple(&line, line.text, ",")?`
+pub fn tuple<'a, T: Tuple>(line: &Line<'a>, text: &'a str, delimiter: &str) -> Result<T> {
+    let mut tokens = tokens(text, delimiter);
+    let tuple = T::from_tokens(line, &mut tokens)?;
+    match tokens.next() {
+        Some(extra) => Err(line.error_at(extra, format!("unexpected '{extra}'"))),
+        None => Ok(tuple),
+    }
+}
+
+/// Lines of `key: value` pairs, e.g. the registers of a computer
+#[derive(Debug, Clone)]
+pub struct Record<'a> {
+    fields: Vec<(Line<'a>, &'a str, &'a str)>,
+}
+
+impl<'a> Record<'a> {
+    /// Every line has to have `separator` in it, the keys and values are trimmed
+    pub fn parse(lines: impl IntoIterator<Item = Line<'a>>, separator: &str) -> Result<Self> {
+        let fields = lines
+            .into_iter()
+            .map(|line| {
+                let (key, value) = line.split_once(separator)?;
+                Ok((line, key.trim(), value.trim()))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { fields })
+    }
+
+    /// The line and the value of the first field called `key`
+    pub fn field(&self, key: &str) -> Result<(Line<'a>, &'a str)> {
+        self.fields
+            .iter()
+            .find(|(_, k, _)| *k == key)
+            .map(|&(line, _, value)| (line, value))
+            .ok_or_else(|| Error::invalid(format!("missing '{key}'")))
+    }
+
+    pub fn get<T: FromStr>(&self, key: &str) -> Result<T> {
+        let (line, value) = self.field(key)?;
+        line.parse(value)
+    }
+
+    /// The value of `key` as a list, see `list`
+    pub fn list<T: FromStr>(&self, key: &str, delimiter: &str) -> Result<Vec<T>> {
+        let (line, value) = self.field(key)?;
+        list(&line, value, delimiter)
+    }
+
+    /// All the fields in order: their line, key and value
+    pub fn fields(&self) -> impl Iterator<Item = (Line<'a>, &'a str, &'a str)> + '_ {
+        self.fields.iter().copied()
+    }
+}
+
+/// The numbers from `unsigned` or `signed` as an array, failing if there aren't exactly `N`:
+/// `let [x, y] = exactly(&line, unsigned(&line)?)?`
+pub fn exactly<T, const N: usize>(line: &Line, numbers: Vec<T>) -> Result<[T; N]> {
+    let found = numbers.len();
+    numbers
+        .try_into()
+        .map_err(|_| line.error(1, format!("expected {N} numbers, found {found}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::input::lines;
+
+    fn line(text: &str) -> Line<'_> {
+        lines(text).next().unwrap()
+    }
+
+    #[test]
+    fn numbers() {
+        let l = line("p=0,4 v=3,-3 range 5-10");
+        assert_eq!(signed::<i32>(&l).unwrap(), [0, 4, 3, -3, 5, 10]);
+        assert_eq!(unsigned::<u32>(&l).unwrap(), [0, 4, 3, 3, 5, 10]);
+        let button = line("Button A: X+94, Y+34");
+        let [x, y] = exactly(&button, unsigned::<u32>(&button).unwrap()).unwrap();
+        assert_eq!((x, y), (94, 34));
+        let error = exactly::<i32, 3>(&l, signed(&l).unwrap()).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 1, column 1: expected 3 numbers, found 6"
+        );
+        let error = unsigned::<u8>(&line("X+94, Y+340")).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 1, column 9: expected u8, found '340'"
+        );
+    }
+
+    #[test]
+    fn lists_and_tuples() {
+        let l = line("3   4");
+        assert_eq!(tuple::<(u32, u32)>(&l, l.text, " ").unwrap(), (3, 4));
+        let l = line("75, 47,61");
+        assert_eq!(list::<u8>(&l, l.text, ",").unwrap(), [75, 47, 61]);
+        let error = tuple::<(u8, u8)>(&l, l.text, ",").unwrap_err();
+        assert_eq!(error.to_string(), "line 1, column 8: unexpected '61'");
+        let error = tuple::<(u8, u8, u8, u8)>(&l, l.text, ",").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 1, column 10: expected u8, found the end of the line"
+        );
+    }
+
+    #[test]
+    fn records() {
+        let data = "Register A: 729\nRegister B: 0\n\nProgram: 0,1,5,4";
+        let record = Record::parse(lines(data).filter(|l| !l.text.is_empty()), ":").unwrap();
+        assert_eq!(record.get::<u64>("Register A").unwrap(), 729);
+        assert_eq!(record.list::<u8>("Program", ",").unwrap(), [0, 1, 5, 4]);
+        assert_eq!(
+            record.get::<u64>("Register C").unwrap_err().to_string(),
+            "invalid input: missing 'Register C'"
+        );
+    }
+}
@@ -0,0 +1,377 @@
+//! Just enough of PNG to save the frames of the visualisations: 8-bit RGB, no filtering,
+//! compressed with `miniz_oxide` when it is built in (day 14 needs it anyway)
+
+use std::{collections::HashMap, fs, ops::RangeInclusive, path::PathBuf, str::FromStr};
+
+use crate::error::{Error, Result};
+use crate::util::hook::Observer;
+use crate::util::viz::{Cell, Color, Frame};
+
+pub type Rgb = [u8; 3];
+
+const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+
+const CRC_TABLE: [u32; 256] = {
+    let mut table = [0; 256];
+    let mut n = 0;
+    while n < 256 {
+        let mut c = n as u32;
+        let mut k = 0;
+        while k < 8 {
+            c = if c & 1 == 1 {
+                0xedb8_8320 ^ (c >> 1)
+            } else {
+                c >> 1
+            };
+            k += 1;
+        }
+        table[n] = c;
+        n += 1;
+    }
+    table
+};
+
+fn crc(bytes: &[u8]) -> u32 {
+    !bytes.iter().fold(!0, |c, &b| {
+        CRC_TABLE[((c ^ b as u32) & 0xff) as usize] ^ (c >> 8)
+    })
+}
+
+/// Length, type, data and CRC of the type and the data
+fn chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend((data.len() as u32).to_be_bytes());
+    let start = png.len();
+    png.extend(kind);
+    png.extend(data);
+    let crc = crc(&png[start..]);
+    png.extend(crc.to_be_bytes());
+}
+
+/// The zlib stream of `raw`, as compressed as `miniz_oxide` makes it
+#[cfg(feature = "miniz_oxide")]
+fn zlib(raw: &[u8]) -> Vec<u8> {
+    miniz_oxide::deflate::compress_to_vec_zlib(raw, 6)
+}
+
+/// The zlib stream of `raw` without compressing it: deflate blocks stored as they are, then the
+/// Adler-32 checksum
+#[cfg(not(feature = "miniz_oxide"))]
+fn zlib(raw: &[u8]) -> Vec<u8> {
+    // Deflate, 32K window, no dictionary, the check bits make it a multiple of 31
+    let mut zlib = vec![0x78, 0x01];
+    let blocks: Vec<&[u8]> = raw.chunks(u16::MAX as usize).collect();
+    for (i, block) in blocks.iter().enumerate() {
+        zlib.push(u8::from(i + 1 == blocks.len()));
+        zlib.extend((block.len() as u16).to_le_bytes());
+        zlib.extend((!(block.len() as u16)).to_le_bytes());
+        zlib.extend(*block);
+    }
+    if blocks.is_empty() {
+        zlib.extend([1, 0, 0, 0xff, 0xff]);
+    }
+    let (a, b) = raw.iter().fold((1u32, 0u32), |(a, b), &byte| {
+        let a = (a + byte as u32) % 65521;
+        (a, (b + a) % 65521)
+    });
+    zlib.extend((b << 16 | a).to_be_bytes());
+    zlib
+}
+
+/// An RGB picture, row by row from the top left corner
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    width: usize,
+    height: usize,
+    pixels: Vec<Rgb>,
+}
+
+impl Image {
+    pub fn new(width: usize, height: usize, fill: Rgb) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![fill; width * height],
+        }
+    }
+
+    /// The frame with every cell drawn as a `scale` by `scale` square, the title is left out
+    pub fn from_frame(frame: &Frame, palette: &Palette, scale: usize) -> Self {
+        let cells = frame.cells();
+        let mut image = Self::new(cells.cols() * scale, cells.rows() * scale, [0; 3]);
+        for (coord, cell) in cells.iter() {
+            let color = palette.pick(cell);
+            let (row, col) = (coord.x as usize * scale, coord.y as usize * scale);
+            for y in row..row + scale {
+                image.pixels[y * image.width + col..y * image.width + col + scale].fill(color);
+            }
+        }
+        image
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<Rgb> {
+        (x < self.width && y < self.height).then(|| self.pixels[y * self.width + x])
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, color: Rgb) {
+        self.pixels[y * self.width + x] = color;
+    }
+
+    /// The contents of a PNG file
+    pub fn encode(&self) -> Vec<u8> {
+        let mut header = vec![];
+        header.extend((self.width as u32).to_be_bytes());
+        header.extend((self.height as u32).to_be_bytes());
+        // 8 bits per channel, RGB, deflate, standard filters, not interlaced
+        header.extend([8, 2, 0, 0, 0]);
+
+        // Every row starts with its filter, 0 meaning none
+        let mut raw = Vec::with_capacity(self.height * (1 + self.width * 3));
+        for row in self.pixels.chunks(self.width.max(1)).take(self.height) {
+            raw.push(0);
+            raw.extend(row.iter().flatten());
+        }
+
+        let mut png = SIGNATURE.to_vec();
+        chunk(&mut png, b"IHDR", &header);
+        chunk(&mut png, b"IDAT", &zlib(&raw));
+        chunk(&mut png, b"IEND", &[]);
+        png
+    }
+}
+
+/// The RGB value of a terminal colour, with the usual xterm values for the named ones
+pub fn rgb(color: Color) -> Rgb {
+    match color {
+        Color::Black => [0, 0, 0],
+        Color::Red => [205, 0, 0],
+        Color::Green => [0, 205, 0],
+        Color::Yellow => [205, 205, 0],
+        Color::Blue => [0, 0, 238],
+        Color::Magenta => [205, 0, 205],
+        Color::Cyan => [0, 205, 205],
+        Color::White => [229, 229, 229],
+        Color::BrightBlack => [127, 127, 127],
+        Color::BrightRed => [255, 0, 0],
+        Color::BrightGreen => [0, 255, 0],
+        Color::BrightYellow => [255, 255, 0],
+        Color::BrightBlue => [92, 92, 255],
+        Color::BrightMagenta => [255, 0, 255],
+        Color::BrightCyan => [0, 255, 255],
+        Color::BrightWhite => [255, 255, 255],
+        Color::AnsiColor(n) => ansi(n),
+        Color::TrueColor { r, g, b } => [r, g, b],
+    }
+}
+
+/// One of the 256 colours of xterm: the 16 named ones, a 6x6x6 cube and 24 greys
+fn ansi(n: u8) -> Rgb {
+    const NAMED: [Color; 16] = [
+        Color::Black,
+        Color::Red,
+        Color::Green,
+        Color::Yellow,
+        Color::Blue,
+        Color::Magenta,
+        Color::Cyan,
+        Color::White,
+        Color::BrightBlack,
+        Color::BrightRed,
+        Color::BrightGreen,
+        Color::BrightYellow,
+        Color::BrightBlue,
+        Color::BrightMagenta,
+        Color::BrightCyan,
+        Color::BrightWhite,
+    ];
+    let level = |i: u8| if i == 0 { 0 } else { 55 + i * 40 };
+    match n {
+        0..=15 => rgb(NAMED[n as usize]),
+        16..=231 => {
+            let i = n - 16;
+            [level(i / 36), level(i / 6 % 6), level(i % 6)]
+        }
+        _ => [8 + (n - 232) * 10; 3],
+    }
+}
+
+/// Colours of the cells of a frame. A highlight always wins, then the colour set here for the
+/// symbol, then the colour the day drew it with; anything else is black for `.` and spaces and
+/// light grey otherwise.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Palette {
+    symbols: HashMap<char, Rgb>,
+}
+
+impl Palette {
+    pub fn with(mut self, symbol: char, color: Rgb) -> Self {
+        self.symbols.insert(symbol, color);
+        self
+    }
+
+    pub fn pick(&self, cell: &Cell) -> Rgb {
+        if let Some(background) = cell.background {
+            return rgb(background);
+        }
+        if let Some(&color) = self.symbols.get(&cell.symbol) {
+            return color;
+        }
+        match (cell.color, cell.symbol) {
+            (Some(color), _) => rgb(color),
+            (None, '.' | ' ') => [0, 0, 0],
+            (None, _) => [192, 192, 192],
+        }
+    }
+}
+
+/// Comma separated `symbol=rrggbb` pairs, like `#=808080,O=ffcc00`
+impl FromStr for Palette {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        s.split(',').try_fold(Self::default(), |palette, entry| {
+            let invalid = || Error::invalid(format!("'{entry}' is not like '#=ffcc00'"));
+            let (symbol, hex) = entry.split_once('=').ok_or_else(invalid)?;
+            let mut chars = symbol.chars();
+            let (Some(symbol), None) = (chars.next(), chars.next()) else {
+                return Err(invalid());
+            };
+            if hex.len() != 6 || !hex.is_ascii() {
+                return Err(invalid());
+            }
+            let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).map_err(|_| invalid());
+            Ok(palette.with(symbol, [channel(0)?, channel(2)?, channel(4)?]))
+        })
+    }
+}
+
+/// Saves the frames it gets as `frame_00001.png`, `frame_00002.png`, ... in a directory,
+/// numbered by their position among all the frames even if only some of them are saved
+pub struct PngWriter {
+    dir: PathBuf,
+    palette: Palette,
+    scale: usize,
+    /// Frame numbers to save, all of them if `None`
+    frames: Option<Vec<RangeInclusive<usize>>>,
+    count: usize,
+    /// The first thing that went wrong, the frames after it aren't saved
+    error: Option<Error>,
+}
+
+impl PngWriter {
+    /// Every frame, a 4x4 square for each cell, in the default palette
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            palette: Palette::default(),
+            scale: 4,
+            frames: None,
+            count: 0,
+            error: None,
+        }
+    }
+
+    pub fn palette(mut self, palette: Palette) -> Self {
+        self.palette = palette;
+        self
+    }
+
+    pub fn scale(mut self, scale: usize) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// Only saves the frames whose 1-based number is in one of `frames`
+    pub fn frames(mut self, frames: Vec<RangeInclusive<usize>>) -> Self {
+        self.frames = Some(frames);
+        self
+    }
+
+    fn write(&self, frame: &Frame) -> Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        let image = Image::from_frame(frame, &self.palette, self.scale);
+        let path = self.dir.join(format!("frame_{:05}.png", self.count));
+        fs::write(path, image.encode())?;
+        Ok(())
+    }
+}
+
+impl Observer for PngWriter {
+    fn frame(&mut self, frame: &Frame) {
+        self.count += 1;
+        let wanted = self
+            .frames
+            .as_ref()
+            .is_none_or(|frames| frames.iter().any(|range| range.contains(&self.count)));
+        if wanted && self.error.is_none() {
+            self.error = self.write(frame).err();
+        }
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        self.error.take().map_or(Ok(()), Err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::coord::Coord;
+    use crate::util::grid::Grid;
+
+    #[test]
+    fn encoding() {
+        // The check value of CRC-32
+        assert_eq!(crc(b"123456789"), 0xcbf4_3926);
+
+        let mut image = Image::new(2, 1, [0; 3]);
+        image.set(1, 0, [255, 0, 0]);
+        let png = image.encode();
+        assert_eq!(png[..8], SIGNATURE);
+        assert_eq!(&png[12..16], b"IHDR");
+        assert_eq!(png[16..24], [0, 0, 0, 2, 0, 0, 0, 1]);
+        assert_eq!(&png[png.len() - 8..png.len() - 4], b"IEND");
+
+        let idat = &png[33..];
+        let length = u32::from_be_bytes(idat[..4].try_into().unwrap()) as usize;
+        let zlib = &idat[8..8 + length];
+        #[cfg(feature = "miniz_oxide")]
+        let raw = miniz_oxide::inflate::decompress_to_vec_zlib(zlib).unwrap();
+        // A single stored block, between the header and the checksum
+        #[cfg(not(feature = "miniz_oxide"))]
+        let raw = {
+            assert_eq!(zlib[..7], [0x78, 0x01, 1, 7, 0, 0xf8, 0xff]);
+            assert_eq!(zlib[zlib.len() - 4..], [0x03, 0x04, 0x01, 0x00]);
+            zlib[7..zlib.len() - 4].to_vec()
+        };
+        assert_eq!(raw, [0, 0, 0, 0, 255, 0, 0]);
+    }
+
+    #[test]
+    fn frames() {
+        let grid = Grid::parse("#.\n.O\n", Some).unwrap();
+        let frame = Frame::new(&grid, |&c| c)
+            .color('O', Color::Yellow)
+            .highlight([Coord::new(1, 0)], Color::Green);
+        let palette: Palette = "#=808080".parse().unwrap();
+        let image = Image::from_frame(&frame, &palette, 2);
+
+        assert_eq!((image.width(), image.height()), (4, 4));
+        assert_eq!(image.get(1, 1), Some([128, 128, 128]));
+        assert_eq!(image.get(2, 0), Some([0, 0, 0]));
+        assert_eq!(image.get(0, 3), Some(rgb(Color::Green)));
+        assert_eq!(image.get(3, 3), Some(rgb(Color::Yellow)));
+        assert_eq!(image.get(4, 0), None);
+
+        assert_eq!(ansi(196), [255, 0, 0]);
+        assert!("#=80808".parse::<Palette>().is_err());
+        assert!("##=808080".parse::<Palette>().is_err());
+    }
+}
@@ -0,0 +1,54 @@
+//! How far the days that read their input a line at a time have got, and a way to show it
+
+use std::{
+    io::{self, Write},
+    time::{Duration, Instant},
+};
+
+use crate::util::hook::Observer;
+
+/// Sent through `hook::progress` every so often while the input is read, and once at its end
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Progress {
+    pub lines: usize,
+    pub bytes: u64,
+    pub done: bool,
+}
+
+impl Progress {
+    /// Lines between two reports, so that the observers aren't bothered for every one of them
+    pub const EVERY: usize = 1 << 16;
+}
+
+/// Shows the progress on a single line of stderr, rewritten at most a few times a second
+pub struct Reporter {
+    interval: Duration,
+    last: Option<Instant>,
+}
+
+impl Reporter {
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            last: None,
+        }
+    }
+}
+
+impl Observer for Reporter {
+    fn progress(&mut self, progress: &Progress) {
+        let now = Instant::now();
+        if !progress.done && self.last.is_some_and(|last| now - last < self.interval) {
+            return;
+        }
+        self.last = Some(now);
+        let mib = progress.bytes as f64 / (1 << 20) as f64;
+        let end = if progress.done { "\n" } else { "" };
+        // Nowhere to complain about stderr itself
+        let _ = write!(
+            io::stderr(),
+            "\r{} lines, {mib:.1} MiB read{end}",
+            progress.lines
+        );
+    }
+}
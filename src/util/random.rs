@@ -0,0 +1,142 @@
+//! Randomness for generating puzzle inputs, reproducible from a seed
+
+use std::ops::RangeInclusive;
+
+use crate::util::coord::Coord;
+use crate::util::grid::Grid;
+
+/// xorshift64*, small and fast. Good enough for test inputs, not for anything that has to be
+/// unpredictable.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    /// The same seed always gives the same numbers
+    pub fn new(seed: u64) -> Self {
+        // One round of splitmix64, so that small seeds don't start from a mostly zero state
+        // (and zero, which xorshift can't leave, isn't possible)
+        let mut z = seed.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        Self {
+            state: (z ^ (z >> 31)).max(1),
+        }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    /// Somewhere in `0..n`, `n` can't be 0
+    pub fn below(&mut self, n: usize) -> usize {
+        assert!(n > 0, "nothing is below 0");
+        ((self.next_u64() as u128 * n as u128) >> 64) as usize
+    }
+
+    pub fn range(&mut self, range: RangeInclusive<i64>) -> i64 {
+        let (start, end) = range.into_inner();
+        let span = end.abs_diff(start) + 1;
+        start.wrapping_add(((self.next_u64() as u128 * span as u128) >> 64) as i64)
+    }
+
+    /// True with the probability `p`
+    pub fn chance(&mut self, p: f64) -> bool {
+        ((self.next_u64() >> 11) as f64 / (1u64 << 53) as f64) < p
+    }
+
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+}
+
+/// A maze of `rows` by `cols` (made odd by adding one if needed) with walls all around it and
+/// exactly one way between any two floor tiles, `true` being the floor. The floor is on the
+/// tiles with odd rows and columns and some of the walls between them.
+pub fn maze(rng: &mut Rng, rows: usize, cols: usize) -> Grid<bool> {
+    let (rows, cols) = (rows.max(3) | 1, cols.max(3) | 1);
+    let mut grid = Grid::new(rows, cols, false);
+    let start = Coord::new(1, 1);
+    grid[start] = true;
+    let mut stack = vec![start];
+    while let Some(&current) = stack.last() {
+        let unvisited: Vec<Coord> = current
+            .neighbors()
+            .into_iter()
+            .map(|next| next + (next - current))
+            .filter(|&next| {
+                next.x > 0
+                    && next.y > 0
+                    && grid.get(next).is_some_and(|floor| !floor)
+                    && next.x < rows as isize - 1
+                    && next.y < cols as isize - 1
+            })
+            .collect();
+        if unvisited.is_empty() {
+            stack.pop();
+            continue;
+        }
+        let next = *rng.pick(&unvisited);
+        let wall = Coord::new((current.x + next.x) / 2, (current.y + next.y) / 2);
+        grid[wall] = true;
+        grid[next] = true;
+        stack.push(next);
+    }
+    grid
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::search;
+
+    #[test]
+    fn reproducible() {
+        let (mut a, mut b) = (Rng::new(7), Rng::new(7));
+        let numbers: Vec<u64> = (0..5).map(|_| a.next_u64()).collect();
+        assert_eq!(numbers, (0..5).map(|_| b.next_u64()).collect::<Vec<_>>());
+        assert_ne!(Rng::new(8).next_u64(), numbers[0]);
+
+        let mut rng = Rng::new(0);
+        for _ in 0..1000 {
+            assert!(rng.below(6) < 6);
+            assert!((-3..=3).contains(&rng.range(-3..=3)));
+        }
+        let mut items = [1, 2, 3, 4, 5];
+        rng.shuffle(&mut items);
+        items.sort();
+        assert_eq!(items, [1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn perfect_maze() {
+        let grid = maze(&mut Rng::new(1), 10, 15);
+        assert_eq!((grid.rows(), grid.cols()), (11, 15));
+        assert!(grid.row(0).chain(grid.column(0)).all(|floor| !floor));
+
+        // A tree: one edge less than tiles, and everything reachable
+        let floor: Vec<Coord> = grid.positions(&true).collect();
+        let edges: usize = floor
+            .iter()
+            .map(|&c| grid.neighbors(c).filter(|&n| grid[n]).count())
+            .sum();
+        assert_eq!(edges / 2, floor.len() - 1);
+        let corner = Coord::new(9, 13);
+        let path = search::bfs(
+            Coord::new(1, 1),
+            |&c| grid.neighbors(c).filter(|&n| grid[n]).collect::<Vec<_>>(),
+            |&c| c == corner,
+        );
+        assert!(path.is_some());
+    }
+}
@@ -0,0 +1,250 @@
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, HashMap, HashSet, VecDeque},
+    hash::Hash,
+    ops::Add,
+};
+
+/// Node waiting in the heap, the cheapest one comes out first
+struct State<N, C> {
+    cost: C,
+    node: N,
+}
+
+impl<N, C: Ord> PartialEq for State<N, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cost == other.cost
+    }
+}
+
+impl<N, C: Ord> Eq for State<N, C> {}
+
+impl<N, C: Ord> PartialOrd for State<N, C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<N, C: Ord> Ord for State<N, C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // BinaryHeap is a max-heap
+        other.cost.cmp(&self.cost)
+    }
+}
+
+/// Walks `came_from` back from `end`, the result starts with the node that has no predecessor
+fn reconstruct<N: Clone + Eq + Hash>(came_from: &HashMap<N, N>, end: N) -> Vec<N> {
+    let mut path = vec![end];
+    while let Some(previous) = came_from.get(path.last().unwrap()) {
+        path.push(previous.clone());
+    }
+    path.reverse();
+    path
+}
+
+/// Breadth-first search for the path with the fewest steps from `start` to a node accepted by `goal`
+pub fn bfs<N, I>(
+    start: N,
+    mut neighbors: impl FnMut(&N) -> I,
+    mut goal: impl FnMut(&N) -> bool,
+) -> Option<Vec<N>>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut came_from = HashMap::new();
+    let mut seen = HashSet::from([start.clone()]);
+    let mut queue = VecDeque::from([start]);
+    while let Some(node) = queue.pop_front() {
+        if goal(&node) {
+            return Some(reconstruct(&came_from, node));
+        }
+        for next in neighbors(&node) {
+            if seen.insert(next.clone()) {
+                came_from.insert(next.clone(), node.clone());
+                queue.push_back(next);
+            }
+        }
+    }
+    None
+}
+
+/// Everything `dijkstra` found out about the nodes reachable from its start
+pub struct Paths<N, C> {
+    start: N,
+    costs: HashMap<N, C>,
+    /// Every node a cheapest path can arrive from, not just the first one found
+    predecessors: HashMap<N, Vec<N>>,
+}
+
+impl<N: Clone + Eq + Hash, C: Copy> Paths<N, C> {
+    /// Cost of the cheapest path to `node`, `None` if it can't be reached
+    pub fn cost(&self, node: &N) -> Option<C> {
+        self.costs.get(node).copied()
+    }
+
+    /// One of the cheapest paths from the start to `end`
+    pub fn path(&self, end: &N) -> Option<Vec<N>> {
+        self.costs.get(end)?;
+        let mut path = vec![end.clone()];
+        let mut node = end;
+        while *node != self.start {
+            node = &self.predecessors[node][0];
+            path.push(node.clone());
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    /// Every node on any of the cheapest paths to any of `ends`, the ends included
+    pub fn on_cheapest_paths(&self, ends: impl IntoIterator<Item = N>) -> HashSet<N> {
+        let mut stack: Vec<N> = ends
+            .into_iter()
+            .filter(|end| self.costs.contains_key(end))
+            .collect();
+        let mut nodes: HashSet<N> = stack.iter().cloned().collect();
+        while let Some(node) = stack.pop() {
+            for previous in self.predecessors.get(&node).into_iter().flatten() {
+                if nodes.insert(previous.clone()) {
+                    stack.push(previous.clone());
+                }
+            }
+        }
+        nodes
+    }
+}
+
+/// Dijkstra's algorithm from `start` to everything reachable. `neighbors` returns the next nodes
+/// with the cost of getting there.
+pub fn dijkstra<N, C, I>(start: N, mut neighbors: impl FnMut(&N) -> I) -> Paths<N, C>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Add<Output = C> + Default,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let mut costs = HashMap::from([(start.clone(), C::default())]);
+    let mut predecessors: HashMap<N, Vec<N>> = HashMap::new();
+    let mut heap = BinaryHeap::from([State {
+        cost: C::default(),
+        node: start.clone(),
+    }]);
+
+    while let Some(State { cost, node }) = heap.pop() {
+        if costs.get(&node).is_some_and(|&best| cost > best) {
+            // Already found a better one
+            continue;
+        }
+        for (next, step) in neighbors(&node) {
+            let next_cost = cost + step;
+            match costs.get(&next) {
+                Some(&best) if next_cost > best => {}
+                Some(&best) if next_cost == best => {
+                    predecessors.entry(next).or_default().push(node.clone());
+                }
+                _ => {
+                    costs.insert(next.clone(), next_cost);
+                    predecessors.insert(next.clone(), vec![node.clone()]);
+                    heap.push(State {
+                        cost: next_cost,
+                        node: next,
+                    });
+                }
+            }
+        }
+    }
+
+    Paths {
+        start,
+        costs,
+        predecessors,
+    }
+}
+
+/// A* from `start` to the first node accepted by `goal`, returning the path and its cost.
+/// `heuristic` must never overestimate the remaining cost, or the path might not be the cheapest.
+pub fn astar<N, C, I>(
+    start: N,
+    mut neighbors: impl FnMut(&N) -> I,
+    mut heuristic: impl FnMut(&N) -> C,
+    mut goal: impl FnMut(&N) -> bool,
+) -> Option<(Vec<N>, C)>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Add<Output = C> + Default,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let mut costs = HashMap::from([(start.clone(), C::default())]);
+    let mut came_from = HashMap::new();
+    let mut heap = BinaryHeap::from([State {
+        cost: heuristic(&start),
+        node: start,
+    }]);
+
+    while let Some(State { node, .. }) = heap.pop() {
+        let cost = costs[&node];
+        if goal(&node) {
+            return Some((reconstruct(&came_from, node), cost));
+        }
+        for (next, step) in neighbors(&node) {
+            let next_cost = cost + step;
+            if costs.get(&next).is_none_or(|&best| next_cost < best) {
+                costs.insert(next.clone(), next_cost);
+                came_from.insert(next.clone(), node.clone());
+                heap.push(State {
+                    cost: next_cost + heuristic(&next),
+                    node: next,
+                });
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::coord::Coord;
+    use crate::util::grid::Grid;
+
+    const MAZE: &str = "\
+        ...#\n\
+        .#..\n\
+        ...#\n";
+
+    fn open(grid: &Grid<char>, coord: Coord) -> Vec<Coord> {
+        grid.neighbors(coord).filter(|&n| grid[n] == '.').collect()
+    }
+
+    #[test]
+    fn fewest_steps() {
+        let grid = Grid::parse(MAZE, Some).unwrap();
+        let end = Coord::new(1, 3);
+        let path = bfs(Coord::new(0, 0), |&c| open(&grid, c), |&c| c == end).unwrap();
+        assert_eq!(path.len(), 5);
+        assert_eq!((path[0], path[4]), (Coord::new(0, 0), end));
+
+        let (path, cost) = astar(
+            Coord::new(0, 0),
+            |&c| open(&grid, c).into_iter().map(|n| (n, 1)),
+            |c| c.distance(&end),
+            |&c| c == end,
+        )
+        .unwrap();
+        assert_eq!((path.len(), cost), (5, 4));
+        assert_eq!(bfs(Coord::new(0, 0), |&c| open(&grid, c), |_| false), None);
+    }
+
+    #[test]
+    fn all_cheapest_paths() {
+        let grid = Grid::parse(MAZE, Some).unwrap();
+        let paths = dijkstra(Coord::new(0, 0), |&c| {
+            open(&grid, c).into_iter().map(|n| (n, 1))
+        });
+        let end = Coord::new(2, 2);
+        assert_eq!(paths.cost(&end), Some(4));
+        assert_eq!(paths.path(&end).unwrap().len(), 5);
+        // Around the wall in the middle either way
+        assert_eq!(paths.on_cheapest_paths([end]).len(), 8);
+        assert_eq!(paths.cost(&Coord::new(0, 3)), None);
+    }
+}
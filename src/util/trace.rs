@@ -0,0 +1,84 @@
+//! What the solvers found out on the way to their answers, for when an answer is wrong and the
+//! numbers alone don't say why. The days send facts through `hook::fact`, `--explain` prints them.
+
+use std::{
+    fmt::{self, Display},
+    io::{self, Write},
+};
+
+use crate::util::hook::Observer;
+
+/// Something a solver found out, e.g. that a report is safe: what it is about and a few named
+/// values
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fact {
+    what: &'static str,
+    values: Vec<(&'static str, String)>,
+}
+
+impl Fact {
+    pub fn new(what: &'static str) -> Self {
+        Self {
+            what,
+            values: vec![],
+        }
+    }
+
+    pub fn with(mut self, name: &'static str, value: impl Display) -> Self {
+        self.values.push((name, value.to_string()));
+        self
+    }
+
+    pub fn what(&self) -> &str {
+        self.what
+    }
+
+    /// The value called `name`, if there is one
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.values
+            .iter()
+            .find(|(key, _)| *key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn values(&self) -> &[(&'static str, String)] {
+        &self.values
+    }
+}
+
+impl Display for Fact {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.what)?;
+        for (i, (name, value)) in self.values.iter().enumerate() {
+            let separator = if i == 0 { ":" } else { "," };
+            write!(f, "{separator} {name}={value}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Prints every fact on its own line of stdout, before the answers they lead to
+pub struct Explainer;
+
+impl Observer for Explainer {
+    fn fact(&mut self, fact: &Fact) {
+        // Nowhere to complain about stdout itself
+        let _ = writeln!(io::stdout(), "  {fact}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display() {
+        let fact = Fact::new("report")
+            .with("levels", "7 6 4 2 1")
+            .with("safe", true);
+        assert_eq!(fact.to_string(), "report: levels=7 6 4 2 1, safe=true");
+        assert_eq!(fact.get("safe"), Some("true"));
+        assert_eq!(fact.get("line"), None);
+        assert_eq!(Fact::new("done").to_string(), "done");
+    }
+}
@@ -0,0 +1,168 @@
+use std::{
+    fmt::{self, Display},
+    io::{self, Write},
+    thread,
+    time::Duration,
+};
+
+pub use colored::Color;
+use colored::Colorize;
+
+use crate::util::coord::Coord;
+use crate::util::grid::Grid;
+use crate::util::hook::Observer;
+
+/// One character of a frame with its colours, `None` being the terminal's default
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cell {
+    pub symbol: char,
+    pub color: Option<Color>,
+    pub background: Option<Color>,
+}
+
+/// A picture of a map, built in layers: the symbols first, then colours for some of them,
+/// then highlights of whatever the puzzle is about (a path, a robot, ...) on top
+#[derive(Debug, Clone, PartialEq)]
+pub struct Frame {
+    title: String,
+    cells: Grid<Cell>,
+}
+
+impl Frame {
+    /// `render` picks the character of each tile of the map
+    pub fn new<T>(grid: &Grid<T>, mut render: impl FnMut(&T) -> char) -> Self {
+        Self {
+            title: String::new(),
+            cells: grid.map(|tile| Cell {
+                symbol: render(tile),
+                color: None,
+                background: None,
+            }),
+        }
+    }
+
+    /// A line above the map, e.g. the step of the animation
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = title.into();
+        self
+    }
+
+    /// Draws every `symbol` in `color`
+    pub fn color(mut self, symbol: char, color: Color) -> Self {
+        for (_, cell) in self.cells.iter_mut() {
+            if cell.symbol == symbol {
+                cell.color = Some(color);
+            }
+        }
+        self
+    }
+
+    /// Puts a `color` background behind the given cells, the ones off the map are ignored
+    pub fn highlight(mut self, coords: impl IntoIterator<Item = Coord>, color: Color) -> Self {
+        for coord in coords {
+            if let Some(cell) = self.cells.get_mut(coord) {
+                cell.background = Some(color);
+            }
+        }
+        self
+    }
+
+    /// Replaces a single cell, e.g. to show where the robot is
+    pub fn mark(mut self, coord: Coord, symbol: char, color: Color) -> Self {
+        if let Some(cell) = self.cells.get_mut(coord) {
+            cell.symbol = symbol;
+            cell.color = Some(color);
+        }
+        self
+    }
+
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    pub fn cells(&self) -> &Grid<Cell> {
+        &self.cells
+    }
+}
+
+/// The title and the map with ANSI colours, unless they are turned off (e.g. by `NO_COLOR`)
+impl Display for Frame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.title.is_empty() {
+            writeln!(f, "{}", self.title.bold())?;
+        }
+        for row in 0..self.cells.rows() {
+            for cell in self.cells.row(row) {
+                let mut symbol = cell.symbol.to_string().normal();
+                if let Some(color) = cell.color {
+                    symbol = symbol.color(color);
+                }
+                if let Some(background) = cell.background {
+                    symbol = symbol.on_color(background);
+                }
+                write!(f, "{symbol}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Shows the frames as an animation in the terminal, redrawing the screen for each of them
+pub struct Terminal {
+    delay: Duration,
+}
+
+impl Terminal {
+    /// `delay` is how long each frame stays on the screen
+    pub fn new(delay: Duration) -> Self {
+        Self { delay }
+    }
+}
+
+impl Observer for Terminal {
+    fn frame(&mut self, frame: &Frame) {
+        let mut out = io::stdout().lock();
+        // Clear the screen and go to its top left corner
+        let _ = write!(out, "\x1b[2J\x1b[H{frame}");
+        let _ = out.flush();
+        thread::sleep(self.delay);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn layers() {
+        let grid = Grid::parse("#..\n..#\n", Some).unwrap();
+        let frame = Frame::new(&grid, |&c| c)
+            .color('#', Color::Red)
+            .highlight(
+                [Coord::new(0, 1), Coord::new(1, 1), Coord::new(5, 5)],
+                Color::Green,
+            )
+            .mark(Coord::new(1, 1), '@', Color::Yellow);
+
+        let cells = frame.cells();
+        assert_eq!(
+            cells[Coord::new(0, 0)],
+            Cell {
+                symbol: '#',
+                color: Some(Color::Red),
+                background: None
+            }
+        );
+        assert_eq!(cells[Coord::new(0, 1)].background, Some(Color::Green));
+        assert_eq!(
+            cells[Coord::new(1, 1)],
+            Cell {
+                symbol: '@',
+                color: Some(Color::Yellow),
+                background: Some(Color::Green)
+            }
+        );
+        assert_eq!(cells[Coord::new(1, 0)].color, None);
+    }
+}
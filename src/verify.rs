@@ -0,0 +1,120 @@
+use std::{fs, path::PathBuf};
+
+use aoc2024::answers::Answers;
+use aoc2024::registry;
+
+use crate::cli::{Selection, VerifyArgs};
+use crate::runner::{self, Outcome};
+
+pub fn verify(args: &VerifyArgs) -> Result<(), String> {
+    let days = match args.days {
+        Selection::All => registry::days(),
+        Selection::Day(n) => {
+            vec![registry::get(n).ok_or(format!("Day {n} is not solved yet or not built in"))?]
+        }
+    };
+    let parts: Vec<u8> = [1, 2]
+        .into_iter()
+        .filter(|&part| args.runs_part(part))
+        .collect();
+
+    let mut failures = 0;
+    let mut unknown = 0;
+    let mut inputs = vec![];
+    let mut recorded = vec![];
+    for day in days {
+        let n = day.number;
+        let input = PathBuf::from(day.input_path());
+        let loaded = Answers::load(&input).and_then(|answers| {
+            let data = fs::read_to_string(&input)?;
+            Ok((answers, data))
+        });
+        match loaded {
+            Ok((answers, data)) => {
+                inputs.push((day, data, answers.params.clone()));
+                recorded.push((input, answers));
+            }
+            Err(e) => {
+                println!("Day {n}: FAILED, {e}");
+                failures += 1;
+            }
+        }
+    }
+
+    let options = runner::Options {
+        parts,
+        jobs: args.jobs,
+        timeout: args.timeout,
+    };
+    let mut recorded = recorded.into_iter();
+    let mut save_errors = vec![];
+    runner::run(inputs, &options, |report| {
+        let n = report.day;
+        let (input, mut answers) = recorded.next().unwrap();
+        match report.parse {
+            Outcome::Done(Ok(()), _) => {}
+            Outcome::Done(Err(e), _) => {
+                println!("Day {n}: FAILED, {e}");
+                failures += 1;
+            }
+            Outcome::Timeout => {
+                println!(
+                    "Day {n}: TIMEOUT after {:?}",
+                    args.timeout.unwrap_or_default()
+                );
+                failures += 1;
+            }
+        }
+
+        for (part, outcome) in report.parts {
+            match (outcome, answers.get(part)) {
+                (Outcome::Done(Ok(answer), _), _) if args.record => {
+                    println!("Day {n}/{part}: recorded {answer}");
+                    answers.set(part, answer);
+                }
+                (Outcome::Done(Ok(answer), _), Some(expected)) if answer == expected => {
+                    println!("Day {n}/{part}: ok ({answer})");
+                }
+                (Outcome::Done(Ok(answer), _), Some(expected)) => {
+                    println!("Day {n}/{part}: MISMATCH, expected {expected}, got {answer}");
+                    failures += 1;
+                }
+                (Outcome::Done(Ok(answer), _), None) => {
+                    println!("Day {n}/{part}: no recorded answer, got {answer}");
+                    unknown += 1;
+                }
+                (Outcome::Done(Err(e), _), _) => {
+                    println!("Day {n}/{part}: FAILED, {e}");
+                    failures += 1;
+                }
+                (Outcome::Timeout, _) => {
+                    println!(
+                        "Day {n}/{part}: TIMEOUT after {:?}",
+                        args.timeout.unwrap_or_default()
+                    );
+                    failures += 1;
+                }
+            }
+        }
+
+        if args.record {
+            if let Err(e) = answers.save(&input) {
+                save_errors.push(format!(
+                    "Can't write {}: {e}",
+                    Answers::path_for(&input).display()
+                ));
+            }
+        }
+    });
+
+    if !save_errors.is_empty() {
+        return Err(save_errors.join("\n"));
+    }
+    if unknown > 0 {
+        println!("\n{unknown} answer(s) not recorded yet, use --record to save them");
+    }
+    match failures {
+        0 => Ok(()),
+        n => Err(format!("{n} check(s) failed")),
+    }
+}
@@ -0,0 +1,50 @@
+//! The days that take shortcuts against their slow reference solvers, on small generated
+//! inputs. Only built by `cargo test --features reference`.
+
+use aoc2024::solution::Solution;
+use aoc2024::util::random::Rng;
+use aoc2024::*;
+
+/// Inputs tried for every day, with the seeds 0 to `SEEDS - 1`
+const SEEDS: u64 = 20;
+
+/// A test for each `day: size` comparing both parts of `dayN::Puzzle` with
+/// `dayN::reference`, errors included
+macro_rules! differential {
+    ($($day:ident: $size:expr),* $(,)?) => {$(
+        #[test]
+        fn $day() {
+            for seed in 0..SEEDS {
+                let text = $day::Puzzle::generate(&mut Rng::new(seed), $size);
+                let input = $day::Puzzle::parse(&text).unwrap();
+                let parts = [
+                    (
+                        $day::Puzzle::part_1(&input).map(|answer| answer.to_string()),
+                        $day::reference::part_1(&input).map(|answer| answer.to_string()),
+                    ),
+                    (
+                        $day::Puzzle::part_2(&input).map(|answer| answer.to_string()),
+                        $day::reference::part_2(&input).map(|answer| answer.to_string()),
+                    ),
+                ];
+                for (part, (fast, slow)) in parts.into_iter().enumerate() {
+                    assert_eq!(
+                        fast.ok(),
+                        slow.ok(),
+                        "{}, part {}, seed {seed}:\n{text}",
+                        stringify!($day),
+                        part + 1
+                    );
+                }
+            }
+        }
+    )*};
+}
+
+differential! {
+    day13: 20,
+    // Only the example's short program, the real ones have too many A to try for the reference
+    day17: 6,
+    day21: 5,
+    day24: 10,
+}
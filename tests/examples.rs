@@ -0,0 +1,176 @@
+//! Every example next to the days: `src/dayN/testK.txt` with its answers in
+//! `src/dayN/testK.answers.toml`, in the same format as the answers of the real inputs, with the
+//! numbers its puzzle text gives if they're not the real ones. Adding an example is dropping in
+//! both files, an example without answers is reported as ignored.
+//!
+//! `cargo test --test examples -- day12/` only checks the examples of day 12, any other argument
+//! picks the cases whose name contains it.
+
+use std::{
+    env, fs,
+    panic::{self, AssertUnwindSafe},
+    path::{Path, PathBuf},
+    process,
+};
+
+use aoc2024::answers::Answers;
+use aoc2024::registry;
+use aoc2024::solution::{Day, Params};
+
+/// A part of an example with a known answer
+struct Case {
+    day: Day,
+    path: PathBuf,
+    part: u8,
+    params: Params,
+    expected: String,
+}
+
+impl Case {
+    fn name(&self) -> String {
+        format!(
+            "day{}/{} part {}",
+            self.day.number,
+            self.path.file_name().unwrap().to_string_lossy(),
+            self.part
+        )
+    }
+
+    /// The answer, or why there is none
+    fn solve(&self) -> Result<String, String> {
+        let data = fs::read_to_string(&self.path).map_err(|e| e.to_string())?;
+        let input = self
+            .day
+            .parse_with(&data, &self.params)
+            .map_err(|e| e.to_string())?;
+        let answer = match self.part {
+            1 => input.part_1(),
+            _ => input.part_2(),
+        };
+        answer.map_err(|e| e.to_string())
+    }
+}
+
+/// `testK.txt` files of a day by K, with the answers recorded for them if there are any
+fn examples(dir: &Path) -> Vec<(PathBuf, Option<Answers>)> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return vec![];
+    };
+    let mut examples: Vec<(u32, PathBuf)> = entries
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            let name = path.file_name()?.to_str()?;
+            let k = name
+                .strip_prefix("test")?
+                .strip_suffix(".txt")?
+                .parse()
+                .ok()?;
+            Some((k, path))
+        })
+        .collect();
+    examples.sort();
+    examples
+        .into_iter()
+        .map(|(_, path)| {
+            if !Answers::path_for(&path).exists() {
+                return (path, None);
+            }
+            let answers = Answers::load(&path).unwrap_or_else(|e| {
+                panic!("{}: {e}", Answers::path_for(&path).display());
+            });
+            (path, Some(answers))
+        })
+        .collect()
+}
+
+/// Every part of every example with an answer, and the names of the examples without any
+fn cases() -> (Vec<Case>, Vec<String>) {
+    let mut cases = vec![];
+    let mut unanswered = vec![];
+    for day in registry::days() {
+        let input = day.input_path();
+        for (path, answers) in examples(Path::new(&input).parent().unwrap()) {
+            let Some(answers) = answers else {
+                let name = path.file_name().unwrap().to_string_lossy();
+                unanswered.push(format!("day{}/{name}", day.number));
+                continue;
+            };
+            for part in [1, 2] {
+                if let Some(expected) = answers.get(part) {
+                    cases.push(Case {
+                        day,
+                        path: path.clone(),
+                        part,
+                        params: answers.params.clone(),
+                        expected: expected.to_string(),
+                    });
+                }
+            }
+        }
+    }
+    (cases, unanswered)
+}
+
+fn main() {
+    // Flags like --nocapture are for the standard harness, they don't mean anything here
+    let filters: Vec<String> = env::args()
+        .skip(1)
+        .filter(|arg| !arg.starts_with('-'))
+        .collect();
+    let picked = |name: &str| filters.is_empty() || filters.iter().any(|f| name.contains(f));
+    let (cases, unanswered) = cases();
+    let cases: Vec<Case> = cases
+        .into_iter()
+        .filter(|case| picked(&case.name()))
+        .collect();
+    let unanswered: Vec<String> = unanswered.into_iter().filter(|name| picked(name)).collect();
+
+    // A panicking day fails its case, not the whole run
+    panic::set_hook(Box::new(|_| {}));
+    println!("\nrunning {} tests", cases.len() + unanswered.len());
+    for name in &unanswered {
+        println!("test {name} ... ignored, no answers file");
+    }
+    let mut failures = vec![];
+    for case in &cases {
+        let result = panic::catch_unwind(AssertUnwindSafe(|| case.solve())).unwrap_or_else(|e| {
+            let message = e
+                .downcast_ref::<&str>()
+                .map(|s| s.to_string())
+                .or_else(|| e.downcast_ref::<String>().cloned());
+            Err(format!("panicked: {}", message.unwrap_or_default()))
+        });
+        let failure = match result {
+            Ok(answer) if answer == case.expected => None,
+            Ok(answer) => Some(format!("expected {}, got {answer}", case.expected)),
+            Err(e) => Some(format!("expected {}, got an error: {e}", case.expected)),
+        };
+        let status = if failure.is_some() { "FAILED" } else { "ok" };
+        println!("test {} ... {status}", case.name());
+        if let Some(failure) = failure {
+            failures.push(format!(
+                "day {}, part {}, {}: {failure}",
+                case.day.number,
+                case.part,
+                case.path.display()
+            ));
+        }
+    }
+
+    if !failures.is_empty() {
+        println!("\nfailures:");
+        for failure in &failures {
+            println!("    {failure}");
+        }
+    }
+    let result = if failures.is_empty() { "ok" } else { "FAILED" };
+    println!(
+        "\ntest result: {result}. {} passed; {} failed; {} ignored\n",
+        cases.len() - failures.len(),
+        failures.len(),
+        unanswered.len()
+    );
+    if !failures.is_empty() {
+        process::exit(101);
+    }
+}